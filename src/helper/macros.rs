@@ -1,6 +1,7 @@
 macro_rules! versioned_inner {
     ($name:ident, $field_type:ty, $error_from_string:ty, $s_from_string:ident, $version_from_string:ident, $inner_from_string:block) => {
         #[derive(PartialEq, Debug, Clone, Eq, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         pub struct $name($field_type);
 
         impl From<$field_type> for $name {
@@ -103,72 +104,123 @@ macro_rules! versioned_field {
 macro_rules! general_section_inner {
     ($(#[$outer:meta])*, $section_name:ident, $($(#[$inner:meta])*, $field:ident, $field_type:ty)*, $parse_error:ty, $spacing:expr, $default_version:ident, $default_field_name:ident) => {
         #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         $(#[$outer])*
         pub struct $section_name {
             $(
                 $(#[$inner])*
                 pub $field: Option<$field_type>,
             )*
+            /// Fields with keys that aren't known, which are kept as they are.
+            pub unknown_fields: Vec<crate::osu_file::types::UnknownField>,
         }
 
         impl $section_name {
             /// Creates a new instance, with all fields being `None`.
-            pub fn new() -> Self {
+            pub const fn new() -> Self {
                 $section_name {
-                    $($field: None),*
+                    $($field: None,)*
+                    unknown_fields: Vec::new(),
                 }
             }
 
             pub fn from_str(s: &str, version: crate::osu_file::types::Version) -> Result<Option<$section_name>, crate::osu_file::types::Error<$parse_error>> {
-                let mut section = $section_name::new();
+                Self::from_str_errors(s, version, &Default::default()).map(|(section, _)| section)
+            }
 
-                let (s, fields) = crate::parsers::get_colon_field_value_lines(s).unwrap();
+            /// Parses the section, collecting the errors instead of returning the first one if [`lenient`][crate::osu_file::ParseOptions::lenient] is set.
+            /// - Lines with errors are skipped when collected.
+            /// - Comment lines are skipped if [`skips_comments`][crate::osu_file::ParseOptions::skips_comments].
+            pub(crate) fn from_str_errors(s: &str, version: crate::osu_file::types::Version, options: &crate::osu_file::ParseOptions) -> crate::helper::ErrorsResult<$section_name, $parse_error> {
+                let lines = crate::helper::colon_lines(s, options.skips_comments(), options.lenient);
 
-                if !s.trim().is_empty() {
-                    // line count from fields
-                    let line_count = { fields.iter().map(|(_, _, _, ws)| ws.lines().count()).sum() };
+                Self::from_fields(lines, version, options.lenient)
+            }
 
-                    return Err(crate::osu_file::types::Error::new(<$parse_error>::InvalidColonSet, line_count));
-                }
+            /// Parses the section from its `key: value` lines, one field at a time.
+            pub(crate) fn from_fields<'a>(lines: impl IntoIterator<Item = crate::helper::ColonLine<'a>>, version: crate::osu_file::types::Version, lenient: bool) -> crate::helper::ErrorsResult<$section_name, $parse_error> {
+                let mut section = $section_name::new();
+                let mut errors = crate::helper::ErrorCollector::new(lenient);
 
-                let mut line_count = 0;
                 let mut parsed_fields = Vec::new();
+                let mut last_field = None;
+
+                for line in lines {
+                    let field = match line {
+                        crate::helper::ColonLine::Field(field) => field,
+                        crate::helper::ColonLine::Invalid(line_index) => {
+                            errors.collect::<()>(Err(crate::osu_file::types::Error::new(<$parse_error>::InvalidColonSet, line_index)))?;
+                            continue;
+                        }
+                    };
+                    let name = field.key;
 
-                for (name, _, value, ws_2) in fields {
                     if parsed_fields.contains(&name) {
-                        return Err(crate::osu_file::types::Error::new(ParseError::DuplicateField, line_count));
+                        errors.collect::<()>(Err(crate::osu_file::types::Error::new(ParseError::DuplicateField, field.line_index).with_columns(Some(field.key_columns))))?;
+                        continue;
                     }
 
                     match name {
                         $(
                             stringify!($field_type) => {
-                                section.$field = crate::osu_file::types::Error::new_from_result_into(<$field_type as crate::osu_file::types::VersionedFromStr>::from_str(value, version), line_count)?;
+                                let result = crate::osu_file::types::Error::new_from_result_into(<$field_type as crate::osu_file::types::VersionedFromStr>::from_str(field.value, version), field.line_index)
+                                    .map_err(|err| err.with_columns(Some(field.value_columns)));
+
+                                if let Some(value) = errors.collect(result)? {
+                                    section.$field = value;
+                                }
                             }
                         )*
-                        _ => return Err(crate::osu_file::types::Error::new(ParseError::InvalidKey, line_count)),
+                        _ => {
+                            section.unknown_fields.push(crate::osu_file::types::UnknownField {
+                                key: name.to_string(),
+                                value: field.value.to_string(),
+                                after: last_field.map(|field: &str| field.to_string()),
+                            });
+                        }
                     }
 
-                    line_count += ws_2.lines().count();
                     parsed_fields.push(name);
+                    last_field = Some(name);
                 }
 
-                Ok(Some(section))
+                Ok((Some(section), errors.into_errors()))
+            }
+
+            pub fn to_string(&self, version: crate::osu_file::types::Version) -> Option<String> {
+                crate::helper::write_versioned_to_string(self, version)
             }
 
-            pub fn to_string(&self, $default_version: crate::osu_file::types::Version) -> Option<String> {
-                let mut v = Vec::new();
+            /// Removes the fields that can't be written in the version.
+            /// - Returns the keys of the removed fields.
+            pub(crate) fn remove_unsupported_fields(&mut self, version: crate::osu_file::types::Version) -> Vec<&'static str> {
+                let mut removed = Vec::new();
 
                 $(
                     if let Some(value) = &self.$field {
-                        if let Some($default_field_name) = crate::osu_file::types::VersionedToString::to_string(value, $default_version) {
-                            let field_name = stringify!($field_type);
-
-                            v.push(format!("{field_name}:{}{}", $spacing, $default_field_name));
+                        if crate::osu_file::types::VersionedToString::to_string(value, version).is_none() {
+                            self.$field = None;
+                            removed.push(stringify!($field_type));
                         }
                     }
                 )*
 
-                Some(v.join("\n"))
+                removed
+            }
+
+            /// Keys of the fields that are set, including the unknown fields.
+            pub fn field_keys(&self) -> Vec<&str> {
+                let mut keys = Vec::new();
+
+                $(
+                    if self.$field.is_some() {
+                        keys.push(stringify!($field_type));
+                    }
+                )*
+
+                keys.extend(self.unknown_fields.iter().map(|field| field.key.as_str()));
+
+                keys
             }
         }
 
@@ -177,6 +229,56 @@ macro_rules! general_section_inner {
                 Self::new()
             }
         }
+
+        impl<'a> crate::osu_file::borrowed::FieldsRef<'a, $section_name> {
+            $(
+                #[doc = concat!("Value of the `", stringify!($field_type), "` field, parsed when it's asked for.")]
+                pub fn $field(&self) -> Option<crate::osu_file::borrowed::ValueRef<'a, $field_type>> {
+                    self.value_ref(stringify!($field_type))
+                }
+            )*
+
+            /// Parses the section, one field at a time.
+            pub fn into_owned(self) -> Result<$section_name, crate::osu_file::types::Error<$parse_error>> {
+                crate::osu_file::types::Error::processing_line($section_name::from_fields(self.lines, self.version, false), self.line_index)
+                    .map(|(section, _)| section.unwrap())
+            }
+        }
+
+        impl crate::helper::VersionedWrite for $section_name {
+            fn write_versioned<W: std::fmt::Write>(&self, f: &mut W, $default_version: crate::osu_file::types::Version) -> Option<std::fmt::Result> {
+                let mut values = Vec::new();
+
+                $(
+                    if let Some(value) = &self.$field {
+                        if let Some(value) = crate::osu_file::types::VersionedToString::to_string(value, $default_version) {
+                            values.push((stringify!($field_type), value));
+                        }
+                    }
+                )*
+
+                let known = values.iter().map(|(field_name, _)| *field_name).collect::<Vec<_>>();
+                let unknown = self.unknown_fields.iter().map(|field| (field.key.as_str(), field.after.as_deref())).collect::<Vec<_>>();
+
+                let write_fields = |f: &mut W| {
+                    for (i, entry) in crate::helper::order_unknown_after(&known, &unknown).into_iter().enumerate() {
+                        let (key, $default_field_name) = entry.either(
+                            |i| (values[i].0, values[i].1.as_str()),
+                            |i| (unknown[i].0, self.unknown_fields[i].value.as_str()),
+                        );
+
+                        if i > 0 {
+                            std::fmt::Write::write_char(f, '\n')?;
+                        }
+                        std::fmt::Write::write_fmt(f, format_args!("{key}:{}{}", $spacing, $default_field_name))?;
+                    }
+
+                    Ok(())
+                };
+
+                Some(write_fields(f))
+            }
+        }
     };
 }
 
@@ -1,20 +1,34 @@
 pub mod macros;
-pub mod trait_ext;
 
+use std::borrow::Cow;
+use std::fmt::{self, Write};
+use std::io;
 use std::num::ParseIntError;
+use std::ops::Range;
 
+use either::Either;
 use thiserror::Error;
 
-use crate::osu_file::{Version, VersionedToString};
+use crate::osu_file::{DecodeError, Error, Version};
+use crate::parsers::get_colon_field_value_lines;
 
-pub fn pipe_vec_to_string<T>(vec: &[T], version: Version) -> String
+/// Writes items separated by `|`.
+pub fn write_pipe_separated<'a, T, W, I>(f: &mut W, items: I, version: Version) -> fmt::Result
 where
-    T: VersionedToString,
+    T: VersionedWrite + 'a,
+    W: Write,
+    I: IntoIterator<Item = &'a T>,
 {
-    vec.iter()
-        .map(|s| s.to_string(version).unwrap())
-        .collect::<Vec<_>>()
-        .join("|")
+    for (i, item) in items.into_iter().enumerate() {
+        if i > 0 {
+            f.write_char('|')?;
+        }
+        if let Some(res) = item.write_versioned(f, version) {
+            res?;
+        }
+    }
+
+    Ok(())
 }
 
 pub fn nth_bit_state_i64(value: i64, nth_bit: u8) -> bool {
@@ -38,3 +52,404 @@ pub enum ParseZeroOneBoolError {
     #[error("Error parsing value as `true` or `false`, expected value of 0 or 1")]
     InvalidValue,
 }
+
+/// Decodes the bytes of an osu! file into a `String`.
+/// - Detects and strips the UTF-8 BOM.
+/// - Decodes UTF-16 LE / BE, with or without a BOM.
+/// - Without a BOM, UTF-16 is detected from the first character being ASCII, which always holds for valid files.
+pub fn decode_file_bytes(bytes: Vec<u8>) -> Result<String, DecodeError> {
+    enum Encoding {
+        Utf8,
+        Utf16Le,
+        Utf16Be,
+    }
+
+    let (encoding, bom_len) = match bytes.as_slice() {
+        [0xEF, 0xBB, 0xBF, ..] => (Encoding::Utf8, 3),
+        [0xFF, 0xFE, ..] => (Encoding::Utf16Le, 2),
+        [0xFE, 0xFF, ..] => (Encoding::Utf16Be, 2),
+        [first, 0, ..] if *first != 0 => (Encoding::Utf16Le, 0),
+        [0, second, ..] if *second != 0 => (Encoding::Utf16Be, 0),
+        _ => (Encoding::Utf8, 0),
+    };
+
+    let s = match encoding {
+        Encoding::Utf8 => {
+            let mut bytes = bytes;
+            bytes.drain(..bom_len);
+            String::from_utf8(bytes)?
+        }
+        Encoding::Utf16Le | Encoding::Utf16Be => {
+            let bytes = &bytes[bom_len..];
+
+            if bytes.len() % 2 != 0 {
+                return Err(DecodeError::OddUtf16Length);
+            }
+
+            let units = bytes
+                .chunks_exact(2)
+                .map(|unit| match encoding {
+                    Encoding::Utf16Le => u16::from_le_bytes([unit[0], unit[1]]),
+                    _ => u16::from_be_bytes([unit[0], unit[1]]),
+                })
+                .collect::<Vec<_>>();
+
+            String::from_utf16(&units)?
+        }
+    };
+
+    // a BOM could still be left if the file was encoded twice
+    Ok(match s.strip_prefix('\u{feff}') {
+        Some(stripped) => stripped.to_string(),
+        None => s,
+    })
+}
+
+/// Checks if the line is a comment, which starts with `//`.
+pub fn is_comment_line(line: &str) -> bool {
+    line.trim_start().starts_with("//")
+}
+
+/// Returns the string after the first line.
+pub fn skip_line(s: &str) -> &str {
+    s.split_once('\n').map_or("", |(_, rest)| rest)
+}
+
+/// Lines of a section that aren't blank, with their index in the section.
+/// - Comment lines are skipped too if `skips_comments` is set.
+pub fn item_lines(s: &str, skips_comments: bool) -> impl Iterator<Item = (usize, &str)> {
+    s.lines().enumerate().filter(move |(_, line)| {
+        !(line.trim().is_empty() || skips_comments && is_comment_line(line))
+    })
+}
+
+/// A line of a `key: value` section.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ColonLine<'a> {
+    Field(ColonField<'a>),
+    /// A line that isn't a `key: value` pair, with its index in the section.
+    Invalid(usize),
+}
+
+/// A `key: value` pair of a section, borrowing the key and value from the input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColonField<'a> {
+    pub key: &'a str,
+    pub value: &'a str,
+    /// Index of the line in the section.
+    pub line_index: usize,
+    pub key_columns: Range<usize>,
+    pub value_columns: Range<usize>,
+}
+
+/// Splits a `key: value` section into its lines without parsing the values.
+/// - Comment lines are skipped if `skips_comments` is set.
+/// - If not `lenient`, nothing is split after the first line that isn't a `key: value` pair.
+pub fn colon_lines(s: &str, skips_comments: bool, lenient: bool) -> Vec<ColonLine<'_>> {
+    let mut lines = Vec::new();
+    let mut s = s;
+    let mut line_count = 0;
+
+    loop {
+        let (rest, fields) = get_colon_field_value_lines(s).unwrap();
+        let rest_is_comment = skips_comments && is_comment_line(rest);
+
+        if !lenient && !rest.trim().is_empty() && !rest_is_comment {
+            // line count from fields
+            let line_count = line_count
+                + fields
+                    .iter()
+                    .map(|(_, _, _, ws)| ws.lines().count())
+                    .sum::<usize>();
+
+            lines.push(ColonLine::Invalid(line_count));
+            return lines;
+        }
+
+        for (key, _, value, ws) in fields {
+            // comment containing a colon
+            if !(skips_comments && is_comment_line(key)) {
+                lines.push(ColonLine::Field(ColonField {
+                    key,
+                    value,
+                    line_index: line_count,
+                    key_columns: columns_in_line(s, key),
+                    value_columns: columns_in_line(s, value),
+                }));
+            }
+
+            line_count += ws.lines().count();
+        }
+
+        if rest.trim().is_empty() {
+            break;
+        }
+
+        // skip the comment or the line that isn't a `key: value` pair, with the blank lines after it
+        if !rest_is_comment {
+            lines.push(ColonLine::Invalid(line_count));
+        }
+
+        s = skip_line(rest);
+        line_count += 1;
+
+        while !s.is_empty() && s.lines().next().unwrap_or_default().trim().is_empty() {
+            s = skip_line(s);
+            line_count += 1;
+        }
+    }
+
+    lines
+}
+
+/// Byte range of the comma separated field at `index` in the line.
+/// - Returns the end of the line if the field doesn't exist.
+pub fn comma_field_columns(line: &str, index: usize) -> Range<usize> {
+    let mut start = 0;
+
+    for (i, field) in line.split(',').enumerate() {
+        if i == index {
+            return start..start + field.len();
+        }
+
+        start += field.len() + 1;
+    }
+
+    line_end_columns(line)
+}
+
+/// Byte range of the last comma separated field in the line.
+pub fn last_comma_field_columns(line: &str) -> Range<usize> {
+    let start = line.rfind(',').map_or(0, |i| i + 1);
+
+    start..line.len()
+}
+
+/// Empty byte range at the end of the line, for errors with missing fields.
+pub fn line_end_columns(line: &str) -> Range<usize> {
+    line.len()..line.len()
+}
+
+/// Byte range of `part` in the line it's in, where `part` is a slice of `s`.
+pub fn columns_in_line(s: &str, part: &str) -> Range<usize> {
+    let start = part.as_ptr() as usize - s.as_ptr() as usize;
+    let line_start = s[..start].rfind('\n').map_or(0, |i| i + 1);
+
+    start - line_start..start - line_start + part.len()
+}
+
+/// Order of the entries after placing the unknown entries after the entries they were after in the input.
+/// - `known` are the names of the known entries in the output order.
+/// - `unknown` are the names of the unknown entries with the name of the entry before it, in the input order.
+/// - Returns `Left` with the index in `known` or `Right` with the index in `unknown`.
+/// - Unknown entries with an entry that isn't found are placed at the end.
+pub fn order_unknown_after(
+    known: &[&str],
+    unknown: &[(&str, Option<&str>)],
+) -> Vec<Either<usize, usize>> {
+    let name = |entry: &Either<usize, usize>| entry.either(|i| known[i], |i| unknown[i].0);
+
+    let mut order = (0..known.len()).map(Either::Left).collect::<Vec<_>>();
+
+    for (i, (_, after)) in unknown.iter().enumerate() {
+        let mut position = match after {
+            Some(after) => order
+                .iter()
+                .rposition(|entry| name(entry) == *after)
+                .map_or(order.len(), |position| position + 1),
+            None => 0,
+        };
+
+        // keep the order of unknown entries that are after the same entry
+        while matches!(order.get(position), Some(Either::Right(j)) if unknown[*j].1 == *after) {
+            position += 1;
+        }
+
+        order.insert(position, Either::Right(i));
+    }
+
+    order
+}
+
+/// Writes the same output as `VersionedToString::to_string` straight to the writer.
+pub trait VersionedWrite {
+    /// - Returns `None` without writing anything if there's no output for the version.
+    fn write_versioned<W: Write>(&self, f: &mut W, version: Version) -> Option<fmt::Result>;
+}
+
+/// Uses `VersionedWrite` to build a `String`.
+pub fn write_versioned_to_string<T: VersionedWrite>(value: &T, version: Version) -> Option<String> {
+    let mut s = String::new();
+
+    value
+        .write_versioned(&mut s, version)
+        .map(|res| res.map(|_| s).expect("writing to a String can't fail"))
+}
+
+/// Writes items separated by new lines, skipping items with no output for the version.
+pub fn write_new_line_separated<'a, T, W, I>(f: &mut W, items: I, version: Version) -> fmt::Result
+where
+    T: VersionedWrite + 'a,
+    W: Write,
+    I: IntoIterator<Item = &'a T>,
+{
+    let mut first = true;
+
+    for item in items {
+        let prefix = if first { None } else { Some("\n".into()) };
+        let mut writer = PrefixWriter::new(f, prefix);
+
+        if let Some(res) = item.write_versioned(&mut writer, version) {
+            res?;
+            // the item could be empty
+            writer.flush_prefix()?;
+            first = false;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writer that writes a prefix before the first write into the inner writer.
+pub struct PrefixWriter<'a, W> {
+    inner: &'a mut W,
+    prefix: Option<Cow<'static, str>>,
+}
+
+impl<'a, W: Write> PrefixWriter<'a, W> {
+    pub fn new(inner: &'a mut W, prefix: Option<Cow<'static, str>>) -> Self {
+        Self { inner, prefix }
+    }
+
+    /// Writes the prefix if it hasn't been written yet.
+    pub fn flush_prefix(&mut self) -> fmt::Result {
+        match self.prefix.take() {
+            Some(prefix) => self.inner.write_str(&prefix),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<W: Write> Write for PrefixWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.flush_prefix()?;
+        self.inner.write_str(s)
+    }
+}
+
+/// Writes sections in the form of `[name]` followed by the section contents, with an empty line between sections.
+pub struct SectionsWriter<'a, W> {
+    f: &'a mut W,
+    first: bool,
+}
+
+impl<'a, W: Write> SectionsWriter<'a, W> {
+    pub fn new(f: &'a mut W) -> Self {
+        Self { f, first: true }
+    }
+
+    /// Writes a section using `write` for the contents.
+    /// - The section is skipped if `write` returns `None`.
+    pub fn section<F>(&mut self, name: &str, write: F) -> fmt::Result
+    where
+        F: FnOnce(&mut PrefixWriter<W>) -> Option<fmt::Result>,
+    {
+        let prefix = if self.first {
+            format!("[{name}]\n")
+        } else {
+            format!("\n\n[{name}]\n")
+        };
+        let mut writer = PrefixWriter::new(self.f, Some(prefix.into()));
+
+        match write(&mut writer) {
+            Some(res) => {
+                res?;
+                // the section could be empty
+                writer.flush_prefix()?;
+                self.first = false;
+
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// Adapter for using `io::Write` as `fmt::Write`, keeping the I/O error around.
+pub struct IoWriter<W> {
+    inner: W,
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write> IoWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self { inner, error: None }
+    }
+
+    /// Converts the result of writing into an I/O result, flushing the writer on success.
+    pub fn finish(mut self, res: fmt::Result) -> io::Result<()> {
+        match res {
+            Ok(()) => self.inner.flush(),
+            Err(_) => Err(self
+                .error
+                .take()
+                .unwrap_or_else(|| io::Error::new(io::ErrorKind::Other, "formatter error"))),
+        }
+    }
+}
+
+impl<W: io::Write> Write for IoWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|err| {
+            self.error = Some(err);
+            fmt::Error
+        })
+    }
+}
+
+/// Result of parsing with an `ErrorCollector`, containing the parsed value and the collected errors.
+pub type ErrorsResult<T, E> = Result<(Option<T>, Vec<Error<E>>), Error<E>>;
+
+/// Collects errors when parsing leniently, otherwise passes the first error on.
+pub struct ErrorCollector<E> {
+    lenient: bool,
+    errors: Vec<Error<E>>,
+}
+
+impl<E> ErrorCollector<E> {
+    pub fn new(lenient: bool) -> Self {
+        Self {
+            lenient,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Returns the error if not lenient, otherwise stores it and returns `None`.
+    pub fn collect<T>(&mut self, result: Result<T, Error<E>>) -> Result<Option<T>, Error<E>> {
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(err) if self.lenient => {
+                self.errors.push(err);
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Stores the errors from parsing a part of the input, which starts at `line_index`.
+    pub fn extend<E2>(&mut self, errors: Vec<Error<E2>>, line_index: usize)
+    where
+        E: From<E2>,
+    {
+        self.errors
+            .extend(errors.into_iter().map(|err| err.offset_line(line_index)));
+    }
+
+    pub fn errors(&self) -> &[Error<E>] {
+        &self.errors
+    }
+
+    pub fn into_errors(self) -> Vec<Error<E>> {
+        self.errors
+    }
+}
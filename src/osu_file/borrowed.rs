@@ -0,0 +1,401 @@
+use std::marker::PhantomData;
+
+use crate::helper::{colon_lines, item_lines, ColonLine};
+
+use super::colours::{Colour, Colours};
+use super::events::Events;
+use super::hitobjects::{HitObject, HitObjects};
+use super::timingpoints::{TimingPoint, TimingPoints};
+use super::{
+    parse_version_line, split_sections, Difficulty, Editor, Error, General, Metadata, OsuFile,
+    ParseError, RawSection, UnknownSection, Version, VersionedFromStr,
+};
+
+/// An .osu file that borrows the text of the input instead of copying it.
+/// - Parsing splits the sections into their fields and lines, and the values are parsed from the input when they're asked for.
+/// - Use [`OsuFileRef::into_owned`] to convert it into an [`OsuFile`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OsuFileRef<'a> {
+    /// Version of the file format.
+    pub version: Version,
+    pub general: Option<FieldsRef<'a, General>>,
+    pub editor: Option<FieldsRef<'a, Editor>>,
+    pub metadata: Option<FieldsRef<'a, Metadata>>,
+    pub difficulty: Option<FieldsRef<'a, Difficulty>>,
+    pub events: Option<LinesRef<'a, Events>>,
+    pub timing_points: Option<LinesRef<'a, TimingPoints>>,
+    pub colours: Option<LinesRef<'a, Colours>>,
+    pub hitobjects: Option<LinesRef<'a, HitObjects>>,
+    /// Sections with names that aren't known.
+    pub unknown_sections: Vec<UnknownSectionRef<'a>>,
+}
+
+/// A `key: value` section that borrows its fields from the input.
+/// - The fields of the section `T` have methods with the same name, which give the value to be parsed when it's asked for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldsRef<'a, T> {
+    pub(crate) lines: Vec<ColonLine<'a>>,
+    /// Index of the first line of the section after its name.
+    pub(crate) line_index: usize,
+    pub(crate) version: Version,
+    pub(crate) section: PhantomData<T>,
+}
+
+impl<'a, T> FieldsRef<'a, T> {
+    fn new(s: &'a str, line_index: usize, version: Version) -> Self {
+        Self {
+            lines: colon_lines(s, false, false),
+            line_index,
+            version,
+            section: PhantomData,
+        }
+    }
+
+    /// `key: value` pairs of the section, in the order they are in the input.
+    /// - Lines that aren't `key: value` pairs are skipped, which are errors in [`into_owned`][Self::into_owned].
+    pub fn fields(&self) -> impl Iterator<Item = (&'a str, &'a str)> + '_ {
+        self.lines.iter().filter_map(|line| match line {
+            ColonLine::Field(field) => Some((field.key, field.value)),
+            ColonLine::Invalid(_) => None,
+        })
+    }
+
+    /// Value of the field with the `key`.
+    pub fn value(&self, key: &str) -> Option<&'a str> {
+        self.fields()
+            .find(|(field_key, _)| *field_key == key)
+            .map(|(_, value)| value)
+    }
+
+    pub(crate) fn value_ref<V>(&self, key: &str) -> Option<ValueRef<'a, V>> {
+        self.value(key)
+            .map(|value| ValueRef::new(value, self.version))
+    }
+}
+
+/// A section of comma separated lines that borrows its lines from the input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinesRef<'a, T> {
+    /// Lines that aren't blank, with their index in the section.
+    lines: Vec<(usize, &'a str)>,
+    /// Index of the first line of the section after its name.
+    line_index: usize,
+    version: Version,
+    section: PhantomData<T>,
+}
+
+impl<'a, T> LinesRef<'a, T> {
+    fn new(s: &'a str, line_index: usize, version: Version) -> Self {
+        Self {
+            lines: item_lines(s, false).collect(),
+            line_index,
+            version,
+            section: PhantomData,
+        }
+    }
+
+    /// Lines of the section, without the blank lines.
+    pub fn lines(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.lines.iter().map(|(_, line)| *line)
+    }
+
+    /// Each line as a value of the type `V`, parsed when it's asked for.
+    fn items<V>(&self) -> impl Iterator<Item = ValueRef<'a, V>> + '_ {
+        self.lines().map(|line| ValueRef::new(line, self.version))
+    }
+}
+
+impl<'a> LinesRef<'a, Events> {
+    /// Filename of the background image, from the first background event.
+    /// - The quotes around the filename are removed.
+    pub fn background(&self) -> Option<&'a str> {
+        self.lines().find_map(|line| {
+            let mut fields = line.split(',').map(|field| field.trim());
+
+            if !matches!(fields.next(), Some("0" | "Background")) {
+                return None;
+            }
+
+            let filename = fields.nth(1)?;
+
+            Some(
+                filename
+                    .strip_prefix('"')
+                    .and_then(|filename| filename.strip_suffix('"'))
+                    .unwrap_or(filename),
+            )
+        })
+    }
+
+    /// Parses the events.
+    /// - The events are parsed from the whole section, since the storyboard commands of an event are on the lines after it.
+    pub fn into_owned(self) -> Result<Option<Events>, Error<super::events::ParseError>> {
+        Error::processing_line(
+            Events::from_lines(self.lines, self.version, &[], false),
+            self.line_index,
+        )
+        .map(|(events, _)| events)
+    }
+}
+
+impl<'a> LinesRef<'a, TimingPoints> {
+    /// Timing points of the section, parsed when they're asked for.
+    pub fn timing_points(&self) -> impl Iterator<Item = ValueRef<'a, TimingPoint>> + '_ {
+        self.items()
+    }
+
+    /// Parses the timing points, one line at a time.
+    pub fn into_owned(
+        self,
+    ) -> Result<Option<TimingPoints>, Error<super::timingpoints::ParseError>> {
+        Error::processing_line(
+            TimingPoints::from_lines(self.lines, self.version, false),
+            self.line_index,
+        )
+        .map(|(timing_points, _)| timing_points)
+    }
+}
+
+impl<'a> LinesRef<'a, Colours> {
+    /// Colours of the section, parsed when they're asked for.
+    pub fn colours(&self) -> impl Iterator<Item = ValueRef<'a, Colour>> + '_ {
+        self.items()
+    }
+
+    /// Parses the colours, one line at a time.
+    /// - `None` if the version doesn't have colours.
+    pub fn into_owned(self) -> Result<Option<Colours>, Error<super::colours::ParseError>> {
+        Error::processing_line(
+            Colours::from_lines(self.lines, self.version, false),
+            self.line_index,
+        )
+        .map(|(colours, _)| colours)
+    }
+}
+
+impl<'a> LinesRef<'a, HitObjects> {
+    /// Hitobjects of the section, parsed when they're asked for.
+    pub fn hitobjects(&self) -> impl Iterator<Item = ValueRef<'a, HitObject>> + '_ {
+        self.items()
+    }
+
+    /// Parses the hitobjects, one line at a time.
+    pub fn into_owned(self) -> Result<Option<HitObjects>, Error<super::hitobjects::ParseError>> {
+        Error::processing_line(
+            HitObjects::from_lines(self.lines, self.version, false),
+            self.line_index,
+        )
+        .map(|(hitobjects, _)| hitobjects)
+    }
+}
+
+/// A value that borrows its text from the input, which is parsed into `T` when it's asked for.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct ValueRef<'a, T> {
+    value: &'a str,
+    version: Version,
+    value_type: PhantomData<T>,
+}
+
+impl<'a, T> Clone for ValueRef<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for ValueRef<'a, T> {}
+
+impl<'a, T> ValueRef<'a, T> {
+    fn new(value: &'a str, version: Version) -> Self {
+        Self {
+            value,
+            version,
+            value_type: PhantomData,
+        }
+    }
+
+    /// Text of the value in the input.
+    pub fn as_str(&self) -> &'a str {
+        self.value
+    }
+}
+
+impl<'a, T: VersionedFromStr> ValueRef<'a, T> {
+    /// Parses the value.
+    /// - Returns `None` if the value doesn't exist in the version.
+    pub fn parse(&self) -> Result<Option<T>, T::Err> {
+        T::from_str(self.value, self.version)
+    }
+}
+
+/// A section with a name that isn't known by the crate, borrowing its lines from the input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownSectionRef<'a> {
+    pub name: &'a str,
+    /// Lines in the section, without the blank lines.
+    pub lines: Vec<&'a str>,
+    /// Name of the section before this one in the input.
+    /// - `None` if this was the first section.
+    pub after: Option<&'a str>,
+}
+
+impl<'a> UnknownSectionRef<'a> {
+    pub fn into_owned(self) -> UnknownSection {
+        UnknownSection {
+            name: self.name.to_string(),
+            lines: self
+                .lines
+                .into_iter()
+                .map(|line| line.to_string())
+                .collect(),
+            after: self.after.map(|after| after.to_string()),
+        }
+    }
+}
+
+impl<'a> OsuFileRef<'a> {
+    /// Splits the .osu file into its sections, fields and lines without copying the text.
+    /// - Errors with the file version, lines before the first section and duplicate sections are returned.
+    /// - The values are checked when they're parsed, or by [`OsuFileRef::into_owned`].
+    pub fn parse(s: &'a str) -> Result<Self, Error<ParseError>> {
+        let (rest, trailing_ws, version) = parse_version_line(s)?;
+
+        let mut osu_file = Self {
+            version,
+            general: None,
+            editor: None,
+            metadata: None,
+            difficulty: None,
+            events: None,
+            timing_points: None,
+            colours: None,
+            hitobjects: None,
+            unknown_sections: Vec::new(),
+        };
+
+        let (pre_section_lines, sections) = split_sections(rest, trailing_ws.matches('\n').count());
+
+        if let Some((line_index, _)) = pre_section_lines.first() {
+            return Err(Error::new(ParseError::UnexpectedLine, *line_index));
+        }
+
+        for (i, section) in sections.iter().enumerate() {
+            let RawSection {
+                name,
+                name_line,
+                body_line,
+                body,
+            } = *section;
+
+            if sections[..i].iter().any(|section| section.name == name) {
+                return Err(Error::new(ParseError::DuplicateSections, name_line));
+            }
+
+            match name {
+                "General" => osu_file.general = Some(FieldsRef::new(body, body_line, version)),
+                "Editor" => osu_file.editor = Some(FieldsRef::new(body, body_line, version)),
+                "Metadata" => osu_file.metadata = Some(FieldsRef::new(body, body_line, version)),
+                "Difficulty" => {
+                    osu_file.difficulty = Some(FieldsRef::new(body, body_line, version))
+                }
+                "Events" => osu_file.events = Some(LinesRef::new(body, body_line, version)),
+                "TimingPoints" => {
+                    osu_file.timing_points = Some(LinesRef::new(body, body_line, version))
+                }
+                "Colours" => osu_file.colours = Some(LinesRef::new(body, body_line, version)),
+                "HitObjects" => osu_file.hitobjects = Some(LinesRef::new(body, body_line, version)),
+                _ => osu_file.unknown_sections.push(UnknownSectionRef {
+                    name,
+                    lines: body
+                        .lines()
+                        .filter(|line| !line.trim().is_empty())
+                        .collect(),
+                    after: i.checked_sub(1).map(|i| sections[i].name),
+                }),
+            }
+        }
+
+        Ok(osu_file)
+    }
+
+    /// Filename of the background image, from the first background event.
+    /// - The quotes around the filename are removed.
+    pub fn background(&self) -> Option<&'a str> {
+        self.events.as_ref()?.background()
+    }
+
+    /// Converts the file into an [`OsuFile`], parsing each section from its borrowed fields and lines.
+    /// - Returns the error that comes first in the input.
+    pub fn into_owned(self) -> Result<OsuFile, Error<ParseError>> {
+        fn convert<T, E>(
+            section: Result<Option<T>, Error<E>>,
+            errors: &mut Vec<Error<ParseError>>,
+        ) -> Option<T>
+        where
+            ParseError: From<E>,
+        {
+            section
+                .map_err(|err| errors.push(err.offset_line(0)))
+                .ok()
+                .flatten()
+        }
+
+        let mut errors = Vec::new();
+
+        let osu_file = OsuFile {
+            version: self.version,
+            general: convert(
+                self.general.map(|section| section.into_owned()).transpose(),
+                &mut errors,
+            ),
+            editor: convert(
+                self.editor.map(|section| section.into_owned()).transpose(),
+                &mut errors,
+            ),
+            metadata: convert(
+                self.metadata
+                    .map(|section| section.into_owned())
+                    .transpose(),
+                &mut errors,
+            ),
+            difficulty: convert(
+                self.difficulty
+                    .map(|section| section.into_owned())
+                    .transpose(),
+                &mut errors,
+            ),
+            events: convert(
+                self.events.map_or(Ok(None), |section| section.into_owned()),
+                &mut errors,
+            ),
+            timing_points: convert(
+                self.timing_points
+                    .map_or(Ok(None), |section| section.into_owned()),
+                &mut errors,
+            ),
+            colours: convert(
+                self.colours
+                    .map_or(Ok(None), |section| section.into_owned()),
+                &mut errors,
+            ),
+            hitobjects: convert(
+                self.hitobjects
+                    .map_or(Ok(None), |section| section.into_owned()),
+                &mut errors,
+            ),
+            osb: None,
+            unknown_sections: self
+                .unknown_sections
+                .into_iter()
+                .map(UnknownSectionRef::into_owned)
+                .collect(),
+            unparsed_lines: Vec::new(),
+            trivia: None,
+        };
+
+        match errors.into_iter().min_by_key(|err| err.line_index()) {
+            Some(err) => Err(err),
+            None => Ok(osu_file),
+        }
+    }
+}
@@ -1,7 +1,11 @@
+use std::ops::Range;
+
 use strum_macros::{EnumString, IntoStaticStr};
 use thiserror::Error;
 
 use crate::helper::macros::verbose_error_to_error;
+use crate::helper::{comma_field_columns, line_end_columns};
+use crate::osu_file::LineError;
 
 #[derive(Debug, Error)]
 #[error(transparent)]
@@ -27,6 +31,36 @@ pub enum ParseColourError {
 
 verbose_error_to_error!(ParseColourError);
 
+impl LineError for ParseColourError {
+    fn columns(&self, line: &str) -> Option<Range<usize>> {
+        match self {
+            ParseColourError::InvalidComboCount | ParseColourError::UnknownColourType => {
+                let key = line.split(':').next().unwrap_or_default();
+                let start = key.len() - key.trim_start().len();
+                Some(start..key.trim_end().len().max(start))
+            }
+            ParseColourError::InvalidColonSeparator => None,
+            ParseColourError::ParseRgbError(err) => {
+                let (key, value) = line.split_once(':')?;
+                let value_start = key.len() + 1 + value.len() - value.trim_start().len();
+                let value = value.trim_start();
+
+                let field = match err {
+                    ParseRgbError::InvalidRed => 0,
+                    ParseRgbError::InvalidGreen => 1,
+                    ParseRgbError::InvalidBlue => 2,
+                    ParseRgbError::MissingGreen | ParseRgbError::MissingBlue => {
+                        return Some(line_end_columns(line))
+                    }
+                };
+                let columns = comma_field_columns(value, field);
+
+                Some(value_start + columns.start..value_start + columns.end)
+            }
+        }
+    }
+}
+
 #[derive(Debug, Error, EnumString, IntoStaticStr)]
 #[non_exhaustive]
 pub enum ParseRgbError {
@@ -1,6 +1,8 @@
 pub mod error;
 pub mod types;
 
+use std::fmt::{self, Write};
+
 use nom::{
     branch::alt,
     bytes::complete::tag,
@@ -11,54 +13,105 @@ use nom::{
     Finish, Parser,
 };
 
+use crate::helper::{
+    item_lines, write_new_line_separated, write_versioned_to_string, ErrorCollector, ErrorsResult,
+    VersionedWrite,
+};
 use crate::parsers::comma;
 
 pub use error::*;
 pub use types::*;
 
-use super::{Error, Version, VersionedDefault, VersionedFromStr, VersionedToString, MIN_VERSION};
+use super::{
+    Error, ParseOptions, Version, VersionedDefault, VersionedFromStr, VersionedToString,
+    MIN_VERSION,
+};
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Colours(pub Vec<Colour>);
 
 impl VersionedFromStr for Colours {
     type Err = Error<ParseError>;
 
     fn from_str(s: &str, version: Version) -> std::result::Result<Option<Self>, Self::Err> {
+        Colours::from_str_errors(s, version, &ParseOptions::default()).map(|(colours, _)| colours)
+    }
+}
+
+impl Colours {
+    /// Parses the colours, collecting the errors instead of returning the first one if [`lenient`][ParseOptions::lenient] is set.
+    /// - Lines with errors are skipped when collected.
+    /// - Comment lines are skipped if [`skips_comments`][ParseOptions::skips_comments].
+    pub(crate) fn from_str_errors(
+        s: &str,
+        version: Version,
+        options: &ParseOptions,
+    ) -> ErrorsResult<Self, ParseError> {
+        Self::from_lines(
+            item_lines(s, options.skips_comments()),
+            version,
+            options.lenient,
+        )
+    }
+
+    /// Parses the colours from the lines of the section, with their index in the section.
+    pub(crate) fn from_lines<'a>(
+        lines: impl IntoIterator<Item = (usize, &'a str)>,
+        version: Version,
+        lenient: bool,
+    ) -> ErrorsResult<Self, ParseError> {
         match version {
-            MIN_VERSION..=4 => Ok(None),
+            MIN_VERSION..=4 => Ok((None, Vec::new())),
             _ => {
                 let mut colours = Vec::new();
-
-                for (line_index, s) in s.lines().enumerate() {
-                    if s.trim().is_empty() {
-                        continue;
-                    }
-
-                    let colour =
-                        Error::new_from_result_into(Colour::from_str(s, version), line_index)?;
-                    if let Some(colour) = colour {
+                let mut errors = ErrorCollector::new(lenient);
+
+                for (line_index, s) in lines {
+                    let colour = errors.collect(Error::new_from_line_result(
+                        Colour::from_str(s, version),
+                        s,
+                        line_index,
+                    ))?;
+                    if let Some(Some(colour)) = colour {
                         colours.push(colour);
                     }
                 }
 
-                Ok(Some(Colours(colours)))
+                Ok((Some(Colours(colours)), errors.into_errors()))
             }
         }
     }
 }
 
+impl Colours {
+    /// Combo colours in the order of their combo number.
+    pub fn combo_colours(&self) -> Vec<Rgb> {
+        let mut combo_colours = self
+            .0
+            .iter()
+            .filter_map(|colour| match colour {
+                Colour::Combo(num, rgb) => Some((*num, *rgb)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        combo_colours.sort_by_key(|(num, _)| *num);
+
+        combo_colours.into_iter().map(|(_, rgb)| rgb).collect()
+    }
+}
+
 impl VersionedToString for Colours {
     fn to_string(&self, version: Version) -> Option<String> {
+        write_versioned_to_string(self, version)
+    }
+}
+
+impl VersionedWrite for Colours {
+    fn write_versioned<W: Write>(&self, f: &mut W, version: Version) -> Option<fmt::Result> {
         match version {
             MIN_VERSION..=4 => None,
-            _ => Some(
-                self.0
-                    .iter()
-                    .filter_map(|c| c.to_string(version))
-                    .collect::<Vec<_>>()
-                    .join("\n"),
-            ),
+            _ => Some(write_new_line_separated(f, &self.0, version)),
         }
     }
 }
@@ -74,6 +127,7 @@ impl VersionedDefault for Colours {
 
 /// Struct representing a single `colour` component in the `Colours` section.
 #[derive(Clone, Copy, Hash, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum Colour {
     /// Additive combo colours.
@@ -172,16 +226,18 @@ impl VersionedFromStr for Colour {
 
 impl VersionedToString for Colour {
     fn to_string(&self, version: Version) -> Option<String> {
-        let colour_str = match self {
-            Colour::Combo(num, rgb) => format!("Combo{num} : {}", rgb.to_string(version).unwrap()),
-            Colour::SliderTrackOverride(rgb) => {
-                format!("SliderTrackOverride : {}", rgb.to_string(version).unwrap())
-            }
-            Colour::SliderBorder(rgb) => {
-                format!("SliderBorder : {}", rgb.to_string(version).unwrap())
-            }
+        write_versioned_to_string(self, version)
+    }
+}
+
+impl VersionedWrite for Colour {
+    fn write_versioned<W: Write>(&self, f: &mut W, version: Version) -> Option<fmt::Result> {
+        let (res, rgb) = match self {
+            Colour::Combo(num, rgb) => (write!(f, "Combo{num} : "), rgb),
+            Colour::SliderTrackOverride(rgb) => (f.write_str("SliderTrackOverride : "), rgb),
+            Colour::SliderBorder(rgb) => (f.write_str("SliderBorder : "), rgb),
         };
 
-        Some(colour_str)
+        Some(res.and_then(|_| rgb.write_versioned(f, version).unwrap()))
     }
 }
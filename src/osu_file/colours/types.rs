@@ -8,6 +8,7 @@ use crate::parsers::consume_rest_type;
 use super::*;
 
 #[derive(Clone, Copy, Hash, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Struct representing the RGB colours with each colour having value from 0 ~ 255.
 pub struct Rgb {
     /// Red colour.
@@ -49,7 +50,13 @@ impl VersionedFromStr for Rgb {
 }
 
 impl VersionedToString for Rgb {
-    fn to_string(&self, _: Version) -> Option<String> {
-        Some(format!("{},{},{}", self.red, self.green, self.blue))
+    fn to_string(&self, version: Version) -> Option<String> {
+        write_versioned_to_string(self, version)
+    }
+}
+
+impl VersionedWrite for Rgb {
+    fn write_versioned<W: Write>(&self, f: &mut W, _: Version) -> Option<fmt::Result> {
+        Some(write!(f, "{},{},{}", self.red, self.green, self.blue))
     }
 }
@@ -0,0 +1,330 @@
+use std::fmt::{self, Display};
+
+use thiserror::Error;
+
+use super::{
+    hitobjects::{HitObjectParams, HitSample, Volume},
+    Colours, Decimal, Editor, General, Integer, OsuFile, Version, VersionedDefault,
+    VersionedToString, LATEST_VERSION, MIN_VERSION,
+};
+
+/// Versions 3 and 4 have their times offset by this amount.
+const OLD_VERSION_TIME_OFFSET: Integer = 24;
+
+/// Something in the [`OsuFile`] that can't be represented in the version it's converted to.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConversionLoss {
+    /// A field of a `key: value` section, which is removed.
+    Field { section: &'static str, key: String },
+    /// A whole section, which is removed.
+    Section(&'static str),
+    /// The .osb storyboard, which is removed.
+    Osb,
+    /// An event with the index in the `Events` section, which is removed.
+    Event(usize),
+    /// A field of a timing point with the index in the `TimingPoints` section, which isn't written.
+    TimingPointField { index: usize, field: &'static str },
+    /// A field of a hitobject with the index in the `HitObjects` section.
+    /// - The `hitsample` is removed, the `volume` and `filename` of the hitsample aren't written.
+    /// - A `hitsample` with the default values is removed without being reported.
+    HitObjectField { index: usize, field: &'static str },
+    /// A time that isn't a number, which can't have the 24ms offset of versions 3 and 4 applied.
+    /// - `index` is the index of the item in the section.
+    TimeOffset { section: &'static str, index: usize },
+}
+
+impl Display for ConversionLoss {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConversionLoss::Field { section, key } => write!(f, "field `{key}` in [{section}]"),
+            ConversionLoss::Section(section) => write!(f, "section [{section}]"),
+            ConversionLoss::Osb => write!(f, "the .osb storyboard"),
+            ConversionLoss::Event(index) => write!(f, "event {index}"),
+            ConversionLoss::TimingPointField { index, field } => {
+                write!(f, "field `{field}` of timing point {index}")
+            }
+            ConversionLoss::HitObjectField { index, field } => {
+                write!(f, "field `{field}` of hitobject {index}")
+            }
+            ConversionLoss::TimeOffset { section, index } => {
+                write!(f, "time offset of item {index} in [{section}]")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+/// Error for when an [`OsuFile`] can't be converted to a version.
+pub enum ConvertError {
+    /// The version to convert to is invalid.
+    #[error("Invalid file version, expected versions from {MIN_VERSION} ~ {LATEST_VERSION}")]
+    InvalidVersion,
+}
+
+/// If the version offsets times by 24ms.
+fn has_time_offset(version: Version) -> bool {
+    (3..=4).contains(&version)
+}
+
+/// If the time is a string, which can't be offset.
+fn is_string_time(time: &Decimal) -> bool {
+    time.get().is_right()
+}
+
+impl OsuFile {
+    /// Converts the file to be written in the `version`.
+    /// - Editor fields are moved between the `[General]` and `[Editor]` sections.
+    /// - Things that can't be written in the `version` are removed, and returned as a report.
+    /// - The [`trivia`][OsuFile::trivia] is removed, since the formatting doesn't apply to the new version.
+    pub fn convert_to_version(
+        &mut self,
+        version: Version,
+    ) -> Result<Vec<ConversionLoss>, ConvertError> {
+        if !(MIN_VERSION..=LATEST_VERSION).contains(&version) {
+            return Err(ConvertError::InvalidVersion);
+        }
+
+        let source = self.version;
+        let mut losses = Vec::new();
+
+        self.move_editor_fields(version, &mut losses);
+
+        if let Some(general) = &mut self.general {
+            losses.extend(
+                general
+                    .remove_unsupported_fields(version)
+                    .into_iter()
+                    .map(|key| ConversionLoss::Field {
+                        section: "General",
+                        key: key.to_string(),
+                    }),
+            );
+        }
+        if let Some(editor) = &mut self.editor {
+            losses.extend(
+                editor
+                    .remove_unsupported_fields(version)
+                    .into_iter()
+                    .map(|key| ConversionLoss::Field {
+                        section: "Editor",
+                        key: key.to_string(),
+                    }),
+            );
+        }
+        if let Some(metadata) = &mut self.metadata {
+            losses.extend(
+                metadata
+                    .remove_unsupported_fields(version)
+                    .into_iter()
+                    .map(|key| ConversionLoss::Field {
+                        section: "Metadata",
+                        key: key.to_string(),
+                    }),
+            );
+        }
+        if let Some(difficulty) = &mut self.difficulty {
+            losses.extend(
+                difficulty
+                    .remove_unsupported_fields(version)
+                    .into_iter()
+                    .map(|key| ConversionLoss::Field {
+                        section: "Difficulty",
+                        key: key.to_string(),
+                    }),
+            );
+        }
+
+        if let Some(events) = &mut self.events {
+            let mut index = 0;
+
+            events.0.retain(|event| {
+                let keep = event.to_string(version).is_some() || event.to_string(source).is_none();
+
+                if !keep {
+                    losses.push(ConversionLoss::Event(index));
+                }
+
+                index += 1;
+                keep
+            });
+        }
+
+        let offset_changed = has_time_offset(source) != has_time_offset(version);
+
+        // hitobjects and timing points keep the offset times, but the preview time is stored as it's written
+        if offset_changed {
+            if let Some(general) = &mut self.general {
+                if let Some(preview_time) = &general.preview_time {
+                    let preview_time: Integer = preview_time.clone().into();
+
+                    if preview_time != -1 {
+                        let preview_time = if has_time_offset(source) {
+                            preview_time.saturating_add(OLD_VERSION_TIME_OFFSET)
+                        } else {
+                            preview_time.saturating_sub(OLD_VERSION_TIME_OFFSET)
+                        };
+
+                        general.preview_time = Some(preview_time.into());
+                    }
+                }
+            }
+        }
+
+        if let Some(timing_points) = &self.timing_points {
+            for (index, timing_point) in timing_points.0.iter().enumerate() {
+                let mut fields = Vec::new();
+
+                if source > 3 && version <= 3 {
+                    fields.extend(["meter", "sample_set", "sample_index"]);
+                }
+                if source > 4 && version <= 4 {
+                    fields.push("volume");
+                    fields.push("uninherited");
+                    if timing_point.effects().is_some() {
+                        fields.push("effects");
+                    }
+                }
+
+                losses.extend(
+                    fields
+                        .into_iter()
+                        .map(|field| ConversionLoss::TimingPointField { index, field }),
+                );
+
+                if offset_changed && is_string_time(timing_point.time()) {
+                    losses.push(ConversionLoss::TimeOffset {
+                        section: "TimingPoints",
+                        index,
+                    });
+                }
+            }
+        }
+
+        if let Some(hitobjects) = &mut self.hitobjects {
+            for (index, hitobject) in hitobjects.0.iter_mut().enumerate() {
+                if let Some(hitsample) = &hitobject.hitsample {
+                    if hitsample.to_string(version).is_none() {
+                        if HitSample::default(version).as_ref() != Some(hitsample) {
+                            losses.push(ConversionLoss::HitObjectField {
+                                index,
+                                field: "hitsample",
+                            });
+                        }
+                        hitobject.hitsample = None;
+                    } else if (10..=11).contains(&version) {
+                        if hitsample.volume != Volume::default(version).unwrap() {
+                            losses.push(ConversionLoss::HitObjectField {
+                                index,
+                                field: "volume",
+                            });
+                        }
+                        if !hitsample.filename.is_empty() {
+                            losses.push(ConversionLoss::HitObjectField {
+                                index,
+                                field: "filename",
+                            });
+                        }
+                    }
+                }
+
+                let end_time = match &hitobject.obj_params {
+                    HitObjectParams::Spinner { end_time }
+                    | HitObjectParams::OsuManiaHold { end_time } => Some(end_time),
+                    _ => None,
+                };
+
+                if offset_changed
+                    && (is_string_time(&hitobject.time) || end_time.is_some_and(is_string_time))
+                {
+                    losses.push(ConversionLoss::TimeOffset {
+                        section: "HitObjects",
+                        index,
+                    });
+                }
+            }
+        }
+
+        if self.colours.is_some() && Colours::default(version).is_none() {
+            self.colours = None;
+            losses.push(ConversionLoss::Section("Colours"));
+        }
+
+        if self.osb.is_some() && version < 14 {
+            self.osb = None;
+            losses.push(ConversionLoss::Osb);
+        }
+
+        self.version = version;
+        self.trivia = None;
+
+        Ok(losses)
+    }
+
+    /// Moves the editor fields to `[General]` for versions without the `[Editor]` section, and back to `[Editor]` otherwise.
+    /// - Fields that are already set where they're moved to are kept, and the moved fields are reported as lost.
+    fn move_editor_fields(&mut self, version: Version, losses: &mut Vec<ConversionLoss>) {
+        if version <= 5 {
+            let mut editor = match self.editor.take() {
+                Some(editor) => editor,
+                None => return,
+            };
+
+            let general = self.general.get_or_insert_with(General::new);
+
+            if general.editor_bookmarks.is_none() {
+                if let Some(bookmarks) = editor.bookmarks.take() {
+                    general.editor_bookmarks = Some(Vec::from(bookmarks).into());
+                }
+            }
+            if general.editor_distance_spacing.is_none() {
+                if let Some(distance_spacing) = editor.distance_spacing.take() {
+                    general.editor_distance_spacing = Some(Decimal::from(distance_spacing).into());
+                }
+            }
+
+            losses.extend(
+                editor
+                    .field_keys()
+                    .into_iter()
+                    .map(|key| ConversionLoss::Field {
+                        section: "Editor",
+                        key: key.to_string(),
+                    }),
+            );
+        } else {
+            let general = match &mut self.general {
+                Some(general) => general,
+                None => return,
+            };
+
+            if general.editor_bookmarks.is_none() && general.editor_distance_spacing.is_none() {
+                return;
+            }
+
+            let editor = self.editor.get_or_insert_with(Editor::new);
+
+            if let Some(bookmarks) = general.editor_bookmarks.take() {
+                if editor.bookmarks.is_none() {
+                    editor.bookmarks = Some(Vec::from(bookmarks).into());
+                } else {
+                    losses.push(ConversionLoss::Field {
+                        section: "General",
+                        key: "EditorBookmarks".to_string(),
+                    });
+                }
+            }
+            if let Some(distance_spacing) = general.editor_distance_spacing.take() {
+                if editor.distance_spacing.is_none() {
+                    editor.distance_spacing = Some(Decimal::from(distance_spacing).into());
+                } else {
+                    losses.push(ConversionLoss::Field {
+                        section: "General",
+                        key: "EditorDistanceSpacing".to_string(),
+                    });
+                }
+            }
+        }
+    }
+}
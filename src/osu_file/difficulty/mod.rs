@@ -1,10 +1,12 @@
 pub mod error;
+pub mod values;
 
 use crate::osu_file::types::Decimal;
 
 use crate::helper::macros::*;
 
 pub use error::*;
+pub use values::*;
 
 versioned_field!(HPDrainRate, Decimal, no_versions, |s| { s.parse() } -> (),,);
 versioned_field!(CircleSize, Decimal, no_versions, |s| { s.parse() } -> (),,);
@@ -37,3 +39,6 @@ general_section!(
         SliderTickRate: 1,
     }
 );
+
+/// Difficulty with none of the fields set, used for beatmaps without a `[Difficulty]` section.
+pub(crate) static EMPTY_DIFFICULTY: Difficulty = Difficulty::new();
@@ -0,0 +1,243 @@
+//! Difficulty settings as numbers, and what they're converted to in each mode.
+
+use super::Difficulty;
+use crate::osu_file::{general::Mode, Decimal};
+
+/// Difficulty value used when the difficulty doesn't have one.
+const DEFAULT_DIFFICULTY: f64 = 5.0;
+/// Slider multiplier used when the difficulty doesn't have one.
+const DEFAULT_SLIDER_MULTIPLIER: f64 = 1.4;
+/// Tick rate used when the difficulty doesn't have one.
+const DEFAULT_SLIDER_TICK_RATE: f64 = 1.0;
+/// Radius of a circle in `osu!pixels` at a scale of `1`.
+const OBJECT_RADIUS: f64 = 64.0;
+/// Circle size scale multiplier osu! uses for a rounding difference of the playfield.
+const BROKEN_GAMEFIELD_ROUNDING_ALLOWANCE: f64 = 1.00041;
+/// Shortest preempt, which is where the fade in starts to get shorter.
+const PREEMPT_MIN: f64 = 450.0;
+/// Fade in time in milliseconds when the preempt isn't shorter than [`PREEMPT_MIN`].
+const FADE_IN: f64 = 400.0;
+/// Width of the catcher in `osu!pixels` at a scale of `1`.
+const CATCHER_BASE_SIZE: f64 = 106.75;
+/// Part of the catcher's width that catches fruits.
+pub(crate) const ALLOWED_CATCH_RANGE: f64 = 0.8;
+
+/// Value of a `key: value` field as a `f64`, or the `default` if it isn't set or isn't a number.
+pub(crate) fn difficulty_value<T: Clone + Into<Decimal>>(field: &Option<T>, default: f64) -> f64 {
+    field
+        .as_ref()
+        .and_then(|value| value.clone().into().to_f64())
+        .unwrap_or(default)
+}
+
+/// Scales the difficulty `value` from `0` ~ `10` to the `min` at `0`, `mid` at `5` and `max` at `10`, like osu! does.
+pub fn difficulty_range(value: f64, min: f64, mid: f64, max: f64) -> f64 {
+    if value > 5.0 {
+        mid + (max - mid) * (value - 5.0) / 5.0
+    } else if value < 5.0 {
+        mid - (mid - min) * (5.0 - value) / 5.0
+    } else {
+        mid
+    }
+}
+
+/// Approach rate with the `preempt` in milliseconds, being the inverse of [`DifficultyValues::preempt`].
+/// - Goes above `10` for shorter preempts, like the ones from double time.
+pub fn approach_rate_from_preempt(preempt: f64) -> f64 {
+    if preempt > 1200.0 {
+        (1800.0 - preempt) / 120.0
+    } else {
+        (1200.0 - preempt) / 150.0 + 5.0
+    }
+}
+
+/// Difficulty settings as numbers, with the defaults for settings that aren't set.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DifficultyValues {
+    pub hp_drain_rate: f64,
+    pub circle_size: f64,
+    pub overall_difficulty: f64,
+    pub approach_rate: f64,
+    pub slider_multiplier: f64,
+    pub slider_tick_rate: f64,
+}
+
+/// Difficulty of osu!standard.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OsuDifficulty {
+    /// Time in milliseconds before a hitobject when it starts to appear.
+    pub preempt: f64,
+    /// Time in milliseconds a hitobject takes to fade in.
+    pub fade_in: f64,
+    /// Hit windows in milliseconds before or after a hitobject for a 300, 100 and 50.
+    pub hit_window_300: f64,
+    pub hit_window_100: f64,
+    pub hit_window_50: f64,
+    /// Hitting earlier than this in milliseconds doesn't count as a miss.
+    pub hit_window_miss: f64,
+    /// Scale of the hitobjects from a radius of 64 `osu!pixels`, which is about `0.5` at a circle size of `5`.
+    pub scale: f64,
+    /// Radius of a circle in `osu!pixels`.
+    pub circle_radius: f64,
+    pub hp_drain_rate: f64,
+}
+
+/// Difficulty of osu!taiko.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TaikoDifficulty {
+    /// Hit windows in milliseconds before or after a hitobject for a great and ok.
+    pub hit_window_great: f64,
+    pub hit_window_ok: f64,
+    /// Hitting earlier than this in milliseconds doesn't count as a miss.
+    pub hit_window_miss: f64,
+    pub hp_drain_rate: f64,
+}
+
+/// Difficulty of osu!catch.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CatchDifficulty {
+    /// Time in milliseconds before a fruit when it starts to appear.
+    pub preempt: f64,
+    /// Scale of the fruits and the catcher, which is `1` at a circle size of `5`.
+    pub scale: f64,
+    /// Width of the part of the catcher that catches fruits, in `osu!pixels`.
+    pub catcher_width: f64,
+    pub hp_drain_rate: f64,
+}
+
+/// Difficulty of osu!mania.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ManiaDifficulty {
+    /// Hit windows in milliseconds before or after a note for a 320, 300, 200, 100 and 50.
+    pub hit_window_320: f64,
+    pub hit_window_300: f64,
+    pub hit_window_200: f64,
+    pub hit_window_100: f64,
+    pub hit_window_50: f64,
+    /// Hitting earlier than this in milliseconds doesn't count as a miss.
+    pub hit_window_miss: f64,
+    pub hp_drain_rate: f64,
+}
+
+/// Difficulty of a mode.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ModeDifficulty {
+    Osu(OsuDifficulty),
+    Taiko(TaikoDifficulty),
+    Catch(CatchDifficulty),
+    Mania(ManiaDifficulty),
+}
+
+impl Default for DifficultyValues {
+    fn default() -> Self {
+        Self {
+            hp_drain_rate: DEFAULT_DIFFICULTY,
+            circle_size: DEFAULT_DIFFICULTY,
+            overall_difficulty: DEFAULT_DIFFICULTY,
+            approach_rate: DEFAULT_DIFFICULTY,
+            slider_multiplier: DEFAULT_SLIDER_MULTIPLIER,
+            slider_tick_rate: DEFAULT_SLIDER_TICK_RATE,
+        }
+    }
+}
+
+impl DifficultyValues {
+    /// Converts the difficulty settings for the `mode`.
+    pub fn mode(&self, mode: Mode) -> ModeDifficulty {
+        match mode {
+            Mode::Osu => ModeDifficulty::Osu(self.osu()),
+            Mode::Taiko => ModeDifficulty::Taiko(self.taiko()),
+            Mode::Catch => ModeDifficulty::Catch(self.catch()),
+            Mode::Mania => ModeDifficulty::Mania(self.mania()),
+        }
+    }
+
+    /// Time in milliseconds before a hitobject when it starts to appear, from the approach rate.
+    pub fn preempt(&self) -> f64 {
+        difficulty_range(self.approach_rate, 1800.0, 1200.0, PREEMPT_MIN)
+    }
+
+    /// Converts the difficulty settings for osu!standard.
+    pub fn osu(&self) -> OsuDifficulty {
+        let preempt = self.preempt();
+        let scale = (1.0 - 0.7 * (self.circle_size - 5.0) / 5.0) / 2.0
+            * BROKEN_GAMEFIELD_ROUNDING_ALLOWANCE;
+        let od = self.overall_difficulty;
+
+        OsuDifficulty {
+            preempt,
+            fade_in: FADE_IN * (preempt / PREEMPT_MIN).min(1.0),
+            hit_window_300: difficulty_range(od, 80.0, 50.0, 20.0),
+            hit_window_100: difficulty_range(od, 140.0, 100.0, 60.0),
+            hit_window_50: difficulty_range(od, 200.0, 150.0, 100.0),
+            hit_window_miss: 400.0,
+            scale,
+            circle_radius: OBJECT_RADIUS * scale,
+            hp_drain_rate: self.hp_drain_rate,
+        }
+    }
+
+    /// Converts the difficulty settings for osu!taiko.
+    pub fn taiko(&self) -> TaikoDifficulty {
+        let od = self.overall_difficulty;
+
+        TaikoDifficulty {
+            hit_window_great: difficulty_range(od, 50.0, 35.0, 20.0),
+            hit_window_ok: difficulty_range(od, 120.0, 80.0, 50.0),
+            hit_window_miss: difficulty_range(od, 135.0, 95.0, 70.0),
+            hp_drain_rate: self.hp_drain_rate,
+        }
+    }
+
+    /// Converts the difficulty settings for osu!catch.
+    pub fn catch(&self) -> CatchDifficulty {
+        let scale = 1.0 - 0.7 * (self.circle_size - 5.0) / 5.0;
+
+        CatchDifficulty {
+            preempt: self.preempt(),
+            scale,
+            catcher_width: CATCHER_BASE_SIZE * scale.abs() * ALLOWED_CATCH_RANGE,
+            hp_drain_rate: self.hp_drain_rate,
+        }
+    }
+
+    /// Converts the difficulty settings for osu!mania.
+    /// - Uses the hit windows of osu!mania beatmaps, which are different for beatmaps converted from other modes.
+    pub fn mania(&self) -> ManiaDifficulty {
+        let od = self.overall_difficulty;
+
+        ManiaDifficulty {
+            hit_window_320: 16.0,
+            hit_window_300: 64.0 - 3.0 * od,
+            hit_window_200: 97.0 - 3.0 * od,
+            hit_window_100: 127.0 - 3.0 * od,
+            hit_window_50: 151.0 - 3.0 * od,
+            hit_window_miss: 188.0 - 3.0 * od,
+            hp_drain_rate: self.hp_drain_rate,
+        }
+    }
+}
+
+impl Difficulty {
+    /// Difficulty settings as numbers, which can be converted for each mode.
+    /// - Settings that aren't set or aren't numbers use osu!'s defaults, which is `5` for the difficulty settings.
+    /// - The approach rate falls back to the overall difficulty if it isn't set, since older files don't always have it.
+    pub fn values(&self) -> DifficultyValues {
+        let overall_difficulty = difficulty_value(&self.overall_difficulty, DEFAULT_DIFFICULTY);
+
+        DifficultyValues {
+            hp_drain_rate: difficulty_value(&self.hp_drain_rate, DEFAULT_DIFFICULTY),
+            circle_size: difficulty_value(&self.circle_size, DEFAULT_DIFFICULTY),
+            overall_difficulty,
+            approach_rate: difficulty_value(&self.approach_rate, overall_difficulty),
+            slider_multiplier: difficulty_value(&self.slider_multiplier, DEFAULT_SLIDER_MULTIPLIER),
+            slider_tick_rate: difficulty_value(&self.slider_tickrate, DEFAULT_SLIDER_TICK_RATE),
+        }
+    }
+}
@@ -0,0 +1,533 @@
+//! Difficulty calculation of osu!catch.
+
+use super::{strain_decay, weighted_sum, StrainPeaks, StrainSkill};
+use crate::osu_file::{
+    difficulty::{approach_rate_from_preempt, Difficulty, ALLOWED_CATCH_RANGE, EMPTY_DIFFICULTY},
+    general::Mode,
+    hitobjects::{HitObjectParams, NestedObjectKind, Vec2, EMPTY_HITOBJECTS},
+    Mods, OsuFile,
+};
+
+const STAR_SCALING_FACTOR: f64 = 0.153;
+/// Radius of a fruit that the positions are scaled to, so the circle size doesn't change them.
+const NORMALISED_HITOBJECT_RADIUS: f64 = 41.0;
+/// How far off the catcher can be from the fruit when the player catches it.
+const ABSOLUTE_PLAYER_POSITIONING_ERROR: f64 = 16.0;
+const DIRECTION_CHANGE_BONUS: f64 = 21.0;
+/// Speed of the catcher while dashing, in `osu!pixels` per millisecond.
+const BASE_DASH_SPEED: f64 = 1.0;
+const SECTION_LENGTH: f64 = 750.0;
+const DECAY_WEIGHT: f64 = 0.94;
+/// Width of the playfield in `osu!pixels`.
+const PLAYFIELD_WIDTH: f32 = 512.0;
+
+/// Difficulty of an osu!catch beatmap, with the attributes needed for the performance points.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CatchDifficultyAttributes {
+    pub star_rating: f64,
+    pub approach_rate: f64,
+    /// Highest combo that can be reached, which is the amount of fruits and droplets.
+    pub max_combo: usize,
+    /// Amount of fruits, including the ones in juice streams.
+    pub fruit_count: usize,
+    /// Amount of droplets from the ticks of juice streams.
+    pub droplet_count: usize,
+    /// Amount of tiny droplets, which don't give combo.
+    pub tiny_droplet_count: usize,
+}
+
+impl OsuFile {
+    /// Computes the star rating and difficulty attributes of an osu!catch beatmap, like osu! does for stable.
+    /// - Returns `None` if the beatmap's mode isn't osu!catch, since converted beatmaps aren't supported.
+    pub fn catch_difficulty(&self) -> Option<CatchDifficultyAttributes> {
+        match self.mode() {
+            Mode::Catch => Some(calculate(self, &Mods::default())),
+            _ => None,
+        }
+    }
+}
+
+/// A fruit or droplet that the catcher has to move to.
+struct PalpableObject {
+    start_time: f64,
+    x: f64,
+    /// If the catcher has to hyperdash to the next object.
+    hyper_dash: bool,
+    /// How far the catcher could still move to the next object before it would need a hyperdash.
+    distance_to_hyper_dash: f64,
+}
+
+/// Random number generator osu!stable uses for the positions of the objects.
+struct LegacyRandom {
+    x: u32,
+    y: u32,
+    z: u32,
+    w: u32,
+    bit_buffer: u32,
+    bit_index: u32,
+}
+
+impl LegacyRandom {
+    const SEED: u32 = 1337;
+
+    fn new() -> Self {
+        Self {
+            x: Self::SEED,
+            y: 842502087,
+            z: 3579807591,
+            w: 273326509,
+            bit_buffer: 0,
+            bit_index: 32,
+        }
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        let t = self.x ^ (self.x << 11);
+        self.x = self.y;
+        self.y = self.z;
+        self.z = self.w;
+        self.w = self.w ^ (self.w >> 19) ^ t ^ (t >> 8);
+        self.w
+    }
+
+    fn next_i32(&mut self) -> i32 {
+        (self.next_u32() & 0x7FFF_FFFF) as i32
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        self.next_i32() as f64 / (i32::MAX as f64 + 1.0)
+    }
+
+    fn next_range(&mut self, lower: f64, upper: f64) -> i32 {
+        (lower + self.next_f64() * (upper - lower)) as i32
+    }
+
+    fn next_bool(&mut self) -> bool {
+        if self.bit_index == 32 {
+            self.bit_buffer = self.next_u32();
+            self.bit_index = 1;
+        } else {
+            self.bit_index += 1;
+            self.bit_buffer >>= 1;
+        }
+
+        self.bit_buffer & 1 == 1
+    }
+}
+
+/// Moves the fruits with hard rock like osu!stable does, depending on the position and time of the previous fruit.
+struct HardRockOffsets {
+    rng: LegacyRandom,
+    last_position: Option<f32>,
+    last_start_time: f64,
+}
+
+impl HardRockOffsets {
+    fn new() -> Self {
+        Self {
+            rng: LegacyRandom::new(),
+            last_position: None,
+            last_start_time: 0.0,
+        }
+    }
+
+    /// Position of a fruit at the `x` after it's moved.
+    fn fruit(&mut self, x: f32, start_time: f64) -> f32 {
+        // osu!stable also starts over for objects that are at `0`
+        let last_position = match self.last_position {
+            Some(last_position) if last_position.abs() > 1e-3 => last_position,
+            _ => {
+                self.last_position = Some(x);
+                self.last_start_time = start_time;
+                return x;
+            }
+        };
+
+        let position_diff = x - last_position;
+        let time_diff = (start_time - self.last_start_time) as i32;
+
+        if time_diff > 1000 {
+            self.last_position = Some(x);
+            self.last_start_time = start_time;
+            return x;
+        }
+
+        // the same position is moved randomly, without becoming the new last position
+        if position_diff == 0.0 {
+            let right = self.rng.next_bool();
+            let offset =
+                (self.rng.next_range(0.0, (time_diff as f64 / 4.0).max(0.0)) as f32).min(20.0);
+
+            return if (right && x + offset <= PLAYFIELD_WIDTH) || (!right && x - offset < 0.0) {
+                x + offset
+            } else {
+                x - offset
+            };
+        }
+
+        let mut x = x;
+        if position_diff.abs() < (time_diff / 3) as f32 {
+            let moved = x + position_diff;
+            if (position_diff > 0.0 && moved < PLAYFIELD_WIDTH)
+                || (position_diff <= 0.0 && moved > 0.0)
+            {
+                x = moved;
+            }
+        }
+
+        self.last_position = Some(x);
+        self.last_start_time = start_time;
+        x
+    }
+
+    /// Continues from a juice stream ending at the `end_x`, with the random numbers used by its `droplets`.
+    fn juice_stream(&mut self, end_x: f32, start_time: f64, droplets: usize) {
+        // osu!stable uses the start time instead of the end time of the juice stream
+        self.last_position = Some(end_x);
+        self.last_start_time = start_time;
+
+        for _ in 0..droplets {
+            self.rng.next_i32();
+        }
+    }
+
+    /// Skips the random numbers used by the positions of `bananas`.
+    fn banana_shower(&mut self, bananas: usize) {
+        for _ in 0..bananas * 4 {
+            self.rng.next_i32();
+        }
+    }
+}
+
+/// Amount of bananas in a banana shower from the `start_time` to the `end_time`.
+fn banana_count(start_time: f64, end_time: f64) -> usize {
+    let mut spacing = end_time - start_time;
+    while spacing > 100.0 {
+        spacing /= 2.0;
+    }
+
+    if spacing <= 0.0 {
+        return 0;
+    }
+
+    let mut count = 0;
+    let mut time = start_time;
+    while time <= end_time {
+        count += 1;
+        time += spacing;
+    }
+
+    count
+}
+
+/// Generates the fruits and droplets of the beatmap in the order of their time, counting them in the `attributes`.
+/// - Bananas are left out, and tiny droplets are only counted since they don't change the difficulty.
+/// - The fruits are moved like osu!stable does with `hard_rock`.
+fn palpable_objects(
+    osu_file: &OsuFile,
+    difficulty: &Difficulty,
+    hard_rock: bool,
+    attributes: &mut CatchDifficultyAttributes,
+) -> Vec<PalpableObject> {
+    let hitobjects = osu_file.hitobjects.as_ref().unwrap_or(&EMPTY_HITOBJECTS);
+    let timing_index = osu_file
+        .timing_points
+        .as_ref()
+        .and_then(|timing_points| timing_points.index());
+
+    let palpable_object = |start_time: f64, x: f64| PalpableObject {
+        start_time,
+        x,
+        hyper_dash: false,
+        distance_to_hyper_dash: 0.0,
+    };
+
+    let mut hard_rock_offsets = HardRockOffsets::new();
+    let mut objects = Vec::new();
+
+    for hitobject in &hitobjects.0 {
+        let start_time = hitobject.start_time().unwrap_or_default();
+        let x = Vec2::from_position(&hitobject.position)
+            .unwrap_or_default()
+            .x;
+
+        let nested = match &hitobject.obj_params {
+            HitObjectParams::Slider(_) => timing_index
+                .as_ref()
+                .and_then(|index| hitobject.nested_objects(index, difficulty, osu_file.version)),
+            HitObjectParams::Spinner { end_time } => {
+                let end_time = end_time.to_f64().unwrap_or(start_time);
+                hard_rock_offsets.banana_shower(banana_count(start_time, end_time));
+                continue;
+            }
+            HitObjectParams::OsuManiaHold { .. } => continue,
+            _ => None,
+        };
+
+        let nested = match nested {
+            Some(nested) => nested,
+            None => {
+                let x = if hard_rock {
+                    hard_rock_offsets.fruit(x as f32, start_time) as f64
+                } else {
+                    x
+                };
+
+                attributes.fruit_count += 1;
+                objects.push(palpable_object(start_time, x));
+                continue;
+            }
+        };
+
+        let droplets_before = attributes.droplet_count + attributes.tiny_droplet_count;
+
+        for (i, object) in nested.iter().enumerate() {
+            // tiny droplets are between each nested object, including the legacy last tick
+            if i > 0 {
+                let since_last_tick = object.time.trunc() - nested[i - 1].time.trunc();
+
+                if since_last_tick > 80.0 {
+                    let mut time_between_tiny = since_last_tick;
+                    while time_between_tiny > 100.0 {
+                        time_between_tiny /= 2.0;
+                    }
+
+                    let mut time = time_between_tiny;
+                    while time < since_last_tick {
+                        attributes.tiny_droplet_count += 1;
+                        time += time_between_tiny;
+                    }
+                }
+            }
+
+            match object.kind {
+                NestedObjectKind::Tick => attributes.droplet_count += 1,
+                NestedObjectKind::LegacyLastTick => continue,
+                _ => attributes.fruit_count += 1,
+            }
+
+            objects.push(palpable_object(object.time, object.position.x));
+        }
+
+        if let HitObjectParams::Slider(params) = &hitobject.obj_params {
+            // osu!stable uses the last curve point instead of the end of the path
+            let end_x = params
+                .curve_points
+                .last()
+                .and_then(|point| Vec2::from_position(&point.0))
+                .map_or(x, |point| point.x);
+
+            hard_rock_offsets.juice_stream(
+                end_x as f32,
+                start_time,
+                attributes.droplet_count + attributes.tiny_droplet_count - droplets_before,
+            );
+        }
+    }
+
+    objects.sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
+    objects
+}
+
+/// Finds the objects that need a hyperdash to the next object, and how close the others are to needing one.
+/// - Uses the full width of the catcher like osu!stable does.
+fn initialise_hyper_dash(objects: &mut [PalpableObject], half_catcher_width: f64) {
+    let mut last_direction = 0;
+    let mut last_excess = half_catcher_width;
+
+    for i in 0..objects.len().saturating_sub(1) {
+        let next_x = objects[i + 1].x;
+        let next_time = objects[i + 1].start_time;
+        let current = &mut objects[i];
+
+        let direction = if next_x > current.x { 1 } else { -1 };
+        // a quarter of a frame is given as grace time, like osu!stable does
+        let time_to_next = next_time.trunc() - current.start_time.trunc() - 1000.0 / 60.0 / 4.0;
+        let distance_to_next = (next_x - current.x).abs()
+            - if last_direction == direction {
+                last_excess
+            } else {
+                half_catcher_width
+            };
+        let distance_to_hyper = time_to_next * BASE_DASH_SPEED - distance_to_next;
+
+        if distance_to_hyper < 0.0 {
+            current.hyper_dash = true;
+            last_excess = half_catcher_width;
+        } else {
+            current.distance_to_hyper_dash = distance_to_hyper;
+            last_excess = distance_to_hyper.clamp(0.0, half_catcher_width);
+        }
+
+        last_direction = direction;
+    }
+}
+
+/// An object with how the catcher moves to it from the previous object, for the difficulty calculation.
+struct DifficultyObject<'a> {
+    last: &'a PalpableObject,
+    start_time: f64,
+    delta_time: f64,
+    strain_time: f64,
+    normalised_position: f64,
+    last_normalised_position: f64,
+}
+
+/// Difficulty of moving the catcher between objects.
+struct Movement {
+    clock_rate: f64,
+    current_strain: f64,
+    last_player_position: Option<f64>,
+    last_distance_moved: f64,
+    last_strain_time: f64,
+}
+
+impl Movement {
+    const SKILL_MULTIPLIER: f64 = 900.0;
+    const STRAIN_DECAY_BASE: f64 = 0.2;
+
+    fn strain_of(&mut self, current: &DifficultyObject) -> f64 {
+        let last_player_position = *self
+            .last_player_position
+            .get_or_insert(current.last_normalised_position);
+
+        let mut player_position = last_player_position.clamp(
+            current.normalised_position
+                - (NORMALISED_HITOBJECT_RADIUS - ABSOLUTE_PLAYER_POSITIONING_ERROR),
+            current.normalised_position
+                + (NORMALISED_HITOBJECT_RADIUS - ABSOLUTE_PLAYER_POSITIONING_ERROR),
+        );
+        let distance_moved = player_position - last_player_position;
+
+        let weighted_strain_time = current.strain_time + 13.0 + (3.0 / self.clock_rate);
+
+        let mut distance_addition = distance_moved.abs().powf(1.3) / 510.0;
+        let sqrt_strain = weighted_strain_time.sqrt();
+
+        if distance_moved.abs() > 0.1 {
+            if self.last_distance_moved.abs() > 0.1
+                && distance_moved.signum() != self.last_distance_moved.signum()
+            {
+                let bonus_factor = distance_moved.abs().min(50.0) / 50.0;
+                let anti_flow_factor = (self.last_distance_moved.abs().min(70.0) / 70.0).max(0.38);
+
+                distance_addition += DIRECTION_CHANGE_BONUS / (self.last_strain_time + 16.0).sqrt()
+                    * bonus_factor
+                    * anti_flow_factor
+                    * (1.0 - (weighted_strain_time / 1000.0).powi(3)).max(0.0);
+            }
+
+            // every movement has some difficulty, giving weight to streams
+            distance_addition += 12.5 * distance_moved.abs().min(NORMALISED_HITOBJECT_RADIUS * 2.0)
+                / (NORMALISED_HITOBJECT_RADIUS * 6.0)
+                / sqrt_strain;
+        }
+
+        // edge dashes are harder, unless the catcher has just hyperdashed into the right position
+        if current.last.distance_to_hyper_dash <= 20.0 {
+            let edge_dash_bonus = if current.last.hyper_dash {
+                player_position = current.normalised_position;
+                0.0
+            } else {
+                5.7
+            };
+
+            distance_addition *= 1.0
+                + edge_dash_bonus
+                    * ((20.0 - current.last.distance_to_hyper_dash) / 20.0)
+                    * ((current.strain_time * self.clock_rate).min(265.0) / 265.0).powf(1.5);
+        }
+
+        self.last_player_position = Some(player_position);
+        self.last_distance_moved = distance_moved;
+        self.last_strain_time = current.strain_time;
+
+        distance_addition / weighted_strain_time
+    }
+}
+
+impl StrainSkill<DifficultyObject<'_>> for Movement {
+    fn strain_value_at(&mut self, objects: &[DifficultyObject], index: usize) -> f64 {
+        self.current_strain *= strain_decay(Self::STRAIN_DECAY_BASE, objects[index].delta_time);
+        self.current_strain += self.strain_of(&objects[index]) * Self::SKILL_MULTIPLIER;
+
+        self.current_strain
+    }
+
+    fn initial_strain(&self, time: f64, objects: &[DifficultyObject], index: usize) -> f64 {
+        self.current_strain
+            * strain_decay(
+                Self::STRAIN_DECAY_BASE,
+                time - objects[index - 1].start_time,
+            )
+    }
+}
+
+/// Computes the osu!catch difficulty of the beatmap with the `mods`.
+pub(crate) fn calculate(osu_file: &OsuFile, mods: &Mods) -> CatchDifficultyAttributes {
+    let difficulty = mods.difficulty(
+        osu_file.difficulty.as_ref().unwrap_or(&EMPTY_DIFFICULTY),
+        Mode::Catch,
+    );
+    let values = difficulty.values();
+    let clock_rate = mods.clock_rate();
+    let catch_difficulty = values.catch();
+    let preempt = catch_difficulty.preempt / clock_rate;
+
+    let mut attributes = CatchDifficultyAttributes {
+        approach_rate: approach_rate_from_preempt(preempt),
+        ..Default::default()
+    };
+
+    let mut objects = palpable_objects(osu_file, &difficulty, mods.hard_rock, &mut attributes);
+    attributes.max_combo = attributes.fruit_count + attributes.droplet_count;
+
+    if objects.is_empty() {
+        return attributes;
+    }
+
+    initialise_hyper_dash(
+        &mut objects,
+        catch_difficulty.catcher_width / ALLOWED_CATCH_RANGE / 2.0,
+    );
+
+    // the catcher is made smaller for high circle sizes, since the fruits are harder to catch perfectly
+    let half_catcher_width =
+        catch_difficulty.catcher_width / 2.0 * (1.0 - (values.circle_size - 5.5).max(0.0) * 0.0625);
+    let scaling_factor = NORMALISED_HITOBJECT_RADIUS / half_catcher_width;
+
+    let difficulty_objects = objects
+        .windows(2)
+        .map(|objects| {
+            let delta_time = (objects[1].start_time - objects[0].start_time) / clock_rate;
+
+            DifficultyObject {
+                last: &objects[0],
+                start_time: objects[1].start_time / clock_rate,
+                delta_time,
+                // capped at 375 BPM streams
+                strain_time: delta_time.max(40.0),
+                normalised_position: objects[1].x * scaling_factor,
+                last_normalised_position: objects[0].x * scaling_factor,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut movement = Movement {
+        clock_rate,
+        current_strain: 0.0,
+        last_player_position: None,
+        last_distance_moved: 0.0,
+        last_strain_time: 0.0,
+    };
+    let mut peaks = StrainPeaks::with_section_length(SECTION_LENGTH);
+
+    for (i, object) in difficulty_objects.iter().enumerate() {
+        peaks.process(&mut movement, &difficulty_objects, i, object.start_time);
+    }
+
+    attributes.star_rating = weighted_sum(peaks.peaks(), DECAY_WEIGHT).sqrt() * STAR_SCALING_FACTOR;
+
+    attributes
+}
@@ -0,0 +1,222 @@
+//! Difficulty calculation of osu!mania.
+
+use super::{weighted_sum, StrainPeaks, StrainSkill};
+use crate::osu_file::{
+    difficulty::EMPTY_DIFFICULTY,
+    general::Mode,
+    hitobjects::{HitObjectParams, Vec2, EMPTY_HITOBJECTS},
+    Mods, OsuFile,
+};
+
+const DIFFICULTY_MULTIPLIER: f64 = 0.018;
+const INDIVIDUAL_DECAY_BASE: f64 = 0.125;
+const OVERALL_DECAY_BASE: f64 = 0.3;
+/// Time between the releases of holds where releasing them together stops being easier, in milliseconds.
+const RELEASE_THRESHOLD: f64 = 24.0;
+/// Weight of each strain peak compared to the next highest one.
+const DECAY_WEIGHT: f64 = 0.9;
+
+/// Difficulty of an osu!mania beatmap, with the attributes needed for the performance points.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ManiaDifficultyAttributes {
+    pub star_rating: f64,
+    /// Hit window for a 300 in milliseconds, adjusted by the mods like osu!stable does.
+    pub great_hit_window: f64,
+    /// Highest combo that can be reached, where holds give combo every 100ms.
+    pub max_combo: usize,
+    pub note_count: usize,
+    pub hold_note_count: usize,
+}
+
+impl OsuFile {
+    /// Computes the star rating and difficulty attributes of an osu!mania beatmap, like osu! does for stable.
+    /// - The amount of columns is the circle size.
+    /// - Returns `None` if the beatmap's mode isn't osu!mania, since converted beatmaps aren't supported.
+    pub fn mania_difficulty(&self) -> Option<ManiaDifficultyAttributes> {
+        match self.mode() {
+            Mode::Mania => Some(calculate(self, &Mods::default())),
+            _ => None,
+        }
+    }
+}
+
+/// A note with the time since the previous note, for the difficulty calculation.
+struct DifficultyObject {
+    start_time: f64,
+    end_time: f64,
+    delta_time: f64,
+    column: usize,
+}
+
+/// Difficulty of the notes in each column and all of them together.
+struct Strain {
+    start_times: Vec<f64>,
+    end_times: Vec<f64>,
+    individual_strains: Vec<f64>,
+    individual_strain: f64,
+    overall_strain: f64,
+}
+
+impl Strain {
+    fn new(columns: usize) -> Self {
+        Self {
+            start_times: vec![0.0; columns],
+            end_times: vec![0.0; columns],
+            individual_strains: vec![0.0; columns],
+            individual_strain: 0.0,
+            overall_strain: 1.0,
+        }
+    }
+}
+
+fn apply_decay(value: f64, delta_time: f64, decay_base: f64) -> f64 {
+    value * decay_base.powf(delta_time / 1000.0)
+}
+
+impl StrainSkill<DifficultyObject> for Strain {
+    fn strain_value_at(&mut self, objects: &[DifficultyObject], index: usize) -> f64 {
+        let current = &objects[index];
+        let start_time = current.start_time;
+        let end_time = current.end_time;
+        let column = current.column;
+
+        let mut is_overlapping = false;
+        // shortest time to the release of another note
+        let mut closest_end_time = (end_time - start_time).abs();
+        // everything is harder while something else is held
+        let mut hold_factor = 1.0;
+        // holds released awkwardly are harder
+        let mut hold_addition = 0.0;
+
+        for &other_end_time in &self.end_times {
+            is_overlapping |= other_end_time - 1.0 > start_time && end_time - 1.0 > other_end_time;
+
+            if other_end_time - 1.0 > end_time {
+                hold_factor = 1.25;
+            }
+
+            closest_end_time = closest_end_time.min((end_time - other_end_time).abs());
+        }
+
+        // releasing notes together is just as easy as releasing one of them
+        if is_overlapping {
+            hold_addition = 1.0 / (1.0 + (0.5 * (RELEASE_THRESHOLD - closest_end_time)).exp());
+        }
+
+        self.individual_strains[column] = apply_decay(
+            self.individual_strains[column],
+            start_time - self.start_times[column],
+            INDIVIDUAL_DECAY_BASE,
+        );
+        self.individual_strains[column] += 2.0 * hold_factor;
+
+        // chords use the hardest column out of the notes
+        self.individual_strain = if current.delta_time <= 1.0 {
+            self.individual_strain.max(self.individual_strains[column])
+        } else {
+            self.individual_strains[column]
+        };
+
+        self.overall_strain =
+            apply_decay(self.overall_strain, current.delta_time, OVERALL_DECAY_BASE);
+        self.overall_strain += (1.0 + hold_addition) * hold_factor;
+
+        self.start_times[column] = start_time;
+        self.end_times[column] = end_time;
+
+        self.individual_strain + self.overall_strain
+    }
+
+    fn initial_strain(&self, time: f64, objects: &[DifficultyObject], index: usize) -> f64 {
+        let delta_time = time - objects[index - 1].start_time;
+
+        apply_decay(self.individual_strain, delta_time, INDIVIDUAL_DECAY_BASE)
+            + apply_decay(self.overall_strain, delta_time, OVERALL_DECAY_BASE)
+    }
+}
+
+/// Computes the osu!mania difficulty of the beatmap with the `mods`.
+/// - Hard rock and easy only change the hit window, since the notes are the same.
+pub(crate) fn calculate(osu_file: &OsuFile, mods: &Mods) -> ManiaDifficultyAttributes {
+    let values = osu_file
+        .difficulty
+        .as_ref()
+        .unwrap_or(&EMPTY_DIFFICULTY)
+        .values();
+    let clock_rate = mods.clock_rate();
+    let hitobjects = osu_file.hitobjects.as_ref().unwrap_or(&EMPTY_HITOBJECTS);
+    let columns = (values.circle_size.round() as usize).max(1);
+
+    let mut hit_window = 34.0 + 3.0 * (10.0 - values.overall_difficulty).clamp(0.0, 10.0);
+    if mods.hard_rock {
+        hit_window /= 1.4;
+    } else if mods.easy {
+        hit_window *= 1.4;
+    }
+    // osu! scales the hit window by the speed of the mods again on top of the clock rate
+    hit_window *= clock_rate;
+    let mut attributes = ManiaDifficultyAttributes {
+        great_hit_window: ((hit_window * clock_rate).trunc() / clock_rate).ceil(),
+        ..Default::default()
+    };
+
+    if hitobjects.0.is_empty() {
+        return attributes;
+    }
+
+    let mut notes = hitobjects
+        .0
+        .iter()
+        .map(|hitobject| {
+            let start_time = hitobject.start_time().unwrap_or_default();
+            let end_time = match &hitobject.obj_params {
+                HitObjectParams::OsuManiaHold { end_time } => {
+                    attributes.hold_note_count += 1;
+                    let end_time = end_time.to_f64().unwrap_or(start_time);
+                    attributes.max_combo += 1 + ((end_time - start_time) / 100.0) as usize;
+                    end_time
+                }
+                _ => {
+                    attributes.note_count += 1;
+                    attributes.max_combo += 1;
+                    start_time
+                }
+            };
+            let x = Vec2::from_position(&hitobject.position)
+                .unwrap_or_default()
+                .x;
+            let column = ((x * columns as f64 / 512.0).floor().max(0.0) as usize).min(columns - 1);
+
+            (start_time, end_time, column)
+        })
+        .collect::<Vec<_>>();
+
+    // osu! sorts the notes by their rounded start times
+    notes.sort_by_key(|(start_time, _, _)| start_time.round() as i64);
+
+    let objects = notes
+        .windows(2)
+        .map(|notes| {
+            let (start_time, end_time, column) = notes[1];
+
+            DifficultyObject {
+                start_time: start_time / clock_rate,
+                end_time: end_time / clock_rate,
+                delta_time: (start_time - notes[0].0) / clock_rate,
+                column,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut strain = Strain::new(columns);
+    let mut peaks = StrainPeaks::new();
+
+    for (i, object) in objects.iter().enumerate() {
+        peaks.process(&mut strain, &objects, i, object.start_time);
+    }
+
+    attributes.star_rating = weighted_sum(peaks.peaks(), DECAY_WEIGHT) * DIFFICULTY_MULTIPLIER;
+
+    attributes
+}
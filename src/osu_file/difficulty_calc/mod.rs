@@ -0,0 +1,98 @@
+//! Difficulty calculation of beatmaps, giving the star rating and the attributes used for performance points.
+//! - Follows the difficulty calculators of osu! for beatmaps played on stable.
+
+pub mod catch;
+pub mod mania;
+pub mod osu;
+pub mod taiko;
+
+pub use catch::CatchDifficultyAttributes;
+pub use mania::ManiaDifficultyAttributes;
+pub use osu::OsuDifficultyAttributes;
+pub use taiko::TaikoDifficultyAttributes;
+
+/// Default length of a section of the strain in milliseconds, where the highest strain of each section is used for the difficulty.
+const SECTION_LENGTH: f64 = 400.0;
+
+/// A skill that builds up strain from each difficulty object, like the strain skills of osu!.
+pub(crate) trait StrainSkill<T> {
+    /// Processes the object at the `index` of the `objects`, returning the strain at the object.
+    fn strain_value_at(&mut self, objects: &[T], index: usize) -> f64;
+
+    /// Strain at the `time` before the object at the `index` is processed, used as the start of a new section.
+    fn initial_strain(&self, time: f64, objects: &[T], index: usize) -> f64;
+}
+
+/// Highest strain of each section of a [`StrainSkill`].
+pub(crate) struct StrainPeaks {
+    section_length: f64,
+    section_end: f64,
+    current_peak: f64,
+    peaks: Vec<f64>,
+}
+
+impl StrainPeaks {
+    pub fn new() -> Self {
+        Self::with_section_length(SECTION_LENGTH)
+    }
+
+    pub fn with_section_length(section_length: f64) -> Self {
+        Self {
+            section_length,
+            section_end: 0.0,
+            current_peak: 0.0,
+            peaks: Vec::new(),
+        }
+    }
+
+    /// Processes the object at the `index` of the `objects` with the `skill`, starting new sections up to its `start_time`.
+    pub fn process<T, S: StrainSkill<T>>(
+        &mut self,
+        skill: &mut S,
+        objects: &[T],
+        index: usize,
+        start_time: f64,
+    ) {
+        // the first object doesn't generate strain, so it begins with the section after it
+        if index == 0 {
+            self.section_end = (start_time / self.section_length).ceil() * self.section_length;
+        }
+
+        while start_time > self.section_end {
+            self.peaks.push(self.current_peak);
+            self.current_peak = skill.initial_strain(self.section_end, objects, index);
+            self.section_end += self.section_length;
+        }
+
+        self.current_peak = skill.strain_value_at(objects, index).max(self.current_peak);
+    }
+
+    /// Peaks of all the sections, including the current one.
+    pub fn peaks(&self) -> Vec<f64> {
+        self.peaks
+            .iter()
+            .copied()
+            .chain(std::iter::once(self.current_peak))
+            .collect()
+    }
+}
+
+/// Strain decaying exponentially with the `decay_base` as the amount left after a second.
+pub(crate) fn strain_decay(decay_base: f64, ms: f64) -> f64 {
+    decay_base.powf(ms / 1000.0)
+}
+
+/// Sum of the `strains` from the highest, with each one weighted by `decay_weight` times the previous weight.
+pub(crate) fn weighted_sum(mut strains: Vec<f64>, decay_weight: f64) -> f64 {
+    strains.sort_by(|a, b| b.total_cmp(a));
+
+    let mut weight = 1.0;
+    let mut sum = 0.0;
+
+    for strain in strains {
+        sum += strain * weight;
+        weight *= decay_weight;
+    }
+
+    sum
+}
@@ -0,0 +1,386 @@
+//! Difficulty of each hitobject for the osu!standard skills.
+
+use std::f64::consts::PI;
+
+use super::object::{DifficultyObject, OsuObjectKind, NORMALISED_DIAMETER, NORMALISED_RADIUS};
+use crate::osu_file::difficulty::OsuDifficulty;
+
+const WIDE_ANGLE_MULTIPLIER: f64 = 1.5;
+const ACUTE_ANGLE_MULTIPLIER: f64 = 1.95;
+const SLIDER_MULTIPLIER: f64 = 1.35;
+const VELOCITY_CHANGE_MULTIPLIER: f64 = 0.75;
+
+const SINGLE_SPACING_THRESHOLD: f64 = 125.0;
+/// Strain time where the speed bonus starts, which is about 200 BPM 1/4.
+const MIN_SPEED_BONUS: f64 = 75.0;
+const SPEED_BALANCING_FACTOR: f64 = 40.0;
+
+/// How far back the rhythm is looked at, in milliseconds.
+const HISTORY_TIME_MAX: f64 = 5000.0;
+const RHYTHM_MULTIPLIER: f64 = 0.75;
+
+const MAX_OPACITY_BONUS: f64 = 0.4;
+const HIDDEN_BONUS: f64 = 0.2;
+const MIN_VELOCITY: f64 = 0.5;
+const FLASHLIGHT_SLIDER_MULTIPLIER: f64 = 1.3;
+const MIN_ANGLE_MULTIPLIER: f64 = 0.2;
+
+/// Object `i` objects before the one at the `index`.
+fn previous<'a, 'b>(
+    objects: &'b [DifficultyObject<'a>],
+    index: usize,
+    i: usize,
+) -> Option<&'b DifficultyObject<'a>> {
+    index.checked_sub(i + 1).map(|index| &objects[index])
+}
+
+fn wide_angle_bonus(angle: f64) -> f64 {
+    (3.0 / 4.0 * (angle.clamp(PI / 6.0, 5.0 / 6.0 * PI) - PI / 6.0))
+        .sin()
+        .powi(2)
+}
+
+fn acute_angle_bonus(angle: f64) -> f64 {
+    1.0 - wide_angle_bonus(angle)
+}
+
+/// Difficulty of aiming the hitobject, from the distances and angles of the jumps to it.
+/// - Slider bodies are only included `with_sliders`.
+pub(super) fn aim(objects: &[DifficultyObject], index: usize, with_sliders: bool) -> f64 {
+    let current = &objects[index];
+
+    if current.base.is_spinner() || index <= 1 || objects[index - 1].base.is_spinner() {
+        return 0.0;
+    }
+
+    let last = &objects[index - 1];
+    let last_last = &objects[index - 2];
+
+    // velocity to the current hitobject, going through the last slider if it is one
+    let mut curr_velocity = current.lazy_jump_distance / current.strain_time;
+
+    if last.base.is_slider() && with_sliders {
+        let travel_velocity = last.travel_distance / last.travel_time;
+        let movement_velocity = current.minimum_jump_distance / current.minimum_jump_time;
+        curr_velocity = curr_velocity.max(movement_velocity + travel_velocity);
+    }
+
+    let mut prev_velocity = last.lazy_jump_distance / last.strain_time;
+
+    if last_last.base.is_slider() && with_sliders {
+        let travel_velocity = last_last.travel_distance / last_last.travel_time;
+        let movement_velocity = last.minimum_jump_distance / last.minimum_jump_time;
+        prev_velocity = prev_velocity.max(movement_velocity + travel_velocity);
+    }
+
+    let mut wide_bonus = 0.0;
+    let mut acute_bonus = 0.0;
+    let mut slider_bonus = 0.0;
+    let mut velocity_change_bonus = 0.0;
+
+    let mut aim_strain = curr_velocity;
+
+    // only the same rhythms are rewarded for angles
+    if current.strain_time.max(last.strain_time) < 1.25 * current.strain_time.min(last.strain_time)
+    {
+        if let (Some(curr_angle), Some(last_angle), Some(last_last_angle)) =
+            (current.angle, last.angle, last_last.angle)
+        {
+            let angle_bonus = curr_velocity.min(prev_velocity);
+
+            wide_bonus = wide_angle_bonus(curr_angle);
+            acute_bonus = acute_angle_bonus(curr_angle);
+
+            // only buffs delta times above 300 BPM 1/2
+            if current.strain_time > 100.0 {
+                acute_bonus = 0.0;
+            } else {
+                acute_bonus *= acute_angle_bonus(last_angle)
+                    * angle_bonus.min(NORMALISED_DIAMETER / current.strain_time)
+                    * (PI / 2.0 * ((100.0 - current.strain_time) / 25.0).min(1.0))
+                        .sin()
+                        .powi(2)
+                    * (PI / 2.0
+                        * (current
+                            .lazy_jump_distance
+                            .clamp(NORMALISED_RADIUS, NORMALISED_DIAMETER)
+                            - NORMALISED_RADIUS)
+                        / NORMALISED_RADIUS)
+                        .sin()
+                        .powi(2);
+            }
+
+            // repeated angles are penalised
+            wide_bonus *=
+                angle_bonus * (1.0 - wide_bonus.min(wide_angle_bonus(last_angle).powi(3)));
+            acute_bonus *=
+                0.5 + 0.5 * (1.0 - acute_bonus.min(acute_angle_bonus(last_last_angle).powi(3)));
+        }
+    }
+
+    if prev_velocity.max(curr_velocity) != 0.0 {
+        // the average velocity over the whole hitobject is used for the changes
+        prev_velocity = (last.lazy_jump_distance + last_last.travel_distance) / last.strain_time;
+        curr_velocity = (current.lazy_jump_distance + last.travel_distance) / current.strain_time;
+
+        let dist_ratio = (PI / 2.0 * (prev_velocity - curr_velocity).abs()
+            / prev_velocity.max(curr_velocity))
+        .sin()
+        .powi(2);
+        let overlap_velocity_buff = (NORMALISED_DIAMETER
+            / current.strain_time.min(last.strain_time))
+        .min((prev_velocity - curr_velocity).abs());
+
+        velocity_change_bonus = overlap_velocity_buff * dist_ratio;
+        // rhythm changes are penalised
+        velocity_change_bonus *= (current.strain_time.min(last.strain_time)
+            / current.strain_time.max(last.strain_time))
+        .powi(2);
+    }
+
+    if last.base.is_slider() {
+        slider_bonus = last.travel_distance / last.travel_time;
+    }
+
+    aim_strain += (acute_bonus * ACUTE_ANGLE_MULTIPLIER).max(
+        wide_bonus * WIDE_ANGLE_MULTIPLIER + velocity_change_bonus * VELOCITY_CHANGE_MULTIPLIER,
+    );
+
+    if with_sliders {
+        aim_strain += slider_bonus * SLIDER_MULTIPLIER;
+    }
+
+    aim_strain
+}
+
+/// Difficulty of tapping the hitobject, from the time and distance from the previous hitobject.
+pub(super) fn speed(objects: &[DifficultyObject], index: usize) -> f64 {
+    let current = &objects[index];
+
+    if current.base.is_spinner() {
+        return 0.0;
+    }
+
+    let prev = previous(objects, index, 0);
+    let next = objects.get(index + 1);
+
+    let mut strain_time = current.strain_time;
+    let mut doubletapness = 1.0;
+
+    // doubles that can be doubletapped are nerfed
+    if let Some(next) = next {
+        let curr_delta_time = current.delta_time.max(1.0);
+        let next_delta_time = next.delta_time.max(1.0);
+        let delta_difference = (next_delta_time - curr_delta_time).abs();
+        let speed_ratio = curr_delta_time / curr_delta_time.max(delta_difference);
+        let window_ratio = (curr_delta_time / current.hit_window_great)
+            .min(1.0)
+            .powi(2);
+        doubletapness = speed_ratio.powf(1.0 - window_ratio);
+    }
+
+    // the delta time is capped to the hit window
+    strain_time /= (strain_time / current.hit_window_great / 0.93).clamp(0.92, 1.0);
+
+    let mut speed_bonus = 1.0;
+    if strain_time < MIN_SPEED_BONUS {
+        speed_bonus += 0.75 * ((MIN_SPEED_BONUS - strain_time) / SPEED_BALANCING_FACTOR).powi(2);
+    }
+
+    let travel_distance = prev.map_or(0.0, |prev| prev.travel_distance);
+    let distance = SINGLE_SPACING_THRESHOLD.min(travel_distance + current.minimum_jump_distance);
+
+    (speed_bonus + speed_bonus * (distance / SINGLE_SPACING_THRESHOLD).powf(3.5)) * doubletapness
+        / strain_time
+}
+
+/// Multiplier of the speed strain for complex rhythms of the previous hitobjects, from `1`.
+pub(super) fn rhythm(objects: &[DifficultyObject], index: usize) -> f64 {
+    let current = &objects[index];
+
+    if current.base.is_spinner() {
+        return 0.0;
+    }
+
+    let mut previous_island_size = 0;
+    let mut rhythm_complexity_sum = 0.0;
+    let mut island_size = 1;
+    // ratio of the start of the current island, to buff tighter rhythms
+    let mut start_ratio = 0.0;
+    let mut first_delta_switch = false;
+
+    let historical_note_count = index.min(32);
+    let mut rhythm_start = 0;
+
+    while rhythm_start + 2 < historical_note_count
+        && current.start_time - objects[index - (rhythm_start + 1)].start_time < HISTORY_TIME_MAX
+    {
+        rhythm_start += 1;
+    }
+
+    for i in (1..=rhythm_start).rev() {
+        let curr = &objects[index - i];
+        let prev = &objects[index - (i + 1)];
+        let last = &objects[index - (i + 2)];
+
+        // scales from 0 to 1, from the start of the history to now
+        let curr_historical_decay = ((HISTORY_TIME_MAX - (current.start_time - curr.start_time))
+            / HISTORY_TIME_MAX)
+            .min((historical_note_count - i) as f64 / historical_note_count as f64);
+
+        let curr_delta = curr.strain_time;
+        let prev_delta = prev.strain_time;
+        let last_delta = last.strain_time;
+        let curr_ratio = 1.0
+            + 6.0
+                * (PI / (prev_delta.min(curr_delta) / prev_delta.max(curr_delta)))
+                    .sin()
+                    .powi(2)
+                    .min(0.5);
+
+        let window_penalty = (((prev_delta - curr_delta).abs() - curr.hit_window_great * 0.3)
+            .max(0.0)
+            / (curr.hit_window_great * 0.3))
+            .min(1.0);
+
+        let mut effective_ratio = window_penalty * curr_ratio;
+
+        if first_delta_switch {
+            if !(prev_delta > 1.25 * curr_delta || prev_delta * 1.25 < curr_delta) {
+                // the island is still going
+                if island_size < 7 {
+                    island_size += 1;
+                }
+            } else {
+                // changing into a slider is easier
+                if curr.base.is_slider() {
+                    effective_ratio *= 0.125;
+                }
+                // changing from a slider is easier
+                if prev.base.is_slider() {
+                    effective_ratio *= 0.25;
+                }
+                // repeated island size
+                if previous_island_size == island_size {
+                    effective_ratio *= 0.25;
+                }
+                // repeated island polarity
+                if previous_island_size % 2 == island_size % 2 {
+                    effective_ratio *= 0.5;
+                }
+                // the previous increase was a note ago, like 1/1 -> 1/2 -> 1/4
+                if last_delta > prev_delta + 10.0 && prev_delta > curr_delta + 10.0 {
+                    effective_ratio *= 0.125;
+                }
+
+                rhythm_complexity_sum += (effective_ratio * start_ratio).sqrt()
+                    * curr_historical_decay
+                    * ((4 + island_size) as f64).sqrt()
+                    / 2.0
+                    * ((4 + previous_island_size) as f64).sqrt()
+                    / 2.0;
+
+                start_ratio = effective_ratio;
+                previous_island_size = island_size;
+
+                // slowing down stops the counting
+                if prev_delta * 1.25 < curr_delta {
+                    first_delta_switch = false;
+                }
+
+                island_size = 1;
+            }
+        } else if prev_delta > 1.25 * curr_delta {
+            // speeding up starts counting the island
+            first_delta_switch = true;
+            start_ratio = effective_ratio;
+            island_size = 1;
+        }
+    }
+
+    (4.0 + rhythm_complexity_sum * RHYTHM_MULTIPLIER).sqrt() / 2.0
+}
+
+/// Difficulty of reading the hitobject with flashlight, from the positions and visibility of the previous hitobjects.
+pub(super) fn flashlight(
+    objects: &[DifficultyObject],
+    index: usize,
+    hidden: bool,
+    osu_difficulty: &OsuDifficulty,
+) -> f64 {
+    let current = &objects[index];
+
+    if current.base.is_spinner() {
+        return 0.0;
+    }
+
+    let scaling_factor = 52.0 / osu_difficulty.circle_radius;
+    let mut small_dist_nerf = 1.0;
+    let mut cumulative_strain_time = 0.0;
+    let mut result = 0.0;
+    let mut last = current;
+    let mut angle_repeat_count = 0.0;
+
+    for i in 0..index.min(10) {
+        let object = &objects[index - (i + 1)];
+
+        if !object.base.is_spinner() {
+            let jump_distance =
+                (current.base.stacked_position - object.base.stacked_end_position).length();
+            cumulative_strain_time += last.strain_time;
+
+            // hitobjects that can be seen within the flashlight are nerfed
+            if i == 0 {
+                small_dist_nerf = (jump_distance / 75.0).min(1.0);
+            }
+
+            // only the first hitobject of a stack is counted
+            let stack_nerf = ((object.lazy_jump_distance / scaling_factor) / 25.0).min(1.0);
+
+            let opacity_bonus = 1.0
+                + MAX_OPACITY_BONUS
+                    * (1.0 - current.opacity_at(object.base.start_time, hidden, osu_difficulty));
+
+            result += stack_nerf * opacity_bonus * scaling_factor * jump_distance
+                / cumulative_strain_time;
+
+            if let (Some(object_angle), Some(current_angle)) = (object.angle, current.angle) {
+                // hitobjects further back count less
+                if (object_angle - current_angle).abs() < 0.02 {
+                    angle_repeat_count += (1.0 - 0.1 * i as f64).max(0.0);
+                }
+            }
+        }
+
+        last = object;
+    }
+
+    result = (small_dist_nerf * result).powi(2);
+
+    if hidden {
+        result *= 1.0 + HIDDEN_BONUS;
+    }
+
+    // repeated angles are nerfed
+    result *= MIN_ANGLE_MULTIPLIER + (1.0 - MIN_ANGLE_MULTIPLIER) / (angle_repeat_count + 1.0);
+
+    let mut slider_bonus = 0.0;
+
+    if let OsuObjectKind::Slider(slider) = &current.base.kind {
+        // the travel distance without the scaling of the circle size
+        let pixel_travel_distance = slider.lazy_travel_distance / scaling_factor;
+
+        slider_bonus = (pixel_travel_distance / current.travel_time - MIN_VELOCITY)
+            .max(0.0)
+            .powf(0.5);
+        // longer sliders need more memorisation
+        slider_bonus *= pixel_travel_distance;
+
+        // repeats need less memorisation
+        if slider.repeat_count > 0 {
+            slider_bonus /= (slider.repeat_count + 1) as f64;
+        }
+    }
+
+    result + slider_bonus * FLASHLIGHT_SLIDER_MULTIPLIER
+}
@@ -0,0 +1,220 @@
+//! Difficulty calculation of osu!standard.
+
+mod evaluators;
+mod object;
+mod skills;
+
+use object::{DifficultyObject, OsuObject, OsuObjectKind};
+use skills::{Aim, Flashlight, Speed};
+
+use super::StrainPeaks;
+use crate::osu_file::{
+    difficulty::{approach_rate_from_preempt, difficulty_value, EMPTY_DIFFICULTY},
+    general::Mode,
+    hitobjects::EMPTY_HITOBJECTS,
+    timingpoints::EMPTY_TIMING_POINTS,
+    Mods, OsuFile,
+};
+
+/// Multiplier of the difficulty values of the skills.
+const DIFFICULTY_MULTIPLIER: f64 = 0.0675;
+/// Base multiplier of the performance points, which the star rating is scaled with.
+pub(crate) const PERFORMANCE_BASE_MULTIPLIER: f64 = 1.14;
+/// Stack leniency used when the general section doesn't have one.
+const DEFAULT_STACK_LENIENCY: f64 = 0.7;
+
+/// Difficulty of an osu!standard beatmap, with the attributes needed for the performance points.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OsuDifficultyAttributes {
+    pub star_rating: f64,
+    /// Difficulty of aiming, including the slider bodies.
+    pub aim_difficulty: f64,
+    /// Difficulty of tapping.
+    pub speed_difficulty: f64,
+    /// Amount of notes that are relevant to the speed difficulty.
+    pub speed_note_count: f64,
+    /// Difficulty of reading with the flashlight mod.
+    /// - Only included in the star rating with [`Mods::flashlight`].
+    pub flashlight_difficulty: f64,
+    /// Aim difficulty without the slider bodies compared to with them, being `1` if the sliders don't add to the difficulty.
+    pub slider_factor: f64,
+    pub approach_rate: f64,
+    pub overall_difficulty: f64,
+    pub drain_rate: f64,
+    /// Highest combo that can be reached.
+    pub max_combo: usize,
+    pub hit_circle_count: usize,
+    pub slider_count: usize,
+    pub spinner_count: usize,
+}
+
+impl OsuFile {
+    /// Computes the star rating and difficulty attributes of an osu!standard beatmap, like osu! does for stable.
+    /// - Returns `None` if the beatmap's mode isn't osu!standard.
+    pub fn osu_difficulty(&self) -> Option<OsuDifficultyAttributes> {
+        match self.mode() {
+            Mode::Osu => Some(calculate(self, &Mods::default())),
+            _ => None,
+        }
+    }
+}
+
+/// Computes the osu!standard difficulty of the beatmap with the `mods`.
+pub(crate) fn calculate(osu_file: &OsuFile, mods: &Mods) -> OsuDifficultyAttributes {
+    let difficulty = mods.difficulty(
+        osu_file.difficulty.as_ref().unwrap_or(&EMPTY_DIFFICULTY),
+        Mode::Osu,
+    );
+    let clock_rate = mods.clock_rate();
+    let values = difficulty.values();
+    let osu_difficulty = values.osu();
+    let hitobjects = osu_file.hitobjects.as_ref().unwrap_or(&EMPTY_HITOBJECTS);
+    let timing_points = osu_file
+        .timing_points
+        .as_ref()
+        .unwrap_or(&EMPTY_TIMING_POINTS);
+    let stack_leniency = osu_file
+        .general
+        .as_ref()
+        .map_or(DEFAULT_STACK_LENIENCY, |general| {
+            difficulty_value(&general.stack_leniency, DEFAULT_STACK_LENIENCY)
+        });
+
+    let timing_index = timing_points.index();
+    let stacking =
+        hitobjects.stacking(timing_points, &difficulty, stack_leniency, osu_file.version);
+    let objects = OsuObject::from_hitobjects(
+        hitobjects,
+        &stacking,
+        timing_index.as_ref(),
+        &difficulty,
+        &osu_difficulty,
+        osu_file.version,
+    );
+
+    // the first hitobject has no difficulty, since there's nothing to move from
+    let difficulty_objects = (1..objects.len())
+        .map(|i| {
+            DifficultyObject::new(
+                &objects[i],
+                &objects[i - 1],
+                i.checked_sub(2).map(|i| &objects[i]),
+                clock_rate,
+                &osu_difficulty,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut aim = Aim::new(true);
+    let mut aim_no_sliders = Aim::new(false);
+    let mut speed = Speed::new();
+    let mut flashlight = Flashlight::new(mods.hidden, &osu_difficulty);
+    let mut peaks = [
+        StrainPeaks::new(),
+        StrainPeaks::new(),
+        StrainPeaks::new(),
+        StrainPeaks::new(),
+    ];
+
+    for (i, object) in difficulty_objects.iter().enumerate() {
+        let time = object.start_time;
+
+        peaks[0].process(&mut aim, &difficulty_objects, i, time);
+        peaks[1].process(&mut aim_no_sliders, &difficulty_objects, i, time);
+        peaks[2].process(&mut speed, &difficulty_objects, i, time);
+        peaks[3].process(&mut flashlight, &difficulty_objects, i, time);
+    }
+
+    let mut aim_rating = Aim::difficulty_value(peaks[0].peaks()).sqrt() * DIFFICULTY_MULTIPLIER;
+    let aim_rating_no_sliders =
+        Aim::difficulty_value(peaks[1].peaks()).sqrt() * DIFFICULTY_MULTIPLIER;
+    let mut speed_rating = Speed::difficulty_value(peaks[2].peaks()).sqrt() * DIFFICULTY_MULTIPLIER;
+    let mut flashlight_rating =
+        Flashlight::difficulty_value(peaks[3].peaks()).sqrt() * DIFFICULTY_MULTIPLIER;
+
+    let slider_factor = if aim_rating > 0.0 {
+        aim_rating_no_sliders / aim_rating
+    } else {
+        1.0
+    };
+
+    if mods.touch_device {
+        aim_rating = aim_rating.powf(0.8);
+        flashlight_rating = flashlight_rating.powf(0.8);
+    }
+    // there's no tapping with relax
+    if mods.relax {
+        aim_rating *= 0.9;
+        speed_rating = 0.0;
+        flashlight_rating *= 0.7;
+    }
+
+    let base_aim_performance = base_performance(aim_rating);
+    let base_speed_performance = base_performance(speed_rating);
+    let base_flashlight_performance = if mods.flashlight {
+        flashlight_performance(flashlight_rating)
+    } else {
+        0.0
+    };
+    let base_performance = (base_aim_performance.powf(1.1)
+        + base_speed_performance.powf(1.1)
+        + base_flashlight_performance.powf(1.1))
+    .powf(1.0 / 1.1);
+
+    // beatmaps without hitobjects have no difficulty at all
+    let star_rating = if objects.is_empty() {
+        0.0
+    } else if base_performance > 0.00001 {
+        PERFORMANCE_BASE_MULTIPLIER.cbrt()
+            * 0.027
+            * ((100000.0 / 2f64.powf(1.0 / 1.1) * base_performance).cbrt() + 4.0)
+    } else {
+        0.0
+    };
+
+    let preempt = osu_difficulty.preempt / clock_rate;
+    let hit_window_great = osu_difficulty.hit_window_300 / clock_rate;
+
+    let mut attributes = OsuDifficultyAttributes {
+        star_rating,
+        aim_difficulty: aim_rating,
+        speed_difficulty: speed_rating,
+        speed_note_count: speed.relevant_note_count(),
+        flashlight_difficulty: flashlight_rating,
+        slider_factor,
+        approach_rate: approach_rate_from_preempt(preempt),
+        overall_difficulty: (80.0 - hit_window_great) / 6.0,
+        drain_rate: values.hp_drain_rate,
+        ..Default::default()
+    };
+
+    for object in &objects {
+        match &object.kind {
+            OsuObjectKind::Circle => {
+                attributes.hit_circle_count += 1;
+                attributes.max_combo += 1;
+            }
+            OsuObjectKind::Slider(slider) => {
+                attributes.slider_count += 1;
+                attributes.max_combo += slider.combo;
+            }
+            OsuObjectKind::Spinner => {
+                attributes.spinner_count += 1;
+                attributes.max_combo += 1;
+            }
+        }
+    }
+
+    attributes
+}
+
+/// Performance points of a skill from its rating, before the multipliers of the score.
+pub(crate) fn base_performance(rating: f64) -> f64 {
+    (5.0 * (rating / 0.0675).max(1.0) - 4.0).powi(3) / 100000.0
+}
+
+/// Performance points of the flashlight skill from its rating, before the multipliers of the score.
+pub(crate) fn flashlight_performance(rating: f64) -> f64 {
+    rating.powi(2) * 25.0
+}
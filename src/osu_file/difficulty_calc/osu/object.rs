@@ -0,0 +1,339 @@
+//! Hitobjects prepared for the osu!standard difficulty calculation.
+
+use crate::osu_file::{
+    difficulty::OsuDifficulty,
+    hitobjects::{HitObjectParams, NestedObjectKind, SliderPath, StackedObject, Vec2},
+    timingpoints::TimingIndex,
+    Difficulty, HitObjects, Version,
+};
+
+/// Radius the distances are scaled to, so the circle size doesn't change them.
+pub(super) const NORMALISED_RADIUS: f64 = 50.0;
+pub(super) const NORMALISED_DIAMETER: f64 = NORMALISED_RADIUS * 2.0;
+/// Shortest strain time, so hitobjects at the same time don't break the calculation.
+pub(super) const MIN_DELTA_TIME: f64 = 25.0;
+const MAXIMUM_SLIDER_RADIUS: f64 = NORMALISED_RADIUS * 2.4;
+const ASSUMED_SLIDER_RADIUS: f64 = NORMALISED_RADIUS * 1.8;
+/// How much earlier than the end of a slider it needs to be followed to, in milliseconds.
+const TAIL_LENIENCY: f64 = -36.0;
+/// Part of the preempt a hitobject takes to fade out with hidden.
+const HIDDEN_FADE_OUT_DURATION_MULTIPLIER: f64 = 0.3;
+
+pub(super) struct OsuObject {
+    pub start_time: f64,
+    pub stacked_position: Vec2,
+    /// Stacked position of the end of a slider, or the stacked position for other hitobjects.
+    pub stacked_end_position: Vec2,
+    pub kind: OsuObjectKind,
+}
+
+pub(super) enum OsuObjectKind {
+    Circle,
+    Slider(Slider),
+    Spinner,
+}
+
+pub(super) struct Slider {
+    pub repeat_count: usize,
+    /// Amount of nested objects that give combo.
+    pub combo: usize,
+    /// Distance of the path followed by a lazy player, scaled to [`NORMALISED_RADIUS`].
+    pub lazy_travel_distance: f64,
+    /// Time the slider needs to be followed for.
+    pub lazy_travel_time: f64,
+    /// Position of the cursor at the end of the path followed by a lazy player.
+    pub lazy_end_position: Vec2,
+}
+
+impl OsuObject {
+    /// Prepares the `hitobjects` with their stacking.
+    /// - Sliders without nested objects from missing timing points are treated as circles.
+    pub fn from_hitobjects(
+        hitobjects: &HitObjects,
+        stacking: &[StackedObject],
+        timing_index: Option<&TimingIndex>,
+        difficulty: &Difficulty,
+        osu_difficulty: &OsuDifficulty,
+        version: Version,
+    ) -> Vec<Self> {
+        hitobjects
+            .0
+            .iter()
+            .zip(stacking)
+            .map(|(hitobject, stacked)| {
+                let start_time = hitobject.start_time().unwrap_or_default();
+                let position = Vec2::from_position(&hitobject.position).unwrap_or_default();
+                let stack_offset = stacked.position - position;
+
+                let kind = match &hitobject.obj_params {
+                    HitObjectParams::HitCircle => OsuObjectKind::Circle,
+                    HitObjectParams::Slider(params) => timing_index
+                        .and_then(|index| {
+                            let nested = hitobject.nested_objects(index, difficulty, version)?;
+                            let span_duration =
+                                hitobject.slider_span_duration(index, difficulty)?;
+
+                            Some(OsuObjectKind::Slider(Slider::new(
+                                start_time,
+                                stacked.position,
+                                stack_offset,
+                                hitobject.slider_path().as_ref(),
+                                span_duration,
+                                params.slides.max(1) as usize - 1,
+                                nested
+                                    .iter()
+                                    .filter(|nested| {
+                                        nested.kind != NestedObjectKind::LegacyLastTick
+                                    })
+                                    .map(|nested| {
+                                        (nested.kind, nested.time, nested.position + stack_offset)
+                                    })
+                                    .collect(),
+                                osu_difficulty.circle_radius,
+                            )))
+                        })
+                        .unwrap_or(OsuObjectKind::Circle),
+                    HitObjectParams::Spinner { .. } | HitObjectParams::OsuManiaHold { .. } => {
+                        OsuObjectKind::Spinner
+                    }
+                };
+
+                OsuObject {
+                    start_time,
+                    stacked_position: stacked.position,
+                    stacked_end_position: stacked.end_position,
+                    kind,
+                }
+            })
+            .collect()
+    }
+
+    /// Position of the cursor after the hitobject.
+    pub fn end_cursor_position(&self) -> Vec2 {
+        match &self.kind {
+            OsuObjectKind::Slider(slider) => slider.lazy_end_position,
+            _ => self.stacked_position,
+        }
+    }
+
+    pub fn is_slider(&self) -> bool {
+        matches!(self.kind, OsuObjectKind::Slider(_))
+    }
+
+    pub fn is_spinner(&self) -> bool {
+        matches!(self.kind, OsuObjectKind::Spinner)
+    }
+}
+
+impl Slider {
+    /// Computes the path a lazy player follows the slider with, which only moves the cursor when it's needed to stay in the follow circle.
+    /// - `nested` are the nested objects with their times and stacked positions, without the legacy last tick.
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        start_time: f64,
+        stacked_position: Vec2,
+        stack_offset: Vec2,
+        path: Option<&SliderPath>,
+        span_duration: f64,
+        repeat_count: usize,
+        mut nested: Vec<(NestedObjectKind, f64, Vec2)>,
+        radius: f64,
+    ) -> Self {
+        let duration = span_duration * (repeat_count + 1) as f64;
+        let mut tracking_end_time =
+            (start_time + duration + TAIL_LENIENCY).max(start_time + duration / 2.0);
+
+        // osu! moves the last tick to the end if it's after the tracking end time
+        let last_tick = nested
+            .iter()
+            .rposition(|(kind, _, _)| *kind == NestedObjectKind::Tick);
+        if let Some(last_tick) = last_tick {
+            if nested[last_tick].1 > tracking_end_time {
+                tracking_end_time = nested[last_tick].1;
+                let tick = nested.remove(last_tick);
+                nested.push(tick);
+            }
+        }
+
+        let lazy_travel_time = tracking_end_time - start_time;
+
+        let mut end_time_min = if span_duration > 0.0 {
+            lazy_travel_time / span_duration
+        } else {
+            0.0
+        };
+        if end_time_min % 2.0 >= 1.0 {
+            end_time_min = 1.0 - end_time_min % 1.0;
+        } else {
+            end_time_min %= 1.0;
+        }
+
+        let lazy_end_position = match path {
+            Some(path) => path.position_at(end_time_min) + stack_offset,
+            None => stacked_position,
+        };
+
+        let mut cursor_position = stacked_position;
+        let mut lazy_travel_distance = 0.0;
+        let scaling_factor = NORMALISED_RADIUS / radius;
+
+        for (i, &(kind, _, position)) in nested.iter().enumerate().skip(1) {
+            let mut movement = position - cursor_position;
+            let mut movement_length = scaling_factor * movement.length();
+            let mut required_movement = ASSUMED_SLIDER_RADIUS;
+
+            if i == nested.len() - 1 {
+                // the lazy end position is used if it's closer than the actual end
+                let lazy_movement = lazy_end_position - cursor_position;
+
+                if lazy_movement.length() < movement.length() {
+                    movement = lazy_movement;
+                }
+
+                movement_length = scaling_factor * movement.length();
+            } else if kind == NestedObjectKind::Repeat {
+                required_movement = NORMALISED_RADIUS;
+            }
+
+            if movement_length > required_movement {
+                let ratio = (movement_length - required_movement) / movement_length;
+                cursor_position = cursor_position + movement * ratio;
+                movement_length *= ratio;
+                lazy_travel_distance += movement_length;
+            }
+        }
+
+        Self {
+            repeat_count,
+            combo: nested.len(),
+            lazy_travel_distance,
+            lazy_travel_time,
+            lazy_end_position: if nested.len() > 1 {
+                cursor_position
+            } else {
+                lazy_end_position
+            },
+        }
+    }
+}
+
+/// A hitobject with how it's played from the previous hitobjects, for the difficulty calculation.
+pub(super) struct DifficultyObject<'a> {
+    pub base: &'a OsuObject,
+    /// Start time adjusted by the clock rate.
+    pub start_time: f64,
+    pub delta_time: f64,
+    pub strain_time: f64,
+    /// Full hit window for a 300, adjusted by the clock rate.
+    pub hit_window_great: f64,
+    pub lazy_jump_distance: f64,
+    pub minimum_jump_distance: f64,
+    pub minimum_jump_time: f64,
+    pub travel_distance: f64,
+    pub travel_time: f64,
+    pub angle: Option<f64>,
+}
+
+impl<'a> DifficultyObject<'a> {
+    pub fn new(
+        base: &'a OsuObject,
+        last: &'a OsuObject,
+        last_last: Option<&'a OsuObject>,
+        clock_rate: f64,
+        osu_difficulty: &OsuDifficulty,
+    ) -> Self {
+        let delta_time = (base.start_time - last.start_time) / clock_rate;
+        let strain_time = delta_time.max(MIN_DELTA_TIME);
+
+        let mut object = Self {
+            base,
+            start_time: base.start_time / clock_rate,
+            delta_time,
+            strain_time,
+            hit_window_great: match base.kind {
+                OsuObjectKind::Spinner => 0.0,
+                _ => 2.0 * osu_difficulty.hit_window_300 / clock_rate,
+            },
+            lazy_jump_distance: 0.0,
+            minimum_jump_distance: 0.0,
+            minimum_jump_time: 0.0,
+            travel_distance: 0.0,
+            travel_time: 0.0,
+            angle: None,
+        };
+
+        if let OsuObjectKind::Slider(slider) = &base.kind {
+            // bonus for repeat sliders
+            object.travel_distance = slider.lazy_travel_distance
+                * (1.0 + slider.repeat_count as f64 / 2.5).powf(1.0 / 2.5);
+            object.travel_time = (slider.lazy_travel_time / clock_rate).max(MIN_DELTA_TIME);
+        }
+
+        if base.is_spinner() || last.is_spinner() {
+            return object;
+        }
+
+        let radius = osu_difficulty.circle_radius;
+        let mut scaling_factor = NORMALISED_RADIUS / radius;
+
+        if radius < 30.0 {
+            let small_circle_bonus = (30.0 - radius).min(5.0) / 50.0;
+            scaling_factor *= 1.0 + small_circle_bonus;
+        }
+
+        let last_cursor_position = last.end_cursor_position();
+
+        object.lazy_jump_distance = (base.stacked_position * scaling_factor
+            - last_cursor_position * scaling_factor)
+            .length();
+        object.minimum_jump_time = strain_time;
+        object.minimum_jump_distance = object.lazy_jump_distance;
+
+        if let OsuObjectKind::Slider(last_slider) = &last.kind {
+            let last_travel_time = (last_slider.lazy_travel_time / clock_rate).max(MIN_DELTA_TIME);
+            object.minimum_jump_time = (strain_time - last_travel_time).max(MIN_DELTA_TIME);
+
+            // the player either cuts the slider short or follows it through to the next hitobject, so the shorter jump is used
+            let tail_jump_distance =
+                (last.stacked_end_position - base.stacked_position).length() * scaling_factor;
+            object.minimum_jump_distance = (object.lazy_jump_distance
+                - (MAXIMUM_SLIDER_RADIUS - ASSUMED_SLIDER_RADIUS))
+                .min(tail_jump_distance - MAXIMUM_SLIDER_RADIUS)
+                .max(0.0);
+        }
+
+        if let Some(last_last) = last_last.filter(|last_last| !last_last.is_spinner()) {
+            let last_last_cursor_position = last_last.end_cursor_position();
+
+            let v1 = last_last_cursor_position - last.stacked_position;
+            let v2 = base.stacked_position - last_cursor_position;
+            let dot = v1.dot(v2);
+            let det = v1.x * v2.y - v1.y * v2.x;
+
+            object.angle = Some(det.atan2(dot).abs());
+        }
+
+        object
+    }
+
+    /// How visible the hitobject is at the `time`, from `0` to `1`.
+    /// - Times are the times of the hitobjects, without the clock rate.
+    pub fn opacity_at(&self, time: f64, hidden: bool, osu_difficulty: &OsuDifficulty) -> f64 {
+        if time > self.base.start_time {
+            return 0.0;
+        }
+
+        let fade_in_start_time = self.base.start_time - osu_difficulty.preempt;
+        let fade_in = ((time - fade_in_start_time) / osu_difficulty.fade_in).clamp(0.0, 1.0);
+
+        if hidden {
+            let fade_out_start_time =
+                self.base.start_time - osu_difficulty.preempt + osu_difficulty.fade_in;
+            let fade_out_duration = osu_difficulty.preempt * HIDDEN_FADE_OUT_DURATION_MULTIPLIER;
+
+            fade_in.min(1.0 - ((time - fade_out_start_time) / fade_out_duration).clamp(0.0, 1.0))
+        } else {
+            fade_in
+        }
+    }
+}
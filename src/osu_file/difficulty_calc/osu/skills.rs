@@ -0,0 +1,185 @@
+//! Strain skills of osu!standard.
+
+use super::evaluators;
+use super::object::DifficultyObject;
+use crate::osu_file::difficulty::OsuDifficulty;
+use crate::osu_file::difficulty_calc::{strain_decay, weighted_sum, StrainSkill};
+
+/// Weight of each strain peak compared to the next highest one.
+const DECAY_WEIGHT: f64 = 0.9;
+/// Amount of the highest strain peaks that are reduced, to lower the effect of difficulty spikes.
+const REDUCED_SECTION_COUNT: usize = 10;
+/// How much the highest strain peak is reduced to.
+const REDUCED_STRAIN_BASELINE: f64 = 0.75;
+pub(super) const DEFAULT_DIFFICULTY_MULTIPLIER: f64 = 1.06;
+
+/// Difficulty of the strain peaks, with the highest peaks reduced.
+pub(super) fn difficulty_value(
+    peaks: Vec<f64>,
+    reduced_section_count: usize,
+    difficulty_multiplier: f64,
+) -> f64 {
+    // sections without strain don't add to the difficulty
+    let mut strains = peaks
+        .into_iter()
+        .filter(|peak| *peak > 0.0)
+        .collect::<Vec<_>>();
+    strains.sort_by(|a, b| b.total_cmp(a));
+
+    for (i, strain) in strains.iter_mut().take(reduced_section_count).enumerate() {
+        let scale = (1.0 + 9.0 * (i as f64 / reduced_section_count as f64).clamp(0.0, 1.0)).log10();
+        *strain *= REDUCED_STRAIN_BASELINE + (1.0 - REDUCED_STRAIN_BASELINE) * scale;
+    }
+
+    weighted_sum(strains, DECAY_WEIGHT) * difficulty_multiplier
+}
+
+/// Difficulty of moving the cursor between hitobjects.
+pub(super) struct Aim {
+    pub with_sliders: bool,
+    current_strain: f64,
+}
+
+impl Aim {
+    const SKILL_MULTIPLIER: f64 = 23.55;
+    const STRAIN_DECAY_BASE: f64 = 0.15;
+
+    pub fn new(with_sliders: bool) -> Self {
+        Self {
+            with_sliders,
+            current_strain: 0.0,
+        }
+    }
+
+    pub fn difficulty_value(peaks: Vec<f64>) -> f64 {
+        difficulty_value(peaks, REDUCED_SECTION_COUNT, DEFAULT_DIFFICULTY_MULTIPLIER)
+    }
+}
+
+impl StrainSkill<DifficultyObject<'_>> for Aim {
+    fn strain_value_at(&mut self, objects: &[DifficultyObject], index: usize) -> f64 {
+        self.current_strain *= strain_decay(Self::STRAIN_DECAY_BASE, objects[index].delta_time);
+        self.current_strain +=
+            evaluators::aim(objects, index, self.with_sliders) * Self::SKILL_MULTIPLIER;
+
+        self.current_strain
+    }
+
+    fn initial_strain(&self, time: f64, objects: &[DifficultyObject], index: usize) -> f64 {
+        self.current_strain
+            * strain_decay(
+                Self::STRAIN_DECAY_BASE,
+                time - objects[index - 1].start_time,
+            )
+    }
+}
+
+/// Difficulty of tapping hitobjects quickly.
+pub(super) struct Speed {
+    current_strain: f64,
+    current_rhythm: f64,
+    /// Strain of each hitobject, for the amount of notes that are relevant to the difficulty.
+    pub object_strains: Vec<f64>,
+}
+
+impl Speed {
+    const SKILL_MULTIPLIER: f64 = 1375.0;
+    const STRAIN_DECAY_BASE: f64 = 0.3;
+    const REDUCED_SECTION_COUNT: usize = 5;
+    const DIFFICULTY_MULTIPLIER: f64 = 1.04;
+
+    pub fn new() -> Self {
+        Self {
+            current_strain: 0.0,
+            current_rhythm: 0.0,
+            object_strains: Vec::new(),
+        }
+    }
+
+    pub fn difficulty_value(peaks: Vec<f64>) -> f64 {
+        difficulty_value(
+            peaks,
+            Self::REDUCED_SECTION_COUNT,
+            Self::DIFFICULTY_MULTIPLIER,
+        )
+    }
+
+    /// Amount of notes weighted by how close their strain is to the highest strain.
+    pub fn relevant_note_count(&self) -> f64 {
+        let max_strain = self.object_strains.iter().copied().fold(0.0, f64::max);
+
+        if max_strain == 0.0 {
+            return 0.0;
+        }
+
+        self.object_strains
+            .iter()
+            .map(|strain| 1.0 / (1.0 + (-(strain / max_strain * 12.0 - 6.0)).exp()))
+            .sum()
+    }
+}
+
+impl StrainSkill<DifficultyObject<'_>> for Speed {
+    fn strain_value_at(&mut self, objects: &[DifficultyObject], index: usize) -> f64 {
+        self.current_strain *= strain_decay(Self::STRAIN_DECAY_BASE, objects[index].strain_time);
+        self.current_strain += evaluators::speed(objects, index) * Self::SKILL_MULTIPLIER;
+        self.current_rhythm = evaluators::rhythm(objects, index);
+
+        let total_strain = self.current_strain * self.current_rhythm;
+        self.object_strains.push(total_strain);
+
+        total_strain
+    }
+
+    fn initial_strain(&self, time: f64, objects: &[DifficultyObject], index: usize) -> f64 {
+        self.current_strain
+            * self.current_rhythm
+            * strain_decay(
+                Self::STRAIN_DECAY_BASE,
+                time - objects[index - 1].start_time,
+            )
+    }
+}
+
+/// Difficulty of reading hitobjects with the flashlight mod.
+pub(super) struct Flashlight<'a> {
+    hidden: bool,
+    osu_difficulty: &'a OsuDifficulty,
+    current_strain: f64,
+}
+
+impl<'a> Flashlight<'a> {
+    const SKILL_MULTIPLIER: f64 = 0.052;
+    const STRAIN_DECAY_BASE: f64 = 0.15;
+
+    pub fn new(hidden: bool, osu_difficulty: &'a OsuDifficulty) -> Self {
+        Self {
+            hidden,
+            osu_difficulty,
+            current_strain: 0.0,
+        }
+    }
+
+    pub fn difficulty_value(peaks: Vec<f64>) -> f64 {
+        peaks.iter().sum::<f64>() * DEFAULT_DIFFICULTY_MULTIPLIER
+    }
+}
+
+impl StrainSkill<DifficultyObject<'_>> for Flashlight<'_> {
+    fn strain_value_at(&mut self, objects: &[DifficultyObject], index: usize) -> f64 {
+        self.current_strain *= strain_decay(Self::STRAIN_DECAY_BASE, objects[index].delta_time);
+        self.current_strain +=
+            evaluators::flashlight(objects, index, self.hidden, self.osu_difficulty)
+                * Self::SKILL_MULTIPLIER;
+
+        self.current_strain
+    }
+
+    fn initial_strain(&self, time: f64, objects: &[DifficultyObject], index: usize) -> f64 {
+        self.current_strain
+            * strain_decay(
+                Self::STRAIN_DECAY_BASE,
+                time - objects[index - 1].start_time,
+            )
+    }
+}
@@ -0,0 +1,177 @@
+//! Colour patterns of osu!taiko notes, encoded the same way as osu! does for the colour difficulty.
+//! - A mono streak is a run of notes with the same colour.
+//! - An alternating mono pattern is a run of mono streaks with the same length, like `kkddkkdd`.
+//! - A repeating hit pattern is a group of alternating mono patterns that repeat each other.
+
+use std::f64::consts::E;
+
+use super::object::{DifficultyObject, HitType};
+
+/// Most amount of repeating hit patterns that are looked back at for a repetition.
+const MAX_REPETITION_INTERVAL: usize = 16;
+
+struct MonoStreak {
+    /// Indices of the difficulty objects in the streak.
+    objects: Vec<usize>,
+    hit_type: Option<HitType>,
+}
+
+struct AlternatingMonoPattern {
+    /// Indices of the mono streaks in the pattern.
+    mono_streaks: Vec<usize>,
+}
+
+struct RepeatingHitPatterns {
+    /// Indices of the alternating mono patterns in the group.
+    patterns: Vec<usize>,
+    repetition_interval: usize,
+}
+
+fn sigmoid(value: f64, center: f64, width: f64, middle: f64, height: f64) -> f64 {
+    (E * -(value - center) / width).tanh() * (height / 2.0) + middle
+}
+
+/// Colour difficulty of each of the `objects`, which is only given to the first object of each encoded pattern.
+pub(super) fn colour_difficulties(objects: &[DifficultyObject]) -> Vec<f64> {
+    let mono_streaks = encode_mono_streaks(objects);
+    let patterns = encode_alternating_mono_patterns(&mono_streaks);
+    let repeating_patterns = encode_repeating_hit_patterns(&mono_streaks, &patterns);
+
+    let mut difficulties = vec![0.0; objects.len()];
+
+    for repeating in &repeating_patterns {
+        let repeating_difficulty =
+            2.0 * (1.0 - sigmoid(repeating.repetition_interval as f64, 2.0, 2.0, 0.5, 1.0));
+
+        for (i, &pattern) in repeating.patterns.iter().enumerate() {
+            let pattern = &patterns[pattern];
+            let pattern_difficulty = sigmoid(i as f64, 2.0, 2.0, 0.5, 1.0) * repeating_difficulty;
+            difficulties[mono_streaks[pattern.mono_streaks[0]].objects[0]] += pattern_difficulty;
+
+            for (j, &mono_streak) in pattern.mono_streaks.iter().enumerate() {
+                let mono_difficulty =
+                    sigmoid(j as f64, 2.0, 2.0, 0.5, 1.0) * pattern_difficulty * 0.5;
+                difficulties[mono_streaks[mono_streak].objects[0]] += mono_difficulty;
+            }
+        }
+
+        let first_pattern = &patterns[repeating.patterns[0]];
+        difficulties[mono_streaks[first_pattern.mono_streaks[0]].objects[0]] +=
+            repeating_difficulty;
+    }
+
+    difficulties
+}
+
+/// Groups the objects into runs of the same colour.
+/// - Drumrolls and swells start their own streak, like osu! does.
+fn encode_mono_streaks(objects: &[DifficultyObject]) -> Vec<MonoStreak> {
+    let mut mono_streaks: Vec<MonoStreak> = Vec::new();
+    let mut previous_note = None;
+
+    for object in objects {
+        let same_colour = previous_note.is_some_and(|previous_note: &DifficultyObject| {
+            object.hit_type == previous_note.hit_type
+        });
+
+        match mono_streaks.last_mut() {
+            Some(mono_streak) if same_colour => mono_streak.objects.push(object.index),
+            _ => mono_streaks.push(MonoStreak {
+                objects: vec![object.index],
+                hit_type: object.hit_type,
+            }),
+        }
+
+        if object.hit_type.is_some() {
+            previous_note = Some(object);
+        }
+    }
+
+    mono_streaks
+}
+
+/// Groups the mono streaks into runs of streaks with the same length.
+fn encode_alternating_mono_patterns(mono_streaks: &[MonoStreak]) -> Vec<AlternatingMonoPattern> {
+    let mut patterns: Vec<AlternatingMonoPattern> = Vec::new();
+
+    for (i, mono_streak) in mono_streaks.iter().enumerate() {
+        match patterns.last_mut() {
+            Some(pattern) if mono_streak.objects.len() == mono_streaks[i - 1].objects.len() => {
+                pattern.mono_streaks.push(i)
+            }
+            _ => patterns.push(AlternatingMonoPattern {
+                mono_streaks: vec![i],
+            }),
+        }
+    }
+
+    patterns
+}
+
+/// Groups the alternating mono patterns that repeat the pattern two before them, and finds how long ago each group was repeated.
+fn encode_repeating_hit_patterns(
+    mono_streaks: &[MonoStreak],
+    patterns: &[AlternatingMonoPattern],
+) -> Vec<RepeatingHitPatterns> {
+    let first_length = |pattern: usize| {
+        mono_streaks[patterns[pattern].mono_streaks[0]]
+            .objects
+            .len()
+    };
+    let is_repetition = |a: usize, b: usize| {
+        first_length(a) == first_length(b)
+            && patterns[a].mono_streaks.len() == patterns[b].mono_streaks.len()
+            && mono_streaks[patterns[a].mono_streaks[0]].hit_type
+                == mono_streaks[patterns[b].mono_streaks[0]].hit_type
+    };
+    let is_coupled = |i: usize| i + 2 < patterns.len() && is_repetition(i, i + 2);
+
+    let mut repeating_patterns: Vec<RepeatingHitPatterns> = Vec::new();
+    let mut i = 0;
+
+    while i < patterns.len() {
+        let mut group = Vec::new();
+
+        if is_coupled(i) {
+            while is_coupled(i) {
+                group.push(i);
+                i += 1;
+            }
+
+            group.push(i);
+            group.push(i + 1);
+            i += 1;
+        } else {
+            group.push(i);
+        }
+
+        repeating_patterns.push(RepeatingHitPatterns {
+            patterns: group,
+            repetition_interval: MAX_REPETITION_INTERVAL + 1,
+        });
+        i += 1;
+    }
+
+    // only the lengths of the first two patterns are compared for the repetitions
+    let is_group_repetition = |a: &RepeatingHitPatterns, b: &RepeatingHitPatterns| {
+        a.patterns.len() == b.patterns.len()
+            && a.patterns
+                .iter()
+                .zip(&b.patterns)
+                .take(2)
+                .all(|(&a, &b)| first_length(a) == first_length(b))
+    };
+
+    for i in 0..repeating_patterns.len() {
+        let repetition_interval = (1..MAX_REPETITION_INTERVAL)
+            .take_while(|interval| *interval <= i)
+            .find(|interval| {
+                is_group_repetition(&repeating_patterns[i], &repeating_patterns[i - interval])
+            })
+            .unwrap_or(MAX_REPETITION_INTERVAL + 1);
+
+        repeating_patterns[i].repetition_interval = repetition_interval;
+    }
+
+    repeating_patterns
+}
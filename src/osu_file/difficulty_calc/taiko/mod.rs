@@ -0,0 +1,140 @@
+//! Difficulty calculation of osu!taiko.
+
+mod colour;
+mod object;
+mod skills;
+
+use object::DifficultyObject;
+use skills::{Colour, Rhythm, Stamina};
+
+use super::StrainPeaks;
+use crate::osu_file::{
+    difficulty::EMPTY_DIFFICULTY, general::Mode, hitobjects::EMPTY_HITOBJECTS, Mods, OsuFile,
+};
+
+/// Multiplier of the difficulty values of the skills.
+const DIFFICULTY_MULTIPLIER: f64 = 1.35;
+const FINAL_MULTIPLIER: f64 = 0.0625;
+const RHYTHM_SKILL_MULTIPLIER: f64 = 0.2 * FINAL_MULTIPLIER;
+const COLOUR_SKILL_MULTIPLIER: f64 = 0.375 * FINAL_MULTIPLIER;
+const STAMINA_SKILL_MULTIPLIER: f64 = 0.375 * FINAL_MULTIPLIER;
+/// Weight of each strain peak compared to the next highest one.
+const DECAY_WEIGHT: f64 = 0.9;
+
+/// Difficulty of an osu!taiko beatmap, with the attributes needed for the performance points.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TaikoDifficultyAttributes {
+    pub star_rating: f64,
+    /// Difficulty of hitting notes quickly with each key.
+    pub stamina_difficulty: f64,
+    /// Difficulty of the rhythm changes.
+    pub rhythm_difficulty: f64,
+    /// Difficulty of the colour changes.
+    pub colour_difficulty: f64,
+    /// Combined difficulty of the skills before it's scaled to the star rating.
+    pub peak_difficulty: f64,
+    /// Hit window for a great in milliseconds, adjusted by the clock rate.
+    pub great_hit_window: f64,
+    /// Highest combo that can be reached, which is the amount of notes.
+    pub max_combo: usize,
+}
+
+impl OsuFile {
+    /// Computes the star rating and difficulty attributes of an osu!taiko beatmap, like osu! does for stable.
+    /// - Returns `None` if the beatmap's mode isn't osu!taiko, since converted beatmaps aren't supported.
+    pub fn taiko_difficulty(&self) -> Option<TaikoDifficultyAttributes> {
+        match self.mode() {
+            Mode::Taiko => Some(calculate(self, &Mods::default())),
+            _ => None,
+        }
+    }
+}
+
+/// Computes the osu!taiko difficulty of the beatmap with the `mods`.
+pub(crate) fn calculate(osu_file: &OsuFile, mods: &Mods) -> TaikoDifficultyAttributes {
+    let values = mods
+        .difficulty(
+            osu_file.difficulty.as_ref().unwrap_or(&EMPTY_DIFFICULTY),
+            Mode::Taiko,
+        )
+        .values();
+    let clock_rate = mods.clock_rate();
+    let hitobjects = osu_file.hitobjects.as_ref().unwrap_or(&EMPTY_HITOBJECTS);
+
+    let mut attributes = TaikoDifficultyAttributes {
+        great_hit_window: values.taiko().hit_window_great / clock_rate,
+        max_combo: hitobjects
+            .0
+            .iter()
+            .filter(|hitobject| object::HitType::from_hitobject(hitobject).is_some())
+            .count(),
+        ..Default::default()
+    };
+
+    if hitobjects.0.is_empty() {
+        return attributes;
+    }
+
+    let objects = DifficultyObject::from_hitobjects(&hitobjects.0, clock_rate);
+    let colour_difficulties = colour::colour_difficulties(&objects);
+
+    let mut rhythm = Rhythm::new();
+    let mut colour = Colour::new(&colour_difficulties);
+    let mut stamina = Stamina::new();
+    let mut peaks = [StrainPeaks::new(), StrainPeaks::new(), StrainPeaks::new()];
+
+    for (i, object) in objects.iter().enumerate() {
+        let time = object.start_time;
+
+        peaks[0].process(&mut rhythm, &objects, i, time);
+        peaks[1].process(&mut colour, &objects, i, time);
+        peaks[2].process(&mut stamina, &objects, i, time);
+    }
+
+    let [rhythm_peaks, colour_peaks, stamina_peaks] = peaks.map(|peaks| peaks.peaks());
+
+    // the skills are combined for each section, so the hardest sections of each skill add up
+    let combined_peaks = rhythm_peaks
+        .iter()
+        .zip(&colour_peaks)
+        .zip(&stamina_peaks)
+        .map(|((rhythm, colour), stamina)| {
+            let peak = norm(
+                1.5,
+                colour * COLOUR_SKILL_MULTIPLIER,
+                stamina * STAMINA_SKILL_MULTIPLIER,
+            );
+            norm(2.0, peak, rhythm * RHYTHM_SKILL_MULTIPLIER)
+        })
+        .collect();
+
+    attributes.rhythm_difficulty = super::weighted_sum(rhythm_peaks, DECAY_WEIGHT)
+        * RHYTHM_SKILL_MULTIPLIER
+        * DIFFICULTY_MULTIPLIER;
+    attributes.colour_difficulty = super::weighted_sum(colour_peaks, DECAY_WEIGHT)
+        * COLOUR_SKILL_MULTIPLIER
+        * DIFFICULTY_MULTIPLIER;
+    attributes.stamina_difficulty = super::weighted_sum(stamina_peaks, DECAY_WEIGHT)
+        * STAMINA_SKILL_MULTIPLIER
+        * DIFFICULTY_MULTIPLIER;
+    attributes.peak_difficulty =
+        super::weighted_sum(combined_peaks, DECAY_WEIGHT) * DIFFICULTY_MULTIPLIER;
+    attributes.star_rating = rescale(attributes.peak_difficulty * 1.4);
+
+    attributes
+}
+
+/// The `p`-norm of the two values.
+fn norm(p: f64, a: f64, b: f64) -> f64 {
+    (a.powf(p) + b.powf(p)).powf(1.0 / p)
+}
+
+/// Scales the combined difficulty down for the higher star ratings.
+fn rescale(star_rating: f64) -> f64 {
+    if star_rating < 0.0 {
+        star_rating
+    } else {
+        10.43 * (star_rating / 8.0 + 1.0).ln()
+    }
+}
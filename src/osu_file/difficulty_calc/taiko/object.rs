@@ -0,0 +1,122 @@
+//! Hitobjects prepared for the osu!taiko difficulty calculation.
+
+use crate::osu_file::hitobjects::{HitObject, HitObjectParams};
+
+/// Colour of a taiko note.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum HitType {
+    /// Don, a red note.
+    Centre,
+    /// Kat, a blue note hit with a whistle or clap.
+    Rim,
+}
+
+impl HitType {
+    /// Colour of the hitobject, or `None` for drumrolls and swells.
+    pub fn from_hitobject(hitobject: &HitObject) -> Option<Self> {
+        match hitobject.obj_params {
+            HitObjectParams::HitCircle => {
+                if hitobject.hitsound.whistle() || hitobject.hitsound.clap() {
+                    Some(HitType::Rim)
+                } else {
+                    Some(HitType::Centre)
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Ratio of the delta time to the previous delta time that a rhythm is snapped to, with its difficulty.
+#[derive(Debug, PartialEq)]
+pub(super) struct Rhythm {
+    pub ratio: f64,
+    pub difficulty: f64,
+}
+
+const COMMON_RHYTHMS: [Rhythm; 9] = [
+    Rhythm::new(1, 1, 0.0),
+    Rhythm::new(2, 1, 0.3),
+    Rhythm::new(1, 2, 0.5),
+    Rhythm::new(3, 1, 0.3),
+    Rhythm::new(1, 3, 0.35),
+    Rhythm::new(3, 2, 0.6),
+    Rhythm::new(2, 3, 0.4),
+    Rhythm::new(5, 4, 0.5),
+    Rhythm::new(4, 5, 0.7),
+];
+
+impl Rhythm {
+    const fn new(numerator: u32, denominator: u32, difficulty: f64) -> Self {
+        Self {
+            ratio: numerator as f64 / denominator as f64,
+            difficulty,
+        }
+    }
+
+    /// Closest common rhythm to the ratio of the delta times, preferring the simpler rhythms.
+    fn closest(delta_time: f64, previous_delta_time: f64) -> &'static Rhythm {
+        let ratio = delta_time / previous_delta_time;
+
+        COMMON_RHYTHMS
+            .iter()
+            .min_by(|a, b| (a.ratio - ratio).abs().total_cmp(&(b.ratio - ratio).abs()))
+            .unwrap()
+    }
+}
+
+/// A hitobject with how it's played from the previous hitobjects, for the difficulty calculation.
+pub(super) struct DifficultyObject {
+    /// Index of the object in the difficulty objects.
+    pub index: usize,
+    /// Start time adjusted by the clock rate.
+    pub start_time: f64,
+    pub delta_time: f64,
+    pub hit_type: Option<HitType>,
+    pub rhythm: &'static Rhythm,
+    /// Time since the previous note hit with the same key, which is two notes of the same colour before.
+    pub key_interval: Option<f64>,
+}
+
+impl DifficultyObject {
+    /// Prepares the `hitobjects` from the third one, since the rhythm needs the two hitobjects before it.
+    pub fn from_hitobjects(hitobjects: &[HitObject], clock_rate: f64) -> Vec<Self> {
+        let start_times = hitobjects
+            .iter()
+            .map(|hitobject| hitobject.start_time().unwrap_or_default() / clock_rate)
+            .collect::<Vec<_>>();
+
+        let mut objects: Vec<DifficultyObject> = Vec::new();
+        let mut centre_times = Vec::new();
+        let mut rim_times = Vec::new();
+
+        for i in 2..hitobjects.len() {
+            let start_time = start_times[i];
+            let delta_time = start_time - start_times[i - 1];
+            let hit_type = HitType::from_hitobject(&hitobjects[i]);
+
+            let mono_times = match hit_type {
+                Some(HitType::Centre) => Some(&mut centre_times),
+                Some(HitType::Rim) => Some(&mut rim_times),
+                None => None,
+            };
+            let key_interval = mono_times.and_then(|mono_times| {
+                let key_previous = mono_times.len().checked_sub(2).map(|i| mono_times[i]);
+                mono_times.push(start_time);
+
+                key_previous.map(|key_previous| start_time - key_previous)
+            });
+
+            objects.push(DifficultyObject {
+                index: objects.len(),
+                start_time,
+                delta_time,
+                hit_type,
+                rhythm: Rhythm::closest(delta_time, start_times[i - 1] - start_times[i - 2]),
+                key_interval,
+            });
+        }
+
+        objects
+    }
+}
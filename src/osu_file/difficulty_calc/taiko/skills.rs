@@ -0,0 +1,211 @@
+//! Strain skills of osu!taiko.
+
+use std::collections::VecDeque;
+
+use super::object::DifficultyObject;
+use crate::osu_file::difficulty_calc::{strain_decay, StrainSkill};
+
+/// Difficulty of changes in the rhythm.
+pub(super) struct Rhythm {
+    current_strain: f64,
+    /// Strain of the rhythm changes, which decays for each note instead of over time.
+    rhythm_strain: f64,
+    notes_since_rhythm_change: usize,
+    /// Recent notes with a rhythm change, for the repetitions of the rhythms.
+    history: VecDeque<usize>,
+}
+
+impl Rhythm {
+    const SKILL_MULTIPLIER: f64 = 10.0;
+    // the strain is only kept for objects at the same time, and builds up in the rhythm strain instead
+    const STRAIN_DECAY_BASE: f64 = 0.0;
+    const STRAIN_DECAY: f64 = 0.96;
+    const HISTORY_MAX_LENGTH: usize = 8;
+
+    pub fn new() -> Self {
+        Self {
+            current_strain: 0.0,
+            rhythm_strain: 0.0,
+            notes_since_rhythm_change: 0,
+            history: VecDeque::with_capacity(Self::HISTORY_MAX_LENGTH),
+        }
+    }
+
+    fn reset(&mut self) {
+        self.rhythm_strain = 0.0;
+        self.notes_since_rhythm_change = 0;
+    }
+
+    /// Penalty for the rhythm repeating the most recent rhythms.
+    fn repetition_penalties(&mut self, objects: &[DifficultyObject], index: usize) -> f64 {
+        if self.history.len() == Self::HISTORY_MAX_LENGTH {
+            self.history.pop_front();
+        }
+        self.history.push_back(index);
+
+        let history = &self.history;
+        let same_pattern = |start: usize, length: usize| {
+            (0..length).all(|i| {
+                std::ptr::eq(
+                    objects[history[start + i]].rhythm,
+                    objects[history[history.len() - length + i]].rhythm,
+                )
+            })
+        };
+
+        let mut penalty = 1.0;
+
+        for length in 2..=Self::HISTORY_MAX_LENGTH / 2 {
+            let Some(last_start) = history.len().checked_sub(length + 1) else {
+                continue;
+            };
+
+            if let Some(start) = (0..=last_start)
+                .rev()
+                .find(|start| same_pattern(*start, length))
+            {
+                let notes_since = index - history[start];
+                penalty *= (0.032 * notes_since as f64).min(1.0);
+            }
+        }
+
+        penalty
+    }
+
+    fn pattern_length_penalty(pattern_length: usize) -> f64 {
+        let short_pattern_penalty = (0.15 * pattern_length as f64).min(1.0);
+        let long_pattern_penalty = (2.5 - 0.15 * pattern_length as f64).clamp(0.0, 1.0);
+
+        short_pattern_penalty.min(long_pattern_penalty)
+    }
+
+    /// Strain of the rhythm change at the `index`.
+    fn rhythm_strain_of(&mut self, objects: &[DifficultyObject], index: usize) -> f64 {
+        let current = &objects[index];
+
+        // drumrolls and swells don't have a rhythm
+        if current.hit_type.is_none() {
+            self.reset();
+            return 0.0;
+        }
+
+        self.rhythm_strain *= Self::STRAIN_DECAY;
+        self.notes_since_rhythm_change += 1;
+
+        if current.rhythm.difficulty == 0.0 {
+            return 0.0;
+        }
+
+        let mut strain = current.rhythm.difficulty;
+        strain *= self.repetition_penalties(objects, index);
+        strain *= Self::pattern_length_penalty(self.notes_since_rhythm_change);
+
+        // slow rhythms are easier to read
+        if current.delta_time >= 210.0 {
+            self.reset();
+            return 0.0;
+        }
+        if current.delta_time >= 80.0 {
+            strain *= (1.4 - 0.005 * current.delta_time).max(0.0);
+        }
+
+        self.notes_since_rhythm_change = 0;
+        self.rhythm_strain += strain;
+        self.rhythm_strain
+    }
+}
+
+impl StrainSkill<DifficultyObject> for Rhythm {
+    fn strain_value_at(&mut self, objects: &[DifficultyObject], index: usize) -> f64 {
+        self.current_strain *= strain_decay(Self::STRAIN_DECAY_BASE, objects[index].delta_time);
+        self.current_strain += self.rhythm_strain_of(objects, index) * Self::SKILL_MULTIPLIER;
+
+        self.current_strain
+    }
+
+    fn initial_strain(&self, time: f64, objects: &[DifficultyObject], index: usize) -> f64 {
+        self.current_strain
+            * strain_decay(
+                Self::STRAIN_DECAY_BASE,
+                time - objects[index - 1].start_time,
+            )
+    }
+}
+
+/// Difficulty of the colour changes of the notes.
+pub(super) struct Colour<'a> {
+    /// Colour difficulty of each object.
+    difficulties: &'a [f64],
+    current_strain: f64,
+}
+
+impl<'a> Colour<'a> {
+    const SKILL_MULTIPLIER: f64 = 0.12;
+    // decays slower than the other skills, since only the first note of each pattern has a difficulty
+    const STRAIN_DECAY_BASE: f64 = 0.8;
+
+    pub fn new(difficulties: &'a [f64]) -> Self {
+        Self {
+            difficulties,
+            current_strain: 0.0,
+        }
+    }
+}
+
+impl StrainSkill<DifficultyObject> for Colour<'_> {
+    fn strain_value_at(&mut self, objects: &[DifficultyObject], index: usize) -> f64 {
+        self.current_strain *= strain_decay(Self::STRAIN_DECAY_BASE, objects[index].delta_time);
+        self.current_strain += self.difficulties[index] * Self::SKILL_MULTIPLIER;
+
+        self.current_strain
+    }
+
+    fn initial_strain(&self, time: f64, objects: &[DifficultyObject], index: usize) -> f64 {
+        self.current_strain
+            * strain_decay(
+                Self::STRAIN_DECAY_BASE,
+                time - objects[index - 1].start_time,
+            )
+    }
+}
+
+/// Difficulty of hitting notes quickly with each key.
+pub(super) struct Stamina {
+    current_strain: f64,
+}
+
+impl Stamina {
+    const SKILL_MULTIPLIER: f64 = 1.1;
+    const STRAIN_DECAY_BASE: f64 = 0.4;
+
+    pub fn new() -> Self {
+        Self {
+            current_strain: 0.0,
+        }
+    }
+
+    fn strain_of(object: &DifficultyObject) -> f64 {
+        match object.key_interval {
+            // the interval is capped to 600 BPM 1/4, which is 50ms for each key
+            Some(key_interval) => 0.5 + 30.0 / key_interval.max(50.0),
+            None => 0.0,
+        }
+    }
+}
+
+impl StrainSkill<DifficultyObject> for Stamina {
+    fn strain_value_at(&mut self, objects: &[DifficultyObject], index: usize) -> f64 {
+        self.current_strain *= strain_decay(Self::STRAIN_DECAY_BASE, objects[index].delta_time);
+        self.current_strain += Self::strain_of(&objects[index]) * Self::SKILL_MULTIPLIER;
+
+        self.current_strain
+    }
+
+    fn initial_strain(&self, time: f64, objects: &[DifficultyObject], index: usize) -> f64 {
+        self.current_strain
+            * strain_decay(
+                Self::STRAIN_DECAY_BASE,
+                time - objects[index - 1].start_time,
+            )
+    }
+}
@@ -19,6 +19,7 @@ use crate::{
 };
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AudioSample {
     pub time: Integer,
     pub layer: Layer,
@@ -105,6 +106,8 @@ impl VersionedToString for AudioSample {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "u8", into = "u8"))]
 pub struct Volume(u8);
 
 impl VersionedDefault for Volume {
@@ -148,6 +151,22 @@ impl VersionedFrom<Volume> for u8 {
     }
 }
 
+#[cfg(feature = "serde")]
+impl TryFrom<u8> for Volume {
+    type Error = VolumeSetError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Volume::new(value, crate::osu_file::LATEST_VERSION)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Volume> for u8 {
+    fn from(volume: Volume) -> Self {
+        volume.0
+    }
+}
+
 impl VersionedFromStr for Volume {
     type Err = ParseVolumeError;
 
@@ -164,6 +183,7 @@ impl VersionedToString for Volume {
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum Layer {
     Background,
@@ -3,21 +3,29 @@ pub mod error;
 pub mod normal_event;
 pub mod storyboard;
 
+use std::fmt::{self, Write};
+
 use nom::branch::alt;
 use nom::combinator::{cut, eof, peek, success};
 use nom::sequence::tuple;
 use nom::Parser;
 use nom::{bytes::complete::tag, combinator::rest, sequence::preceded};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
 
 use crate::events::storyboard::cmds::CommandProperties;
-use crate::helper::trait_ext::MapOptStringNewLine;
+use crate::helper::{item_lines, ErrorCollector, ErrorsResult, PrefixWriter, VersionedWrite};
 use crate::osb::Variable;
 use crate::parsers::comma;
 
 use self::storyboard::cmds::Command;
 use self::storyboard::error::CommandPushError;
-use self::storyboard::{error::ParseObjectError, sprites::Object};
+use self::storyboard::{
+    error::ParseObjectError,
+    sprites::{Object, ObjectType},
+};
 
+use super::offset::shift_decimal;
+use super::rate::{scale_decimal, scale_integer};
 use super::Version;
 use super::{types::Error, Integer, VersionedDefault, VersionedFromStr, VersionedToString};
 
@@ -26,6 +34,7 @@ pub use error::*;
 pub use normal_event::*;
 
 #[derive(Default, Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Events(pub Vec<Event>);
 
 const OLD_VERSION_TIME_OFFSET: Integer = 24;
@@ -44,7 +53,31 @@ impl Events {
         version: Version,
         variables: &[Variable],
     ) -> std::result::Result<Option<Self>, Error<ParseError>> {
+        Events::from_str_errors(s, version, variables, false).map(|(events, _)| events)
+    }
+
+    /// Parses the events, collecting the errors instead of returning the first one if `lenient` is set.
+    /// - Lines with errors are skipped when collected, along with the commands of an event that failed to parse.
+    pub(crate) fn from_str_errors(
+        s: &str,
+        version: Version,
+        variables: &[Variable],
+        lenient: bool,
+    ) -> ErrorsResult<Self, ParseError> {
+        Self::from_lines(item_lines(s, false), version, variables, lenient)
+    }
+
+    /// Parses the events from the lines of the section that aren't blank, with their index in the section.
+    pub(crate) fn from_lines<'a>(
+        lines: impl IntoIterator<Item = (usize, &'a str)>,
+        version: Version,
+        variables: &[Variable],
+        lenient: bool,
+    ) -> ErrorsResult<Self, ParseError> {
         let mut events = Events(Vec::new());
+        let mut errors = ErrorCollector::new(lenient);
+        // commands can't be pushed to an event that failed to parse
+        let mut last_event_failed = false;
 
         #[derive(Clone)]
         enum NormalEventType {
@@ -115,11 +148,7 @@ impl Events {
             .map(|_| NormalEventType::SampleLegacy)
         };
 
-        for (line_index, line) in s.lines().enumerate() {
-            if line.trim().is_empty() {
-                continue;
-            }
-
+        for (line_index, line) in lines {
             if let Ok((_, comment)) = comment(line) {
                 events.0.push(Event::Comment(comment.to_string()));
                 continue;
@@ -163,70 +192,83 @@ impl Events {
                     }
                 };
 
-                match events.0.last_mut() {
-                    Some(event) => match event {
-                        Event::Background(bg) => {
-                            if let Some(cmd) = cmd_parse()? {
-                                Error::new_from_result_into(
-                                    bg.try_push_cmd(cmd, indent),
-                                    line_index,
-                                )?
+                let mut push_cmd = || {
+                    if last_event_failed {
+                        return Err(Error::new(
+                            ParseError::StoryboardCmdWithNoSprite,
+                            line_index,
+                        ));
+                    }
+
+                    match events.0.last_mut() {
+                        Some(event) => match event {
+                            Event::Background(bg) => {
+                                if let Some(cmd) = cmd_parse()? {
+                                    Error::new_from_result_into(
+                                        bg.try_push_cmd(cmd, indent),
+                                        line_index,
+                                    )?
+                                }
                             }
-                        }
-                        Event::Video(video) => {
-                            if let Some(cmd) = cmd_parse()? {
-                                Error::new_from_result_into(
-                                    video.try_push_cmd(cmd, indent),
-                                    line_index,
-                                )?
+                            Event::Video(video) => {
+                                if let Some(cmd) = cmd_parse()? {
+                                    Error::new_from_result_into(
+                                        video.try_push_cmd(cmd, indent),
+                                        line_index,
+                                    )?
+                                }
                             }
-                        }
-                        Event::SpriteLegacy(sprite) => {
-                            if let Some(cmd) = cmd_parse()? {
-                                Error::new_from_result_into(
-                                    sprite.try_push_cmd(cmd, indent),
-                                    line_index,
-                                )?
+                            Event::SpriteLegacy(sprite) => {
+                                if let Some(cmd) = cmd_parse()? {
+                                    Error::new_from_result_into(
+                                        sprite.try_push_cmd(cmd, indent),
+                                        line_index,
+                                    )?
+                                }
                             }
-                        }
-                        Event::AnimationLegacy(animation) => {
-                            if let Some(cmd) = cmd_parse()? {
-                                Error::new_from_result_into(
-                                    animation.try_push_cmd(cmd, indent),
-                                    line_index,
-                                )?
+                            Event::AnimationLegacy(animation) => {
+                                if let Some(cmd) = cmd_parse()? {
+                                    Error::new_from_result_into(
+                                        animation.try_push_cmd(cmd, indent),
+                                        line_index,
+                                    )?
+                                }
                             }
-                        }
-                        Event::SampleLegacy(sample) => {
-                            if let Some(cmd) = cmd_parse()? {
-                                Error::new_from_result_into(
-                                    sample.try_push_cmd(cmd, indent),
-                                    line_index,
-                                )?
+                            Event::SampleLegacy(sample) => {
+                                if let Some(cmd) = cmd_parse()? {
+                                    Error::new_from_result_into(
+                                        sample.try_push_cmd(cmd, indent),
+                                        line_index,
+                                    )?
+                                }
                             }
-                        }
-                        Event::StoryboardObject(obj) => {
-                            if let Some(cmd) = cmd_parse()? {
-                                Error::new_from_result_into(
-                                    obj.try_push_cmd(cmd, indent),
+                            Event::StoryboardObject(obj) => {
+                                if let Some(cmd) = cmd_parse()? {
+                                    Error::new_from_result_into(
+                                        obj.try_push_cmd(cmd, indent),
+                                        line_index,
+                                    )?
+                                }
+                            }
+                            _ => {
+                                return Err(Error::new(
+                                    ParseError::StoryboardCmdWithNoSprite,
                                     line_index,
-                                )?
+                                ))
                             }
-                        }
+                        },
                         _ => {
                             return Err(Error::new(
                                 ParseError::StoryboardCmdWithNoSprite,
                                 line_index,
                             ))
                         }
-                    },
-                    _ => {
-                        return Err(Error::new(
-                            ParseError::StoryboardCmdWithNoSprite,
-                            line_index,
-                        ))
                     }
-                }
+
+                    Ok(())
+                };
+
+                errors.collect(push_cmd())?;
                 continue;
             }
 
@@ -291,26 +333,63 @@ impl Events {
                 }
             };
 
-            match res {
-                Ok(event) => {
+            match errors.collect(Error::new_from_result(res, line_index))? {
+                Some(event) => {
+                    last_event_failed = false;
+
                     if let Some(event) = event {
                         events.0.push(event)
                     }
                 }
-                Err(e) => return Err(Error::new(e, line_index)),
+                None => last_event_failed = true,
             }
         }
 
-        Ok(Some(events))
+        Ok((Some(events), errors.into_errors()))
     }
 
     pub fn to_string_variables(&self, version: Version, variables: &[Variable]) -> Option<String> {
-        let mut s = self
-            .0
-            .iter()
-            .map(|event| event.to_string_variables(version, variables));
+        let mut s = String::new();
+        self.write_variables(&mut s, version, variables)
+            .expect("writing to a String can't fail");
+
+        Some(s)
+    }
+
+    /// Writes the same output as `to_string_variables` straight to the writer.
+    pub(crate) fn write_variables<W: Write>(
+        &self,
+        f: &mut W,
+        version: Version,
+        variables: &[Variable],
+    ) -> fmt::Result {
+        let mut first = true;
+
+        for event in &self.0 {
+            let prefix = if first { None } else { Some("\n".into()) };
+            let mut writer = PrefixWriter::new(f, prefix);
 
-        Some(s.map_string_new_line())
+            if let Some(res) = event.write_variables(&mut writer, version, variables) {
+                res?;
+                first = false;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Changes the speed of the events to the `rate`, like [`Event::scale_time`].
+    pub fn scale_time(&mut self, rate: f64) {
+        for event in &mut self.0 {
+            event.scale_time(rate);
+        }
+    }
+
+    /// Shifts the times of the events by `delta` milliseconds, like [`Event::shift_time`].
+    pub fn shift_time(&mut self, delta: Integer) {
+        for event in &mut self.0 {
+            event.shift_time(delta);
+        }
     }
 }
 
@@ -320,6 +399,12 @@ impl VersionedToString for Events {
     }
 }
 
+impl VersionedWrite for Events {
+    fn write_versioned<W: Write>(&self, f: &mut W, version: Version) -> Option<fmt::Result> {
+        Some(self.write_variables(f, version, &[]))
+    }
+}
+
 impl VersionedDefault for Events {
     fn default(_: Version) -> Option<Self> {
         Some(Events(Vec::new()))
@@ -327,6 +412,7 @@ impl VersionedDefault for Events {
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 /// All possible events types.
 pub enum Event {
@@ -363,22 +449,136 @@ impl Event {
             Event::AudioSample(audio_sample) => Some(audio_sample.to_string(version).unwrap()),
         }
     }
+
+    /// Writes the same output as `to_string_variables` straight to the writer.
+    /// - Returns `None` without writing anything if there's no output for the version.
+    fn write_variables<W: Write>(
+        &self,
+        f: &mut W,
+        version: Version,
+        variables: &[Variable],
+    ) -> Option<fmt::Result> {
+        match self {
+            Event::Comment(comment) => Some(write!(f, "//{comment}")),
+            Event::Background(background) => background.write_variables(f, version, &[]),
+            Event::Video(video) => video.write_variables(f, version, &[]),
+            Event::SpriteLegacy(sprite) => sprite.write_variables(f, version, variables),
+            Event::AnimationLegacy(animation) => animation.write_variables(f, version, variables),
+            Event::SampleLegacy(sample) => sample.write_variables(f, version, variables),
+            Event::StoryboardObject(object) => object.write_variables(f, version, variables),
+            _ => self
+                .to_string_variables(version, variables)
+                .map(|s| f.write_str(&s)),
+        }
+    }
+
+    /// Changes the speed of the event to the `rate`, dividing its times and the times of its commands by the rate.
+    /// - Frame delays of storyboard animations are scaled too, so they play at the same speed as the beatmap.
+    pub fn scale_time(&mut self, rate: f64) {
+        let commands = match self {
+            Event::Comment(_) => return,
+            Event::Background(background) => {
+                background.start_time = scale_integer(background.start_time, rate);
+                &mut background.commands
+            }
+            Event::Video(video) => {
+                video.start_time = scale_integer(video.start_time, rate);
+                &mut video.commands
+            }
+            Event::Break(break_) => {
+                break_.start_time = scale_integer(break_.start_time, rate);
+                break_.end_time = scale_integer(break_.end_time, rate);
+                return;
+            }
+            Event::ColourTransformation(colour_trans) => {
+                colour_trans.start_time = scale_integer(colour_trans.start_time, rate);
+                return;
+            }
+            Event::SpriteLegacy(sprite) => &mut sprite.commands,
+            Event::AnimationLegacy(animation) => &mut animation.commands,
+            Event::SampleLegacy(sample) => {
+                scale_decimal(&mut sample.time, rate, true);
+                &mut sample.commands
+            }
+            Event::StoryboardObject(object) => {
+                if let ObjectType::Animation(animation) = &mut object.object_type {
+                    if let Some(frame_delay) = animation
+                        .frame_delay
+                        .to_f64()
+                        .and_then(|frame_delay| rust_decimal::Decimal::from_f64(frame_delay / rate))
+                    {
+                        animation.frame_delay = frame_delay;
+                    }
+                }
+                &mut object.commands
+            }
+            Event::AudioSample(audio_sample) => {
+                audio_sample.time = scale_integer(audio_sample.time, rate);
+                return;
+            }
+        };
+
+        for cmd in commands {
+            cmd.scale_time(rate);
+        }
+    }
+
+    /// Shifts the times of the event and the times of its commands by `delta` milliseconds.
+    /// - Commands in loops and triggers aren't shifted, like [`Command::shift_time`].
+    pub fn shift_time(&mut self, delta: Integer) {
+        let commands = match self {
+            Event::Comment(_) => return,
+            Event::Background(background) => {
+                background.start_time = background.start_time.saturating_add(delta);
+                &mut background.commands
+            }
+            Event::Video(video) => {
+                video.start_time = video.start_time.saturating_add(delta);
+                &mut video.commands
+            }
+            Event::Break(break_) => {
+                break_.start_time = break_.start_time.saturating_add(delta);
+                break_.end_time = break_.end_time.saturating_add(delta);
+                return;
+            }
+            Event::ColourTransformation(colour_trans) => {
+                colour_trans.start_time = colour_trans.start_time.saturating_add(delta);
+                return;
+            }
+            Event::SpriteLegacy(sprite) => &mut sprite.commands,
+            Event::AnimationLegacy(animation) => &mut animation.commands,
+            Event::SampleLegacy(sample) => {
+                shift_decimal(&mut sample.time, delta);
+                &mut sample.commands
+            }
+            Event::StoryboardObject(object) => &mut object.commands,
+            Event::AudioSample(audio_sample) => {
+                audio_sample.time = audio_sample.time.saturating_add(delta);
+                return;
+            }
+        };
+
+        for cmd in commands {
+            cmd.shift_time(delta);
+        }
+    }
 }
 
-fn commands_to_string_variables(
+/// Writes the commands on new lines, indented by their depth.
+fn write_commands_variables<W: Write>(
+    f: &mut W,
     cmds: &[Command],
     version: Version,
     variables: &[Variable],
-) -> Option<String> {
-    let mut builder = Vec::new();
+) -> fmt::Result {
+    let mut write_cmd = |cmd: &Command, indentation: usize| {
+        write!(f, "\n{:indentation$}", "")?;
+        cmd.write_variables(f, version, variables)
+    };
     let mut indentation = 1usize;
 
     for cmd in cmds {
-        builder.push(format!(
-            "{}{}",
-            " ".repeat(indentation),
-            cmd.to_string_variables(version, variables).unwrap()
-        ));
+        write_cmd(cmd, indentation)?;
 
         if let CommandProperties::Loop { commands, .. }
         | CommandProperties::Trigger { commands, .. } = &cmd.properties
@@ -399,11 +599,7 @@ fn commands_to_string_variables(
                 let cmd = &current_cmds[current_index];
                 current_index += 1;
 
-                builder.push(format!(
-                    "{}{}",
-                    " ".repeat(indentation),
-                    cmd.to_string_variables(version, variables).unwrap()
-                ));
+                write_cmd(cmd, indentation)?;
                 match &cmd.properties {
                     CommandProperties::Loop { commands, .. }
                     | CommandProperties::Trigger { commands, .. }
@@ -439,7 +635,7 @@ fn commands_to_string_variables(
         }
     }
 
-    Some(builder.join("\n"))
+    Ok(())
 }
 
 pub trait EventWithCommands {
@@ -496,21 +692,30 @@ pub trait EventWithCommands {
     /// - Use this in the `to_string` method with an empty `variables` array.
     fn to_string_variables(&self, version: Version, variables: &[Variable]) -> Option<String> {
         match self.to_string_cmd(version) {
-            Some(s) => {
-                let cmds = match commands_to_string_variables(self.commands(), version, variables) {
-                    Some(mut cmds) => {
-                        if !cmds.is_empty() {
-                            cmds = format!("\n{cmds}");
-                        }
+            Some(mut s) => {
+                write_commands_variables(&mut s, self.commands(), version, variables)
+                    .expect("writing to a String can't fail");
 
-                        cmds
-                    }
-                    None => return None,
-                };
-
-                Some(format!("{s}{cmds}"))
+                Some(s)
             }
             None => None,
         }
     }
+
+    /// Writes the same output as `to_string_variables` straight to the writer.
+    /// - Returns `None` without writing anything if there's no output for the version.
+    fn write_variables<W: Write>(
+        &self,
+        f: &mut W,
+        version: Version,
+        variables: &[Variable],
+    ) -> Option<fmt::Result>
+    where
+        Self: Sized,
+    {
+        self.to_string_cmd(version).map(|s| {
+            f.write_str(&s)?;
+            write_commands_variables(f, self.commands(), version, variables)
+        })
+    }
 }
@@ -1,11 +1,13 @@
 pub mod error;
 pub mod types;
 
-use std::fmt::Display;
+use std::fmt::{self, Display, Write};
 
 use super::error::*;
 use super::types::*;
+use crate::helper::{write_versioned_to_string, VersionedWrite};
 use crate::osb::Variable;
+use crate::osu_file::rate::scale_integer;
 use crate::osu_file::types::Decimal;
 use crate::osu_file::{Integer, Version, VersionedFromStr, VersionedToString};
 use crate::parsers::*;
@@ -22,98 +24,128 @@ pub use error::*;
 pub use types::*;
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Command {
     pub start_time: Option<Integer>,
     pub properties: CommandProperties,
 }
 
-fn continuing_to_string<T>(continuing: &[T]) -> String
-where
-    T: Display,
-{
-    if continuing.is_empty() {
-        String::new()
-    } else {
-        format!(
-            ",{}",
-            continuing
-                .iter()
-                .map(|field| field.to_string())
-                .collect::<Vec<_>>()
-                .join(",")
-        )
+/// Writes each of the continuing fields after a comma.
+fn write_continuing<W: Write, T: Display>(f: &mut W, continuing: &[T]) -> fmt::Result {
+    for field in continuing {
+        write!(f, ",{field}")?;
     }
+
+    Ok(())
 }
 
-fn continuing_versioned_to_string<T>(continuing: &[T], version: Version) -> String
-where
-    T: VersionedToString,
-{
-    if continuing.is_empty() {
-        String::new()
-    } else {
-        format!(
-            ",{}",
-            continuing
-                .iter()
-                .map(|field| field.to_string(version).unwrap())
-                .collect::<Vec<_>>()
-                .join(",")
-        )
+/// Writes each of the continuing fields after a comma.
+fn write_continuing_versioned<W: Write, T: VersionedWrite>(
+    f: &mut W,
+    continuing: &[T],
+    version: Version,
+) -> fmt::Result {
+    for field in continuing {
+        f.write_char(',')?;
+        field.write_versioned(f, version).unwrap()?;
     }
+
+    Ok(())
 }
 
 impl VersionedToString for Command {
     fn to_string(&self, version: Version) -> Option<String> {
-        self.to_string_variables(version, &[])
+        write_versioned_to_string(self, version)
+    }
+}
+
+impl VersionedWrite for Command {
+    fn write_versioned<W: Write>(&self, f: &mut W, version: Version) -> Option<fmt::Result> {
+        Some(self.write_variables(f, version, &[]))
     }
 }
 
 impl Command {
     pub fn to_string_variables(&self, version: Version, variables: &[Variable]) -> Option<String> {
-        let end_time_to_string =
-            |end_time: &Option<i32>| end_time.map_or("".to_string(), |t| t.to_string());
-        let variable_replace = |header, cmd: String| {
-            let mut cmd = cmd;
-
-            for variable in variables {
-                if cmd.contains(&variable.value) {
-                    cmd = cmd.replace(&variable.value, &format!("${}", variable.name));
-                }
+        let mut s = String::new();
+        self.write_variables(&mut s, version, variables)
+            .expect("writing to a String can't fail");
+
+        Some(s)
+    }
+
+    /// Writes the same output as `to_string_variables` straight to the writer.
+    /// - The fields are only collected into a `String` when there are `variables` to replace in them.
+    pub(crate) fn write_variables<W: Write>(
+        &self,
+        f: &mut W,
+        version: Version,
+        variables: &[Variable],
+    ) -> fmt::Result {
+        let header = match &self.properties {
+            CommandProperties::Fade { .. } => "F",
+            CommandProperties::Move { .. } => "M",
+            CommandProperties::MoveX { .. } => "MX",
+            CommandProperties::MoveY { .. } => "MY",
+            CommandProperties::Scale { .. } => "S",
+            CommandProperties::VectorScale { .. } => "V",
+            CommandProperties::Rotate { .. } => "R",
+            CommandProperties::Colour { .. } => "C",
+            CommandProperties::Parameter { .. } => "P",
+            CommandProperties::Loop { .. } => "L",
+            CommandProperties::Trigger { .. } => "T",
+        };
+        write!(f, "{header},")?;
+
+        if variables.is_empty() {
+            return self.write_fields(f, version);
+        }
+
+        let mut cmd = String::new();
+        self.write_fields(&mut cmd, version)?;
+
+        for variable in variables {
+            if cmd.contains(&variable.value) {
+                cmd = cmd.replace(&variable.value, &format!("${}", variable.name));
             }
+        }
+
+        f.write_str(&cmd)
+    }
 
-            format!("{header},{cmd}")
+    /// Writes the fields after the command's header.
+    fn write_fields<W: Write>(&self, f: &mut W, version: Version) -> fmt::Result {
+        let write_times = |f: &mut W, easing: &Easing, end_time: &Option<Integer>| {
+            easing.write_versioned(f, version).unwrap()?;
+            f.write_char(',')?;
+            if let Some(start_time) = self.start_time {
+                write!(f, "{start_time}")?;
+            }
+            f.write_char(',')?;
+            if let Some(end_time) = end_time {
+                write!(f, "{end_time}")?;
+            }
+            f.write_char(',')
         };
-        let start_time = self.start_time.map_or(String::new(), |t| t.to_string());
 
-        let cmd_str = match &self.properties {
+        match &self.properties {
             CommandProperties::Fade {
                 easing,
                 end_time,
                 start_opacity,
                 continuing_opacities,
             } => {
-                let cmd = format!(
-                    "{},{start_time},{},{start_opacity}{}",
-                    easing.to_string(version).unwrap(),
-                    end_time_to_string(end_time),
-                    continuing_to_string(continuing_opacities),
-                );
-
-                variable_replace("F", cmd)
+                write_times(f, easing, end_time)?;
+                write!(f, "{start_opacity}")?;
+                write_continuing(f, continuing_opacities)
             }
             CommandProperties::Move {
                 easing,
                 end_time,
                 positions_xy,
             } => {
-                let cmd = format!(
-                    "{},{start_time},{},{positions_xy}",
-                    easing.to_string(version).unwrap(),
-                    end_time_to_string(end_time),
-                );
-
-                variable_replace("M", cmd)
+                write_times(f, easing, end_time)?;
+                write!(f, "{positions_xy}")
             }
             CommandProperties::MoveX {
                 easing,
@@ -121,14 +153,9 @@ impl Command {
                 start_x,
                 continuing_x,
             } => {
-                let cmd = format!(
-                    "{},{start_time},{},{start_x}{}",
-                    easing.to_string(version).unwrap(),
-                    end_time_to_string(end_time),
-                    continuing_to_string(continuing_x),
-                );
-
-                variable_replace("MX", cmd)
+                write_times(f, easing, end_time)?;
+                write!(f, "{start_x}")?;
+                write_continuing(f, continuing_x)
             }
             CommandProperties::MoveY {
                 easing,
@@ -136,14 +163,9 @@ impl Command {
                 start_y,
                 continuing_y,
             } => {
-                let cmd = format!(
-                    "{},{start_time},{},{start_y}{}",
-                    easing.to_string(version).unwrap(),
-                    end_time_to_string(end_time),
-                    continuing_to_string(continuing_y),
-                );
-
-                variable_replace("MY", cmd)
+                write_times(f, easing, end_time)?;
+                write!(f, "{start_y}")?;
+                write_continuing(f, continuing_y)
             }
             CommandProperties::Scale {
                 easing,
@@ -151,28 +173,17 @@ impl Command {
                 start_scale,
                 continuing_scales,
             } => {
-                let cmd = format!(
-                    "{},{start_time},{},{start_scale}{}",
-                    easing.to_string(version).unwrap(),
-                    end_time_to_string(end_time),
-                    continuing_to_string(continuing_scales),
-                );
-
-                variable_replace("S", cmd)
+                write_times(f, easing, end_time)?;
+                write!(f, "{start_scale}")?;
+                write_continuing(f, continuing_scales)
             }
             CommandProperties::VectorScale {
                 easing,
                 end_time,
                 scales_xy,
             } => {
-                let cmd = format!(
-                    "{},{start_time},{},{}",
-                    easing.to_string(version).unwrap(),
-                    end_time_to_string(end_time),
-                    scales_xy,
-                );
-
-                variable_replace("V", cmd)
+                write_times(f, easing, end_time)?;
+                write!(f, "{scales_xy}")
             }
             CommandProperties::Rotate {
                 easing,
@@ -180,28 +191,17 @@ impl Command {
                 start_rotation,
                 continuing_rotations,
             } => {
-                let cmd = format!(
-                    "{},{start_time},{},{start_rotation}{}",
-                    easing.to_string(version).unwrap(),
-                    end_time_to_string(end_time),
-                    continuing_to_string(continuing_rotations),
-                );
-
-                variable_replace("R", cmd)
+                write_times(f, easing, end_time)?;
+                write!(f, "{start_rotation}")?;
+                write_continuing(f, continuing_rotations)
             }
             CommandProperties::Colour {
                 easing,
                 end_time,
                 colours,
             } => {
-                let cmd = format!(
-                    "{},{start_time},{},{}",
-                    easing.to_string(version).unwrap(),
-                    end_time_to_string(end_time),
-                    colours.to_string(version).unwrap(),
-                );
-
-                variable_replace("C", cmd)
+                write_times(f, easing, end_time)?;
+                colours.write_versioned(f, version).unwrap()
             }
             CommandProperties::Parameter {
                 easing,
@@ -209,24 +209,19 @@ impl Command {
                 parameter,
                 continuing_parameters,
             } => {
-                let cmd = format!(
-                    "{},{start_time},{},{}{}",
-                    easing.to_string(version).unwrap(),
-                    end_time_to_string(end_time),
-                    parameter.to_string(version).unwrap(),
-                    continuing_versioned_to_string(continuing_parameters, version),
-                );
-
-                variable_replace("P", cmd)
+                write_times(f, easing, end_time)?;
+                parameter.write_versioned(f, version).unwrap()?;
+                write_continuing_versioned(f, continuing_parameters, version)
             }
             CommandProperties::Loop {
                 loop_count,
                 // ignore commands since its handled separately
                 commands: _,
             } => {
-                let cmd = format!("{start_time},{loop_count}");
-
-                variable_replace("L", cmd)
+                if let Some(start_time) = self.start_time {
+                    write!(f, "{start_time}")?;
+                }
+                write!(f, ",{loop_count}")
             }
             CommandProperties::Trigger {
                 trigger_type,
@@ -235,54 +230,89 @@ impl Command {
                 // ignore commands since its handled separately
                 commands: _,
             } => {
-                let cmd = format!(
-                    "{}{}{}{}{}",
-                    trigger_type.to_string(version).unwrap(),
-                    if self.start_time.is_some() || end_time.is_some() || group_number.is_some() {
-                        ",".to_string()
-                    } else {
-                        String::new()
-                    },
-                    match self.start_time {
-                        Some(t) =>
-                            if end_time.is_some() || group_number.is_some() {
-                                format!("{t},")
-                            } else {
-                                t.to_string()
-                            },
-                        None =>
-                            if end_time.is_some() || group_number.is_some() {
-                                ",".to_string()
-                            } else {
-                                String::new()
-                            },
-                    },
-                    match end_time {
-                        Some(t) =>
-                            if group_number.is_some() {
-                                format!("{t},")
-                            } else {
-                                t.to_string()
-                            },
-                        None =>
-                            if group_number.is_some() {
-                                ",".to_string()
-                            } else {
-                                String::new()
-                            },
-                    },
-                    group_number.map_or(String::new(), |group_number| group_number.to_string()),
-                );
+                trigger_type.write_versioned(f, version).unwrap()?;
+
+                // trailing fields that aren't set are left out
+                let fields = [self.start_time, *end_time, *group_number];
+                let len = fields
+                    .iter()
+                    .rposition(Option::is_some)
+                    .map_or(0, |i| i + 1);
+
+                for field in &fields[..len] {
+                    f.write_char(',')?;
+                    if let Some(field) = field {
+                        write!(f, "{field}")?;
+                    }
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Changes the speed of the command to the `rate`, dividing its start and end times by the rate.
+    /// - Commands in loops and triggers are scaled too, since their times are relative to the loop or trigger.
+    pub fn scale_time(&mut self, rate: f64) {
+        let scale = |time: &mut Option<Integer>| {
+            if let Some(time) = time {
+                *time = scale_integer(*time, rate);
+            }
+        };
+
+        scale(&mut self.start_time);
+
+        match &mut self.properties {
+            CommandProperties::Fade { end_time, .. }
+            | CommandProperties::Move { end_time, .. }
+            | CommandProperties::MoveX { end_time, .. }
+            | CommandProperties::MoveY { end_time, .. }
+            | CommandProperties::Scale { end_time, .. }
+            | CommandProperties::VectorScale { end_time, .. }
+            | CommandProperties::Rotate { end_time, .. }
+            | CommandProperties::Colour { end_time, .. }
+            | CommandProperties::Parameter { end_time, .. } => scale(end_time),
+            CommandProperties::Loop { commands, .. } => {
+                commands.iter_mut().for_each(|cmd| cmd.scale_time(rate))
+            }
+            CommandProperties::Trigger {
+                end_time, commands, ..
+            } => {
+                scale(end_time);
+                commands.iter_mut().for_each(|cmd| cmd.scale_time(rate));
+            }
+        }
+    }
 
-                variable_replace("T", cmd)
+    /// Shifts the start and end times of the command by `delta` milliseconds.
+    /// - Commands in loops and triggers aren't shifted, since their times are relative to the loop or trigger.
+    pub fn shift_time(&mut self, delta: Integer) {
+        let shift = |time: &mut Option<Integer>| {
+            if let Some(time) = time {
+                *time = time.saturating_add(delta);
             }
         };
 
-        Some(cmd_str)
+        shift(&mut self.start_time);
+
+        match &mut self.properties {
+            CommandProperties::Fade { end_time, .. }
+            | CommandProperties::Move { end_time, .. }
+            | CommandProperties::MoveX { end_time, .. }
+            | CommandProperties::MoveY { end_time, .. }
+            | CommandProperties::Scale { end_time, .. }
+            | CommandProperties::VectorScale { end_time, .. }
+            | CommandProperties::Rotate { end_time, .. }
+            | CommandProperties::Colour { end_time, .. }
+            | CommandProperties::Parameter { end_time, .. }
+            | CommandProperties::Trigger { end_time, .. } => shift(end_time),
+            CommandProperties::Loop { .. } => (),
+        }
     }
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum CommandProperties {
     Fade {
@@ -1,6 +1,7 @@
 use super::*;
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ContinuingFields<T> {
     pub(crate) start: (T, T),
     pub(crate) continuing: Vec<(T, Option<T>)>,
@@ -83,20 +84,21 @@ where
     T: Display,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut builder = vec![self.start.0.to_string(), self.start.1.to_string()];
+        write!(f, "{},{}", self.start.0, self.start.1)?;
 
         for fields in &self.continuing {
-            builder.push(fields.0.to_string());
+            write!(f, ",{}", fields.0)?;
             if let Some(field) = &fields.1 {
-                builder.push(field.to_string());
+                write!(f, ",{field}")?;
             }
         }
 
-        write!(f, "{}", builder.join(","))
+        Ok(())
     }
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Colours {
     pub start: (u8, u8, u8),
     pub continuing: Vec<(u8, Option<u8>, Option<u8>)>,
@@ -196,23 +198,29 @@ impl Colours {
 }
 
 impl VersionedToString for Colours {
-    fn to_string(&self, _: Version) -> Option<String> {
-        let mut builder = vec![
-            self.start.0.to_string(),
-            self.start.1.to_string(),
-            self.start.2.to_string(),
-        ];
+    fn to_string(&self, version: Version) -> Option<String> {
+        write_versioned_to_string(self, version)
+    }
+}
 
-        for fields in &self.continuing {
-            builder.push(fields.0.to_string());
-            if let Some(field) = &fields.1 {
-                builder.push(field.to_string());
-            }
-            if let Some(field) = &fields.2 {
-                builder.push(field.to_string());
+impl VersionedWrite for Colours {
+    fn write_versioned<W: Write>(&self, f: &mut W, _: Version) -> Option<fmt::Result> {
+        let write_colours = |f: &mut W| {
+            write!(f, "{},{},{}", self.start.0, self.start.1, self.start.2)?;
+
+            for fields in &self.continuing {
+                write!(f, ",{}", fields.0)?;
+                if let Some(field) = &fields.1 {
+                    write!(f, ",{field}")?;
+                }
+                if let Some(field) = &fields.2 {
+                    write!(f, ",{field}")?;
+                }
             }
-        }
 
-        Some(builder.join(","))
+            Ok(())
+        };
+
+        Some(write_colours(f))
     }
 }
@@ -22,6 +22,7 @@ use super::cmds::*;
 use super::error::*;
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum Layer {
     Background,
@@ -61,6 +62,7 @@ impl VersionedToString for Layer {
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Object {
     pub layer: Layer,
     pub origin: Origin,
@@ -234,6 +236,7 @@ impl VersionedFromStr for Object {
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Animation {
     pub frame_count: u32,
     pub frame_delay: rust_decimal::Decimal,
@@ -265,6 +268,7 @@ impl Animation {
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Sprite {
     pub filepath: FilePath,
 }
@@ -282,6 +286,7 @@ impl Sprite {
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum ObjectType {
     Sprite(Sprite),
@@ -289,6 +294,7 @@ pub enum ObjectType {
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Origin {
     /// Origin type.
     /// - `Left` variant would be the valid enum variants.
@@ -298,6 +304,7 @@ pub struct Origin {
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum OriginType {
     TopLeft,
@@ -456,6 +463,7 @@ impl VersionedFrom<OriginType> for Integer {
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum LoopType {
     LoopForever,
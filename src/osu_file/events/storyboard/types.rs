@@ -1,4 +1,7 @@
+use std::fmt::{self, Write};
+
 use crate::{
+    helper::{write_versioned_to_string, VersionedWrite},
     osu_file::{Version, VersionedFromStr, VersionedToString},
     Integer, VersionedFrom,
 };
@@ -6,6 +9,7 @@ use crate::{
 use super::error::*;
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum TriggerType {
     HitSound {
@@ -111,28 +115,45 @@ impl VersionedFromStr for TriggerType {
 
 impl VersionedToString for TriggerType {
     fn to_string(&self, version: Version) -> Option<String> {
-        let trigger_type = match self {
+        write_versioned_to_string(self, version)
+    }
+}
+
+impl VersionedWrite for TriggerType {
+    fn write_versioned<W: Write>(&self, f: &mut W, version: Version) -> Option<fmt::Result> {
+        let write_trigger_type = |f: &mut W| match self {
             TriggerType::HitSound {
                 sample_set,
                 additions_sample_set,
                 addition,
                 custom_sample_set,
-            } => format!(
-                "HitSound{}{}{}{}",
-                sample_set.map_or(String::new(), |s| s.to_string(version).unwrap()),
-                additions_sample_set.map_or(String::new(), |s| s.to_string(version).unwrap()),
-                addition.map_or(String::new(), |s| s.to_string(version).unwrap()),
-                custom_sample_set.map_or(String::new(), |s| s.to_string())
-            ),
-            TriggerType::Passing => "Passing".to_string(),
-            TriggerType::Failing => "Failing".to_string(),
+            } => {
+                f.write_str("HitSound")?;
+                if let Some(sample_set) = sample_set {
+                    sample_set.write_versioned(f, version).unwrap()?;
+                }
+                if let Some(additions_sample_set) = additions_sample_set {
+                    additions_sample_set.write_versioned(f, version).unwrap()?;
+                }
+                if let Some(addition) = addition {
+                    addition.write_versioned(f, version).unwrap()?;
+                }
+                if let Some(custom_sample_set) = custom_sample_set {
+                    write!(f, "{custom_sample_set}")?;
+                }
+
+                Ok(())
+            }
+            TriggerType::Passing => f.write_str("Passing"),
+            TriggerType::Failing => f.write_str("Failing"),
         };
 
-        Some(trigger_type)
+        Some(write_trigger_type(f))
     }
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum SampleSet {
     All,
@@ -156,7 +177,13 @@ impl VersionedFromStr for SampleSet {
 }
 
 impl VersionedToString for SampleSet {
-    fn to_string(&self, _: Version) -> Option<String> {
+    fn to_string(&self, version: Version) -> Option<String> {
+        write_versioned_to_string(self, version)
+    }
+}
+
+impl VersionedWrite for SampleSet {
+    fn write_versioned<W: Write>(&self, f: &mut W, _: Version) -> Option<fmt::Result> {
         let sample_set = match self {
             SampleSet::All => "All",
             SampleSet::Normal => "Normal",
@@ -164,11 +191,12 @@ impl VersionedToString for SampleSet {
             SampleSet::Drum => "Drum",
         };
 
-        Some(sample_set.to_string())
+        Some(f.write_str(sample_set))
     }
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum Addition {
     Whistle,
@@ -190,18 +218,25 @@ impl VersionedFromStr for Addition {
 }
 
 impl VersionedToString for Addition {
-    fn to_string(&self, _: Version) -> Option<String> {
+    fn to_string(&self, version: Version) -> Option<String> {
+        write_versioned_to_string(self, version)
+    }
+}
+
+impl VersionedWrite for Addition {
+    fn write_versioned<W: Write>(&self, f: &mut W, _: Version) -> Option<fmt::Result> {
         let addtion = match self {
             Addition::Whistle => "Whistle",
             Addition::Finish => "Finish",
             Addition::Clap => "Clap",
         };
 
-        Some(addtion.to_string())
+        Some(f.write_str(addtion))
     }
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum Easing {
     Linear,
@@ -330,11 +365,18 @@ impl VersionedFrom<Easing> for Integer {
 
 impl VersionedToString for Easing {
     fn to_string(&self, version: Version) -> Option<String> {
-        <Integer as VersionedFrom<Easing>>::from(*self, version).map(|value| value.to_string())
+        write_versioned_to_string(self, version)
+    }
+}
+
+impl VersionedWrite for Easing {
+    fn write_versioned<W: Write>(&self, f: &mut W, version: Version) -> Option<fmt::Result> {
+        <Integer as VersionedFrom<Easing>>::from(*self, version).map(|value| write!(f, "{value}"))
     }
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum Parameter {
     ImageFlipHorizontal,
@@ -343,14 +385,20 @@ pub enum Parameter {
 }
 
 impl VersionedToString for Parameter {
-    fn to_string(&self, _: Version) -> Option<String> {
+    fn to_string(&self, version: Version) -> Option<String> {
+        write_versioned_to_string(self, version)
+    }
+}
+
+impl VersionedWrite for Parameter {
+    fn write_versioned<W: Write>(&self, f: &mut W, _: Version) -> Option<fmt::Result> {
         let parameter = match self {
             Parameter::ImageFlipHorizontal => "H",
             Parameter::ImageFlipVertical => "V",
             Parameter::UseAdditiveColourBlending => "A",
         };
 
-        Some(parameter.to_string())
+        Some(f.write_str(parameter))
     }
 }
 
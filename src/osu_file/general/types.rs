@@ -7,6 +7,7 @@ use super::error::*;
 
 /// Speed of the countdown before the first hitobject.
 #[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum Countdown {
     /// No countdown.
@@ -68,6 +69,7 @@ impl VersionedDefault for Countdown {
 
 /// Sample set that will be used if timing points do not override it
 #[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum SampleSet {
     /// The `Normal` sample set.
@@ -129,6 +131,7 @@ impl VersionedDefault for SampleSet {
 
 /// Game mode of the .osu file
 #[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum Mode {
     /// Osu! gamemode.
@@ -185,6 +188,7 @@ impl VersionedDefault for Mode {
 
 /// Draw order of hit circle overlays compared to hit numbers
 #[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum OverlayPosition {
     /// Use skin setting.
@@ -0,0 +1,97 @@
+//! Combo numbers and combo colours of hitobjects.
+
+use super::{HitObjectParams, HitObjects};
+use crate::osu_file::{colours::Rgb, OsuFile, Version};
+
+/// Combo of a hitobject and the colour it's drawn with.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ComboInfo {
+    /// Index of the combo the hitobject is in, counting from `0`.
+    pub combo_index: usize,
+    /// Index of the hitobject in its combo, counting from `0`.
+    /// - The combo number shown on the hitobject is one more than this.
+    pub index_in_combo: usize,
+    /// Index of the combo colour, which includes the skipped colours of `combo_skip_count`.
+    /// - The colour used is this index wrapped around the amount of combo colours.
+    pub colour_index: usize,
+    /// Combo colour of the hitobject, or `None` if there are no combo colours.
+    pub colour: Option<Rgb>,
+}
+
+impl HitObjects {
+    /// Resolves the combo and combo colour of each hitobject the same way osu! does.
+    /// - The first hitobject and hitobjects with `new_combo` start a new combo, which skips `combo_skip_count` colours.
+    /// - Spinners don't start a combo themselves, but the next hitobject starts a new combo with their `combo_skip_count` added if the spinner has `new_combo`, or always for versions 8 and below.
+    /// - Like osu!, the colour index of the first combo is `1`, so it uses the second colour.
+    pub fn combos(&self, combo_colours: &[Rgb], version: Version) -> Vec<ComboInfo> {
+        let mut combos: Vec<ComboInfo> = Vec::with_capacity(self.0.len());
+        let mut force_new_combo = false;
+        let mut extra_skip_count = 0;
+
+        for hitobject in &self.0 {
+            let skip_count = hitobject.combo_skip_count.get() as usize;
+
+            let (new_combo, skip_count) = match hitobject.obj_params {
+                HitObjectParams::Spinner { .. } => {
+                    force_new_combo |= version <= 8 || hitobject.new_combo;
+                    extra_skip_count += skip_count;
+                    (false, 0)
+                }
+                _ => {
+                    let new_combo = hitobject.new_combo || force_new_combo;
+                    let skip_count = skip_count + extra_skip_count;
+                    force_new_combo = false;
+                    extra_skip_count = 0;
+                    (new_combo, skip_count)
+                }
+            };
+
+            let combo = match combos.last() {
+                Some(last) if !new_combo => ComboInfo {
+                    index_in_combo: last.index_in_combo + 1,
+                    ..*last
+                },
+                last => ComboInfo {
+                    combo_index: last.map_or(0, |last| last.combo_index + 1),
+                    index_in_combo: 0,
+                    colour_index: last.map_or(0, |last| last.colour_index) + skip_count + 1,
+                    colour: None,
+                },
+            };
+
+            combos.push(ComboInfo {
+                colour: match combo_colours.len() {
+                    0 => None,
+                    len => Some(combo_colours[combo.colour_index % len]),
+                },
+                ..combo
+            });
+        }
+
+        combos
+    }
+}
+
+impl OsuFile {
+    /// Resolves the combo and combo colour of each hitobject with the combo colours of the file.
+    /// - The `fallback_colours` are used if the file has no combo colours, like the colours of a skin.
+    /// - Same as [`HitObjects::combos`].
+    pub fn combos(&self, fallback_colours: &[Rgb]) -> Vec<ComboInfo> {
+        let hitobjects = match &self.hitobjects {
+            Some(hitobjects) => hitobjects,
+            None => return Vec::new(),
+        };
+        let combo_colours = self
+            .colours
+            .as_ref()
+            .map(|colours| colours.combo_colours())
+            .unwrap_or_default();
+
+        if combo_colours.is_empty() {
+            hitobjects.combos(fallback_colours, self.version)
+        } else {
+            hitobjects.combos(&combo_colours, self.version)
+        }
+    }
+}
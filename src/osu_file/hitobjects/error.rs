@@ -1,11 +1,14 @@
 //! Module defining `error` types that's used for the `hitobject` related modules.
 
 use std::num::ParseIntError;
+use std::ops::Range;
 
 use strum_macros::{EnumString, IntoStaticStr};
 use thiserror::Error;
 
 use crate::helper::macros::verbose_error_to_error;
+use crate::helper::{comma_field_columns, last_comma_field_columns, line_end_columns};
+use crate::osu_file::LineError;
 
 #[derive(Debug, Error)]
 #[error(transparent)]
@@ -120,6 +123,41 @@ pub enum ParseHitObjectError {
 
 verbose_error_to_error!(ParseHitObjectError);
 
+impl LineError for ParseHitObjectError {
+    fn columns(&self, line: &str) -> Option<Range<usize>> {
+        let field = match self {
+            ParseHitObjectError::InvalidX => 0,
+            ParseHitObjectError::InvalidY => 1,
+            ParseHitObjectError::InvalidTime => 2,
+            ParseHitObjectError::InvalidObjType | ParseHitObjectError::UnknownObjType => 3,
+            ParseHitObjectError::InvalidHitSound => 4,
+            ParseHitObjectError::InvalidCurveType
+            | ParseHitObjectError::InvalidCurvePoint
+            | ParseHitObjectError::InvalidEndTime => 5,
+            ParseHitObjectError::InvalidSlidesCount => 6,
+            ParseHitObjectError::InvalidLength => 7,
+            ParseHitObjectError::InvalidEdgeSound => 8,
+            ParseHitObjectError::InvalidEdgeSet => 9,
+            ParseHitObjectError::InvalidHitSample => return Some(last_comma_field_columns(line)),
+            ParseHitObjectError::MissingX
+            | ParseHitObjectError::MissingY
+            | ParseHitObjectError::MissingTime
+            | ParseHitObjectError::MissingObjType
+            | ParseHitObjectError::MissingHitSound
+            | ParseHitObjectError::MissingHitSample
+            | ParseHitObjectError::MissingCurveType
+            | ParseHitObjectError::MissingCurvePoint
+            | ParseHitObjectError::MissingEdgeSound
+            | ParseHitObjectError::MissingEdgeSet
+            | ParseHitObjectError::MissingSlidesCount
+            | ParseHitObjectError::MissingLength
+            | ParseHitObjectError::MissingEndTime => return Some(line_end_columns(line)),
+        };
+
+        Some(comma_field_columns(line, field))
+    }
+}
+
 #[derive(Debug, Error, EnumString, IntoStaticStr)]
 #[non_exhaustive]
 /// Error used when there was a problem parsing a `str` into a [`hitsample`][super::types::HitSample].
@@ -1,6 +1,15 @@
+pub mod combo;
 pub mod error;
+pub mod nested;
+pub mod path;
+pub mod snap;
+pub mod stacking;
+pub mod timing;
+pub mod transform;
 pub mod types;
 
+use std::fmt::{self, Write};
+
 use crate::osu_file::types::Decimal;
 use either::Either;
 use nom::branch::alt;
@@ -15,11 +24,21 @@ use rust_decimal_macros::dec;
 use crate::helper::*;
 use crate::parsers::*;
 
+pub use combo::ComboInfo;
 pub use error::*;
+pub use nested::{NestedObject, NestedObjectKind};
+pub use path::{SliderPath, Vec2};
+pub use snap::{HitObjectSnap, SnapPoint};
+pub use stacking::StackedObject;
+pub use timing::SliderSpan;
+pub use transform::{Transform, TransformOptions};
 pub use types::*;
 
+use super::offset::shift_decimal;
+use super::rate::scale_decimal;
 use super::Error;
 use super::Integer;
+use super::ParseOptions;
 use super::Position;
 use super::Version;
 use super::VersionedDefault;
@@ -28,38 +47,69 @@ use super::VersionedToString;
 use super::VersionedTryFrom;
 
 #[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HitObjects(pub Vec<HitObject>);
 
+/// Hitobjects of a beatmap without a `[HitObjects]` section, for borrowing instead of cloning a default.
+pub(crate) static EMPTY_HITOBJECTS: HitObjects = HitObjects(Vec::new());
+
 impl VersionedFromStr for HitObjects {
     type Err = Error<ParseError>;
 
     fn from_str(s: &str, version: Version) -> std::result::Result<Option<Self>, Self::Err> {
-        let mut hitobjects = Vec::new();
+        HitObjects::from_str_errors(s, version, &ParseOptions::default())
+            .map(|(hitobjects, _)| hitobjects)
+    }
+}
 
-        for (line_index, s) in s.lines().enumerate() {
-            if s.trim().is_empty() {
-                continue;
-            }
+impl HitObjects {
+    /// Parses the hitobjects, collecting the errors instead of returning the first one if [`lenient`][ParseOptions::lenient] is set.
+    /// - Lines with errors are skipped when collected.
+    /// - Comment lines are skipped if [`skips_comments`][ParseOptions::skips_comments].
+    pub(crate) fn from_str_errors(
+        s: &str,
+        version: Version,
+        options: &ParseOptions,
+    ) -> ErrorsResult<Self, ParseError> {
+        Self::from_lines(
+            item_lines(s, options.skips_comments()),
+            version,
+            options.lenient,
+        )
+    }
+
+    /// Parses the hitobjects from the lines of the section, with their index in the section.
+    pub(crate) fn from_lines<'a>(
+        lines: impl IntoIterator<Item = (usize, &'a str)>,
+        version: Version,
+        lenient: bool,
+    ) -> ErrorsResult<Self, ParseError> {
+        let mut hitobjects = Vec::new();
+        let mut errors = ErrorCollector::new(lenient);
 
-            hitobjects.push(Error::new_from_result_into(
+        for (line_index, s) in lines {
+            if let Some(hitobject) = errors.collect(Error::new_from_line_result(
                 HitObject::from_str(s, version).map(|v| v.unwrap()),
+                s,
                 line_index,
-            )?);
+            ))? {
+                hitobjects.push(hitobject);
+            }
         }
 
-        Ok(Some(HitObjects(hitobjects)))
+        Ok((Some(HitObjects(hitobjects)), errors.into_errors()))
     }
 }
 
 impl VersionedToString for HitObjects {
     fn to_string(&self, version: Version) -> Option<String> {
-        Some(
-            self.0
-                .iter()
-                .filter_map(|o| o.to_string(version))
-                .collect::<Vec<_>>()
-                .join("\n"),
-        )
+        write_versioned_to_string(self, version)
+    }
+}
+
+impl VersionedWrite for HitObjects {
+    fn write_versioned<W: Write>(&self, f: &mut W, version: Version) -> Option<fmt::Result> {
+        Some(write_new_line_separated(f, &self.0, version))
     }
 }
 
@@ -76,6 +126,7 @@ impl VersionedDefault for HitObjects {
 /// The `type` property is a `u8` integer with each bit flags containing some information, which are split into the functions and enums:
 /// [hitobject_type][Self::obj_params], [new_combo][Self::new_combo], [combo_skip_count][Self::combo_skip_count]
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub struct HitObject {
     /// The position of the hitobject.
@@ -97,7 +148,7 @@ pub struct HitObject {
 }
 
 impl HitObject {
-    fn type_to_string(&self) -> String {
+    fn type_bits(&self) -> u8 {
         let mut bit_flag: u8 = 0;
 
         bit_flag |= match self.obj_params {
@@ -114,7 +165,7 @@ impl HitObject {
         // 3 bit value from 4th ~ 6th bits
         bit_flag |= self.combo_skip_count.get() << 4;
 
-        bit_flag.to_string()
+        bit_flag
     }
 
     pub fn hitcircle_default() -> Self {
@@ -159,6 +210,45 @@ impl HitObject {
             hitsample: Default::default(),
         }
     }
+
+    /// Changes the speed of the hitobject to the `rate`, dividing its time and the end time of spinners and osu!mania holds by the rate.
+    /// - Times are rounded to whole milliseconds.
+    pub fn scale_time(&mut self, rate: f64) {
+        scale_decimal(&mut self.time, rate, true);
+
+        if let HitObjectParams::Spinner { end_time } | HitObjectParams::OsuManiaHold { end_time } =
+            &mut self.obj_params
+        {
+            scale_decimal(end_time, rate, true);
+        }
+    }
+
+    /// Shifts the time of the hitobject and the end time of spinners and osu!mania holds by `delta` milliseconds.
+    pub fn shift_time(&mut self, delta: Integer) {
+        shift_decimal(&mut self.time, delta);
+
+        if let HitObjectParams::Spinner { end_time } | HitObjectParams::OsuManiaHold { end_time } =
+            &mut self.obj_params
+        {
+            shift_decimal(end_time, delta);
+        }
+    }
+}
+
+impl HitObjects {
+    /// Changes the speed of the hitobjects to the `rate`, like [`HitObject::scale_time`].
+    pub fn scale_time(&mut self, rate: f64) {
+        for hitobject in &mut self.0 {
+            hitobject.scale_time(rate);
+        }
+    }
+
+    /// Shifts the times of the hitobjects by `delta` milliseconds, like [`HitObject::shift_time`].
+    pub fn shift_time(&mut self, delta: Integer) {
+        for hitobject in &mut self.0 {
+            hitobject.shift_time(delta);
+        }
+    }
 }
 
 const OLD_VERSION_TIME_OFFSET: rust_decimal::Decimal = dec!(24);
@@ -193,7 +283,7 @@ impl VersionedFromStr for HitObject {
                     context(ParseHitObjectError::InvalidY.into(), comma_field_type()),
                 ),
             ))
-            .map(|(x, y)| (Position { x, y })),
+            .map(|(x, y)| Position { x, y }),
             preceded(
                 context(ParseHitObjectError::MissingTime.into(), comma()),
                 context(ParseHitObjectError::InvalidTime.into(), comma_field_type()),
@@ -454,20 +544,22 @@ impl VersionedFromStr for HitObject {
 
 impl VersionedToString for HitObject {
     fn to_string(&self, version: Version) -> Option<String> {
-        let mut properties: Vec<String> = vec![
-            self.position.x.to_string(),
-            self.position.y.to_string(),
-            if (3..=4).contains(&version) {
-                match self.time.get() {
-                    Either::Left(value) => (value - OLD_VERSION_TIME_OFFSET).to_string(),
-                    Either::Right(value) => value.to_string(),
-                }
-            } else {
-                self.time.to_string()
-            },
-            self.type_to_string(),
-            self.hitsound.to_string(version).unwrap(),
-        ];
+        write_versioned_to_string(self, version)
+    }
+}
+
+impl VersionedWrite for HitObject {
+    fn write_versioned<W: Write>(&self, f: &mut W, version: Version) -> Option<fmt::Result> {
+        Some(self.write_fields(f, version))
+    }
+}
+
+impl HitObject {
+    fn write_fields<W: Write>(&self, f: &mut W, version: Version) -> fmt::Result {
+        write!(f, "{},{},", self.position.x, self.position.y)?;
+        write_time(f, &self.time, version)?;
+        write!(f, ",{},", self.type_bits())?;
+        self.hitsound.write_versioned(f, version).unwrap()?;
 
         match &self.obj_params {
             HitObjectParams::HitCircle => (),
@@ -481,21 +573,23 @@ impl VersionedToString for HitObject {
                 edge_sounds_short_hand,
                 edge_sets_shorthand,
             }) => {
-                properties.push(curve_type.to_string(version).unwrap());
-
-                let has_curve_points = version == 3 || !curve_points.is_empty();
-
-                let mut properties_2 = Vec::new();
+                f.write_char(',')?;
+                curve_type.write_versioned(f, version).unwrap()?;
 
                 if version == 3 {
-                    let mut curve_points = curve_points.clone();
-                    curve_points.insert(0, CurvePoint(self.position.clone()));
-                    properties_2.push(pipe_vec_to_string(&curve_points, version));
-                } else if has_curve_points {
-                    properties_2.push(pipe_vec_to_string(curve_points, version));
+                    f.write_char('|')?;
+                    CurvePoint(self.position.clone())
+                        .write_versioned(f, version)
+                        .unwrap()?;
+                    for curve_point in curve_points {
+                        f.write_char('|')?;
+                        curve_point.write_versioned(f, version).unwrap()?;
+                    }
+                } else if !curve_points.is_empty() {
+                    f.write_char('|')?;
+                    write_pipe_separated(f, curve_points, version)?;
                 }
-                properties_2.push(slides.to_string());
-                properties_2.push(length.to_string());
+                write!(f, ",{slides},{length}")?;
 
                 if !edge_sounds.is_empty()
                     || !*edge_sounds_short_hand
@@ -503,75 +597,71 @@ impl VersionedToString for HitObject {
                     || !*edge_sets_shorthand
                     || self.hitsample.is_some()
                 {
-                    properties_2.push(pipe_vec_to_string(edge_sounds, version));
+                    f.write_char(',')?;
+                    write_pipe_separated(f, edge_sounds, version)?;
                 }
                 if !edge_sets.is_empty() || !*edge_sets_shorthand || self.hitsample.is_some() {
-                    properties_2.push(pipe_vec_to_string(edge_sets, version));
+                    f.write_char(',')?;
+                    write_pipe_separated(f, edge_sets, version)?;
                 }
                 if let Some(hitsample) = &self.hitsample {
-                    if let Some(hitsample) = hitsample.to_string(version) {
-                        properties_2.push(hitsample);
+                    let mut writer = PrefixWriter::new(f, Some(",".into()));
+                    if let Some(res) = hitsample.write_versioned(&mut writer, version) {
+                        res?;
+                        writer.flush_prefix()?;
                     }
                 }
 
-                let slider_str = if has_curve_points {
-                    format!("{}|{}", properties.join(","), properties_2.join(","))
-                } else {
-                    format!("{},{}", properties.join(","), properties_2.join(","))
-                };
-
-                return Some(slider_str);
+                return Ok(());
             }
             HitObjectParams::Spinner { end_time } => {
-                properties.push(if (3..=4).contains(&version) {
-                    match end_time.get() {
-                        Either::Left(value) => (value - OLD_VERSION_TIME_OFFSET).to_string(),
-                        Either::Right(value) => value.to_string(),
-                    }
-                } else {
-                    end_time.to_string()
-                });
+                f.write_char(',')?;
+                write_time(f, end_time, version)?;
             }
             HitObjectParams::OsuManiaHold { end_time } => {
-                properties.push(if (3..=4).contains(&version) {
-                    match end_time.get() {
-                        Either::Left(value) => (value - OLD_VERSION_TIME_OFFSET).to_string(),
-                        Either::Right(value) => value.to_string(),
-                    }
-                } else {
-                    end_time.to_string()
-                });
+                f.write_char(',')?;
+                write_time(f, end_time, version)?;
+                f.write_char(':')?;
 
-                let hitsample = if let Some(hitsample) = &self.hitsample {
-                    hitsample.to_string(version).unwrap_or_default()
-                } else {
-                    String::new()
-                };
+                if let Some(hitsample) = &self.hitsample {
+                    if let Some(res) = hitsample.write_versioned(f, version) {
+                        res?;
+                    }
+                }
 
-                return Some(format!("{}:{hitsample}", properties.join(",")));
+                return Ok(());
             }
         }
 
         if let Some(hitsample) = &self.hitsample {
-            if let Some(hitsample) = hitsample.to_string(version) {
-                properties.push(hitsample);
+            let mut writer = PrefixWriter::new(f, Some(",".into()));
+            if let Some(res) = hitsample.write_versioned(&mut writer, version) {
+                res?;
+                writer.flush_prefix()?;
             }
         }
 
-        let s = properties.join(",");
-
         // v3 for some reason has a trailing comma for hitcircles
-        let s = if version == 3 && matches!(self.obj_params, HitObjectParams::HitCircle) {
-            format!("{s},")
-        } else {
-            s
-        };
+        if version == 3 && matches!(self.obj_params, HitObjectParams::HitCircle) {
+            f.write_char(',')?;
+        }
 
-        Some(s)
+        Ok(())
+    }
+}
+
+/// Writes the time, which is offset in versions 3 and 4.
+fn write_time<W: Write>(f: &mut W, time: &Decimal, version: Version) -> fmt::Result {
+    match time.get() {
+        Either::Left(value) if (3..=4).contains(&version) => {
+            write!(f, "{}", value - OLD_VERSION_TIME_OFFSET)
+        }
+        _ => write!(f, "{time}"),
     }
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum HitObjectParams {
     HitCircle,
@@ -581,6 +671,7 @@ pub enum HitObjectParams {
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SlideParams {
     pub curve_type: CurveType,
     pub curve_points: Vec<CurvePoint>,
@@ -0,0 +1,179 @@
+//! Nested objects of sliders, which osu! generates from the slider's path and timing.
+
+use super::{HitObject, HitObjectParams, Vec2};
+use crate::osu_file::{timingpoints::TimingLookup, Difficulty, Version};
+
+/// Longest distance of a slider that nested objects are generated for, in `osu!pixels`.
+const MAX_LENGTH: f64 = 100000.0;
+/// How much earlier the legacy last tick is from the end of the slider, in milliseconds.
+const LEGACY_LAST_TICK_OFFSET: f64 = 36.0;
+
+/// Type of a [`NestedObject`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum NestedObjectKind {
+    /// Start of the slider.
+    Head,
+    /// Tick along the slider.
+    Tick,
+    /// Reverse arrow at the end of a span that isn't the last one.
+    Repeat,
+    /// Judgement of the end of the slider in osu!standard, which is a bit before the end.
+    LegacyLastTick,
+    /// End of the slider.
+    Tail,
+}
+
+/// An object that is a part of a slider.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NestedObject {
+    pub kind: NestedObjectKind,
+    /// Index of the span the object is in.
+    pub span_index: usize,
+    /// Time of the object in milliseconds.
+    pub time: f64,
+    /// Progress along the slider's path, from `0` at the start to `1` at the end.
+    pub path_progress: f64,
+    /// Position of the object.
+    pub position: Vec2,
+}
+
+impl HitObject {
+    /// Generates the nested objects of a slider the same way osu! does, in the order of their time.
+    /// - There's a head, ticks at the `SliderTickRate`, repeats, a legacy last tick 36ms before the end, and a tail.
+    /// - In osu!standard, the slider tail is judged at the legacy last tick, so the combo of a slider is the amount of nested objects without the tail.
+    /// - `version` is the version of the file, which changes the tick distance for versions before 8.
+    /// - Returns `None` if the hitobject isn't a slider, or for the same reasons as [`end_time`][Self::end_time].
+    pub fn nested_objects(
+        &self,
+        timing_points: &impl TimingLookup,
+        difficulty: &Difficulty,
+        version: Version,
+    ) -> Option<Vec<NestedObject>> {
+        let params = match &self.obj_params {
+            HitObjectParams::Slider(params) => params,
+            _ => return None,
+        };
+
+        let path = self.slider_path();
+        let start_time = self.start_time()?;
+        let span_duration = self.slider_span_duration(timing_points, difficulty)?;
+        let velocity = self.slider_velocity(timing_points, difficulty)?;
+        let scoring_distance = self.slider_scoring_distance(timing_points, difficulty)?;
+        let span_count = params.slides.max(1) as usize;
+
+        let distance = match &path {
+            Some(path) => path.distance(),
+            None => params.length.to_f64()?,
+        };
+        let length = distance.clamp(0.0, MAX_LENGTH);
+
+        let tick_rate = difficulty.values().slider_tick_rate;
+        // old versions ignore the slider velocity for the tick distance
+        let tick_distance_multiplier = if version < 8 {
+            1.0 / timing_points.timing_state_at(start_time)?.slider_velocity
+        } else {
+            1.0
+        };
+        let tick_distance =
+            (scoring_distance / tick_rate * tick_distance_multiplier).clamp(0.0, length);
+        let min_distance_from_end = velocity * 10.0;
+
+        let position_at = |path_progress: f64| match &path {
+            Some(path) => path.position_at(path_progress),
+            None => Vec2::from_position(&self.position).unwrap_or_default(),
+        };
+        let nested_object = |kind, span_index, time, path_progress| NestedObject {
+            kind,
+            span_index,
+            time,
+            path_progress,
+            position: position_at(path_progress),
+        };
+
+        let mut objects = vec![nested_object(NestedObjectKind::Head, 0, start_time, 0.0)];
+
+        for span in 0..span_count {
+            let span_start_time = start_time + span as f64 * span_duration;
+            let reversed = span % 2 == 1;
+
+            let mut ticks = Vec::new();
+
+            if tick_distance > 0.0 {
+                let mut d = tick_distance;
+
+                while d <= length {
+                    if d >= length - min_distance_from_end {
+                        break;
+                    }
+
+                    let path_progress = d / length;
+                    let time_progress = if reversed {
+                        1.0 - path_progress
+                    } else {
+                        path_progress
+                    };
+
+                    ticks.push(nested_object(
+                        NestedObjectKind::Tick,
+                        span,
+                        span_start_time + time_progress * span_duration,
+                        path_progress,
+                    ));
+
+                    d += tick_distance;
+                }
+            }
+
+            if reversed {
+                ticks.reverse();
+            }
+
+            objects.extend(ticks);
+
+            if span < span_count - 1 {
+                objects.push(nested_object(
+                    NestedObjectKind::Repeat,
+                    span,
+                    span_start_time + span_duration,
+                    ((span + 1) % 2) as f64,
+                ));
+            }
+        }
+
+        let total_duration = span_count as f64 * span_duration;
+        let final_span_index = span_count - 1;
+        let final_span_start_time = start_time + final_span_index as f64 * span_duration;
+        let final_span_end_time = (start_time + total_duration / 2.0)
+            .max(final_span_start_time + span_duration - LEGACY_LAST_TICK_OFFSET);
+
+        let mut final_progress = if span_duration > 0.0 {
+            (final_span_end_time - final_span_start_time) / span_duration
+        } else {
+            1.0
+        };
+        if span_count % 2 == 0 {
+            final_progress = 1.0 - final_progress;
+        }
+
+        objects.push(nested_object(
+            NestedObjectKind::LegacyLastTick,
+            final_span_index,
+            final_span_end_time,
+            final_progress,
+        ));
+        objects.push(nested_object(
+            NestedObjectKind::Tail,
+            final_span_index,
+            start_time + total_duration,
+            (span_count % 2) as f64,
+        ));
+
+        // the legacy last tick can be before the last ticks
+        objects.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+        Some(objects)
+    }
+}
@@ -0,0 +1,514 @@
+//! Computing the path of a slider from its curve.
+//! - Follows how osu! approximates the curves into a list of points.
+
+use std::f64::consts::PI;
+use std::ops::{Add, Mul, Sub};
+
+use super::{CurvePoint, CurveType, HitObject, HitObjectParams, Position, SlideParams};
+
+/// Maximum distance between the control points of a flat enough bezier curve.
+const BEZIER_TOLERANCE: f64 = 0.25;
+/// Amount of points for each part of a catmull-rom curve.
+const CATMULL_DETAIL: usize = 50;
+/// Maximum distance of a perfect circle curve's points from the actual arc.
+const CIRCULAR_ARC_TOLERANCE: f64 = 0.1;
+/// Precision used when comparing floats.
+const FLOAT_EPSILON: f64 = 1e-3;
+
+/// A 2D point or vector in `osu!pixels`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Vec2 {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Vec2 {
+    pub fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+
+    /// Converts the position, returning `None` if the coordinates aren't numbers.
+    pub fn from_position(position: &Position) -> Option<Self> {
+        Some(Self::new(position.x.to_f64()?, position.y.to_f64()?))
+    }
+
+    pub fn dot(self, other: Self) -> f64 {
+        self.x * other.x + self.y * other.y
+    }
+
+    pub fn length_squared(self) -> f64 {
+        self.dot(self)
+    }
+
+    pub fn length(self) -> f64 {
+        self.length_squared().sqrt()
+    }
+
+    /// The vector with a length of `1`.
+    /// - Returns a zero vector for a zero vector.
+    pub fn normalize(self) -> Self {
+        let length = self.length();
+
+        if length == 0.0 {
+            self
+        } else {
+            self * (1.0 / length)
+        }
+    }
+
+    pub fn distance(self, other: Self) -> f64 {
+        (self - other).length()
+    }
+}
+
+impl Add for Vec2 {
+    type Output = Vec2;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Vec2::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Sub for Vec2 {
+    type Output = Vec2;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Vec2::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Mul<f64> for Vec2 {
+    type Output = Vec2;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        Vec2::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+/// Path of a slider, as a list of points with the distance along the path to each point.
+/// - Shortened or extended to the length of the slider.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SliderPath {
+    points: Vec<Vec2>,
+    cumulative_length: Vec<f64>,
+}
+
+impl SliderPath {
+    /// Computes the path from the control points, with the first one being the slider's position.
+    /// - Consecutive control points at the same position split bezier and linear curves into segments, like the red anchors in the editor.
+    /// - A perfect circle curve that doesn't have exactly 3 control points, or has them on a line, is computed as a bezier curve.
+    /// - `length` is the length of the slider, which the path is shortened or extended to. The path isn't changed if it's `None`.
+    pub fn new(curve_type: CurveType, control_points: &[Vec2], length: Option<f64>) -> Self {
+        // osu! only uses a circular arc when the whole slider has 3 control points, even with red anchors
+        let curve_type = match curve_type {
+            CurveType::PerfectCircle if control_points.len() != 3 => CurveType::Bezier,
+            curve_type => curve_type,
+        };
+
+        let mut points = Vec::new();
+
+        for segment in segments(curve_type, control_points) {
+            for point in approximate_segment(curve_type, segment) {
+                // skips the point the segments are split at, and any other repeated points
+                if points.last() != Some(&point) {
+                    points.push(point);
+                }
+            }
+        }
+
+        let mut path = Self {
+            points,
+            cumulative_length: Vec::new(),
+        };
+        path.calculate_length(control_points, length);
+
+        path
+    }
+
+    /// Points of the path.
+    pub fn points(&self) -> &[Vec2] {
+        &self.points
+    }
+
+    /// Distance along the path to each of the points.
+    pub fn cumulative_length(&self) -> &[f64] {
+        &self.cumulative_length
+    }
+
+    /// Length of the path.
+    pub fn distance(&self) -> f64 {
+        self.cumulative_length.last().copied().unwrap_or_default()
+    }
+
+    /// Position on the path at the `progress`, from `0` at the start to `1` at the end.
+    /// - The `progress` is clamped to `0` ~ `1`.
+    pub fn position_at(&self, progress: f64) -> Vec2 {
+        let distance = progress.clamp(0.0, 1.0) * self.distance();
+
+        self.position_at_distance(distance)
+    }
+
+    /// Position on the path at the `distance` from the start.
+    pub fn position_at_distance(&self, distance: f64) -> Vec2 {
+        let index = self
+            .cumulative_length
+            .partition_point(|length| *length < distance);
+
+        if self.points.is_empty() {
+            return Vec2::default();
+        }
+        if index == 0 {
+            return self.points[0];
+        }
+        if index >= self.points.len() {
+            return *self.points.last().unwrap();
+        }
+
+        let (p0, p1) = (self.points[index - 1], self.points[index]);
+        let (d0, d1) = (
+            self.cumulative_length[index - 1],
+            self.cumulative_length[index],
+        );
+
+        if almost_equals(d0, d1) {
+            return p0;
+        }
+
+        let w = (distance - d0) / (d1 - d0);
+
+        p0 + (p1 - p0) * w
+    }
+
+    fn calculate_length(&mut self, control_points: &[Vec2], expected: Option<f64>) {
+        let mut calculated = 0.0;
+        self.cumulative_length = vec![0.0];
+
+        for pair in self.points.windows(2) {
+            calculated += pair[0].distance(pair[1]);
+            self.cumulative_length.push(calculated);
+        }
+
+        let expected = match expected {
+            Some(expected) if expected != calculated => expected,
+            _ => return,
+        };
+        if self.points.is_empty() {
+            return;
+        }
+
+        // osu! doesn't extend the path if the last two control points are the same
+        if let [.., a, b] = control_points {
+            if a == b && expected > calculated {
+                return;
+            }
+        }
+
+        // the last length is always changed
+        self.cumulative_length.pop();
+        let mut end_index = self.points.len() - 1;
+
+        if calculated > expected {
+            // remove the points that are past the expected length
+            while self
+                .cumulative_length
+                .last()
+                .is_some_and(|length| *length >= expected)
+            {
+                self.cumulative_length.pop();
+                self.points.remove(end_index);
+                end_index -= 1;
+            }
+        }
+
+        if end_index == 0 {
+            self.cumulative_length.push(0.0);
+            return;
+        }
+
+        let direction = (self.points[end_index] - self.points[end_index - 1]).normalize();
+        let last_length = *self.cumulative_length.last().unwrap();
+
+        self.points[end_index] = self.points[end_index - 1] + direction * (expected - last_length);
+        self.cumulative_length.push(expected);
+    }
+}
+
+impl SlideParams {
+    /// Computes the path of the slider starting at the `position` of the hitobject.
+    /// - Returns `None` if the position, curve points or length aren't numbers.
+    pub fn path(&self, position: &Position) -> Option<SliderPath> {
+        let control_points = std::iter::once(Vec2::from_position(position))
+            .chain(
+                self.curve_points
+                    .iter()
+                    .map(|CurvePoint(position)| Vec2::from_position(position)),
+            )
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(SliderPath::new(
+            self.curve_type,
+            &control_points,
+            Some(self.length.to_f64()?),
+        ))
+    }
+}
+
+impl HitObject {
+    /// Computes the path of the hitobject if it's a slider.
+    /// - See [`SlideParams::path`].
+    pub fn slider_path(&self) -> Option<SliderPath> {
+        match &self.obj_params {
+            HitObjectParams::Slider(params) => params.path(&self.position),
+            _ => None,
+        }
+    }
+}
+
+fn almost_equals(a: f64, b: f64) -> bool {
+    (a - b).abs() <= FLOAT_EPSILON
+}
+
+/// Splits the control points into segments at consecutive points at the same position.
+/// - Catmull-rom curves aren't split, since osu! doesn't split them.
+fn segments(curve_type: CurveType, control_points: &[Vec2]) -> Vec<&[Vec2]> {
+    if curve_type == CurveType::Centripetal {
+        return vec![control_points];
+    }
+
+    let mut segments = Vec::new();
+    let mut start = 0;
+
+    for i in 1..control_points.len() {
+        if control_points[i] == control_points[i - 1] {
+            segments.push(&control_points[start..i]);
+            start = i;
+        }
+    }
+    segments.push(&control_points[start..]);
+
+    segments
+}
+
+fn approximate_segment(curve_type: CurveType, points: &[Vec2]) -> Vec<Vec2> {
+    match curve_type {
+        CurveType::Linear => points.to_vec(),
+        CurveType::Centripetal => approximate_catmull(points),
+        CurveType::PerfectCircle if points.len() == 3 => {
+            approximate_circular_arc(points).unwrap_or_else(|| approximate_bezier(points))
+        }
+        _ => approximate_bezier(points),
+    }
+}
+
+fn approximate_bezier(points: &[Vec2]) -> Vec<Vec2> {
+    let mut output = Vec::new();
+
+    if points.is_empty() {
+        return output;
+    }
+
+    let mut to_flatten = vec![points.to_vec()];
+
+    while let Some(parent) = to_flatten.pop() {
+        if bezier_is_flat_enough(&parent) {
+            bezier_approximate(&parent, &mut output);
+            continue;
+        }
+
+        let (left, right) = bezier_subdivide(&parent);
+
+        to_flatten.push(right);
+        to_flatten.push(left);
+    }
+
+    output.push(*points.last().unwrap());
+
+    output
+}
+
+fn bezier_is_flat_enough(points: &[Vec2]) -> bool {
+    points.windows(3).all(|p| {
+        (p[0] - p[1] * 2.0 + p[2]).length_squared() <= BEZIER_TOLERANCE * BEZIER_TOLERANCE * 4.0
+    })
+}
+
+/// Splits the bezier curve in half with de Casteljau's algorithm.
+fn bezier_subdivide(points: &[Vec2]) -> (Vec<Vec2>, Vec<Vec2>) {
+    let count = points.len();
+    let mut midpoints = points.to_vec();
+    let mut left = vec![Vec2::default(); count];
+    let mut right = vec![Vec2::default(); count];
+
+    for i in 0..count {
+        left[i] = midpoints[0];
+        right[count - i - 1] = midpoints[count - i - 1];
+
+        for j in 0..count - i - 1 {
+            midpoints[j] = (midpoints[j] + midpoints[j + 1]) * 0.5;
+        }
+    }
+
+    (left, right)
+}
+
+/// Adds the points of a flat enough bezier curve to the output, without the last point.
+fn bezier_approximate(points: &[Vec2], output: &mut Vec<Vec2>) {
+    let count = points.len();
+    let (mut left, right) = bezier_subdivide(points);
+
+    left.extend_from_slice(&right[1..]);
+
+    output.push(points[0]);
+
+    for i in 1..count.saturating_sub(1) {
+        let index = 2 * i;
+        output.push((left[index - 1] + left[index] * 2.0 + left[index + 1]) * 0.25);
+    }
+}
+
+fn approximate_catmull(points: &[Vec2]) -> Vec<Vec2> {
+    let mut output = Vec::with_capacity(points.len().saturating_sub(1) * CATMULL_DETAIL * 2);
+
+    for i in 0..points.len().saturating_sub(1) {
+        let v1 = if i > 0 { points[i - 1] } else { points[i] };
+        let v2 = points[i];
+        let v3 = if i + 1 < points.len() {
+            points[i + 1]
+        } else {
+            v2 * 2.0 - v1
+        };
+        let v4 = if i + 2 < points.len() {
+            points[i + 2]
+        } else {
+            v3 * 2.0 - v2
+        };
+
+        for c in 0..CATMULL_DETAIL {
+            output.push(catmull_find_point(
+                v1,
+                v2,
+                v3,
+                v4,
+                c as f64 / CATMULL_DETAIL as f64,
+            ));
+            output.push(catmull_find_point(
+                v1,
+                v2,
+                v3,
+                v4,
+                (c + 1) as f64 / CATMULL_DETAIL as f64,
+            ));
+        }
+    }
+
+    output
+}
+
+fn catmull_find_point(v1: Vec2, v2: Vec2, v3: Vec2, v4: Vec2, t: f64) -> Vec2 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+
+    let find = |v1: f64, v2: f64, v3: f64, v4: f64| {
+        0.5 * (2.0 * v2
+            + (-v1 + v3) * t
+            + (2.0 * v1 - 5.0 * v2 + 4.0 * v3 - v4) * t2
+            + (-v1 + 3.0 * v2 - 3.0 * v3 + v4) * t3)
+    };
+
+    Vec2::new(find(v1.x, v2.x, v3.x, v4.x), find(v1.y, v2.y, v3.y, v4.y))
+}
+
+/// Circle going through the 3 control points of a perfect circle curve.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct CircularArc {
+    pub centre: Vec2,
+    pub radius: f64,
+    /// Angle of the first point from the centre.
+    pub theta_start: f64,
+    /// Angle from the first point to the last, which is negative if the arc goes anticlockwise.
+    pub theta_range: f64,
+}
+
+impl CircularArc {
+    /// The arc going through the 3 points.
+    /// - Returns `None` if the points are on a line, or if any of them are at the same position.
+    pub fn new(points: &[Vec2]) -> Option<Self> {
+        let (a, b, c) = (points[0], points[1], points[2]);
+
+        let a_sq = (b - c).length_squared();
+        let b_sq = (a - c).length_squared();
+        let c_sq = (a - b).length_squared();
+
+        if almost_equals(a_sq, 0.0) || almost_equals(b_sq, 0.0) || almost_equals(c_sq, 0.0) {
+            return None;
+        }
+
+        let s = a_sq * (b_sq + c_sq - a_sq);
+        let t = b_sq * (a_sq + c_sq - b_sq);
+        let u = c_sq * (a_sq + b_sq - c_sq);
+        let sum = s + t + u;
+
+        if almost_equals(sum, 0.0) {
+            return None;
+        }
+
+        let centre = (a * s + b * t + c * u) * (1.0 / sum);
+        let d_a = a - centre;
+        let d_c = c - centre;
+        let radius = d_a.length();
+
+        let theta_start = d_a.y.atan2(d_a.x);
+        let mut theta_end = d_c.y.atan2(d_c.x);
+
+        while theta_end < theta_start {
+            theta_end += 2.0 * PI;
+        }
+
+        let mut theta_range = theta_end - theta_start;
+
+        // the arc goes the other way if b is on the other side of the line from a to c
+        let ortho_a_to_c = Vec2::new((c - a).y, -(c - a).x);
+
+        if ortho_a_to_c.dot(b - a) < 0.0 {
+            theta_range = -(2.0 * PI - theta_range);
+        }
+
+        Some(Self {
+            centre,
+            radius,
+            theta_start,
+            theta_range,
+        })
+    }
+
+    /// Point on the circle at the `theta` angle.
+    pub fn point_at(&self, theta: f64) -> Vec2 {
+        self.centre + Vec2::new(theta.cos(), theta.sin()) * self.radius
+    }
+}
+
+/// Approximates the arc going through the 3 points.
+/// - Returns `None` if the points are on a line, or if any of them are at the same position.
+fn approximate_circular_arc(points: &[Vec2]) -> Option<Vec<Vec2>> {
+    let arc = CircularArc::new(points)?;
+    let radius = arc.radius;
+    let theta_range = arc.theta_range.abs();
+
+    let amount_points = if 2.0 * radius <= CIRCULAR_ARC_TOLERANCE {
+        2
+    } else {
+        let points = theta_range / (2.0 * (1.0 - CIRCULAR_ARC_TOLERANCE / radius).acos());
+        (points.ceil() as usize).max(2)
+    };
+
+    Some(
+        (0..amount_points)
+            .map(|i| {
+                let fract = i as f64 / (amount_points - 1) as f64;
+
+                arc.point_at(arc.theta_start + fract * arc.theta_range)
+            })
+            .collect(),
+    )
+}
@@ -0,0 +1,91 @@
+//! Beat snapping of hitobjects, for finding unsnapped hitobjects.
+
+use super::{HitObjectParams, HitObjects};
+use crate::osu_file::{timingpoints::BeatSnap, Difficulty, TimingPoints};
+
+/// Part of a hitobject that is snapped.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum SnapPoint {
+    /// Start of the hitobject.
+    Start,
+    /// Reverse arrow of a slider, with the index of the span it ends.
+    Repeat(usize),
+    /// End of a slider, spinner or osu!mania hold.
+    End,
+}
+
+/// Beat snap of a part of a hitobject.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HitObjectSnap {
+    /// Index of the hitobject in the [`HitObjects`].
+    pub index: usize,
+    pub point: SnapPoint,
+    /// Time of the part in milliseconds.
+    pub time: f64,
+    pub snap: BeatSnap,
+}
+
+impl HitObjects {
+    /// Closest beat divisor and error of the start of each hitobject, and the repeats and ends of the ones with a duration.
+    /// - Snaps use the timing state at the time of the part being snapped, like the osu! editor does.
+    /// - Hitobjects with times that aren't numbers are skipped, and slider repeats and ends are skipped if they can't be computed.
+    /// - Returns an empty list if there's no uninherited timing point with a valid beat length.
+    pub fn beat_snaps(
+        &self,
+        timing_points: &TimingPoints,
+        difficulty: &Difficulty,
+    ) -> Vec<HitObjectSnap> {
+        let index = match timing_points.index() {
+            Some(index) => index,
+            None => return Vec::new(),
+        };
+        let mut snaps = Vec::new();
+
+        for (i, hitobject) in self.0.iter().enumerate() {
+            let mut push = |point, time: f64| {
+                snaps.push(HitObjectSnap {
+                    index: i,
+                    point,
+                    time,
+                    snap: index.beat_snap(time),
+                })
+            };
+
+            if let Some(time) = hitobject.start_time() {
+                push(SnapPoint::Start, time);
+            } else {
+                continue;
+            }
+
+            match &hitobject.obj_params {
+                HitObjectParams::HitCircle => (),
+                HitObjectParams::Slider(_) => {
+                    let spans = hitobject
+                        .slider_spans(&index, difficulty)
+                        .unwrap_or_default();
+                    let last = spans.len().saturating_sub(1);
+
+                    for (span_index, span) in spans.iter().enumerate() {
+                        let point = if span_index == last {
+                            SnapPoint::End
+                        } else {
+                            SnapPoint::Repeat(span_index)
+                        };
+                        push(point, span.end_time);
+                    }
+                }
+                HitObjectParams::Spinner { end_time }
+                | HitObjectParams::OsuManiaHold { end_time } => {
+                    if let Some(time) = end_time.to_f64() {
+                        push(SnapPoint::End, time);
+                    }
+                }
+            }
+        }
+
+        snaps
+    }
+}
@@ -0,0 +1,252 @@
+//! Stacking of osu!standard hitobjects that are close to each other in time and position.
+//! - Follows how osu! stacks hitobjects, with the older algorithm for versions before 6.
+
+use super::{HitObjectParams, HitObjects, Vec2};
+use crate::osu_file::{
+    difficulty::{difficulty_value, EMPTY_DIFFICULTY},
+    timingpoints::EMPTY_TIMING_POINTS,
+    Difficulty, OsuFile, TimingPoints, Version,
+};
+
+/// Hitobjects closer than this in `osu!pixels` are stacked.
+const STACK_DISTANCE: f64 = 3.0;
+/// How far each stack moves a hitobject in `osu!pixels`, multiplied by the scale of the hitobjects.
+const STACK_OFFSET: f64 = -6.4;
+/// Stack leniency used when the general section doesn't have one.
+const DEFAULT_STACK_LENIENCY: f64 = 0.7;
+
+/// Stack of a hitobject, with the positions moved by it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StackedObject {
+    /// How many hitobjects are stacked under the hitobject.
+    /// - Negative for hitobjects stacked on the end of a slider, which are moved the other way.
+    pub stack_height: i32,
+    /// Position of the hitobject with the stack offset.
+    pub position: Vec2,
+    /// Position of the end of the hitobject with the stack offset.
+    /// - Same as the position for hitobjects that aren't sliders.
+    pub end_position: Vec2,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Circle,
+    Slider,
+    Spinner,
+}
+
+/// Hitobject with what's needed for stacking.
+struct StackObject {
+    kind: Kind,
+    start_time: f64,
+    end_time: f64,
+    position: Vec2,
+    /// Position at the end of the slider, after the repeats.
+    end_position: Vec2,
+    /// Position at the end of the slider's path, ignoring the repeats.
+    path_end_position: Vec2,
+    stack_height: i32,
+}
+
+impl HitObjects {
+    /// Computes the stack of each hitobject the same way osu!standard does, in the order of the hitobjects.
+    /// - `stack_leniency` is the `StackLeniency` of the general section, and `version` is the version of the file, which uses the older algorithm before 6.
+    /// - Hitobjects are stacked when they're within the approach time multiplied by the `stack_leniency`, so the approach rate of the `difficulty` is used.
+    ///   The approach rate falls back to the overall difficulty if it isn't set.
+    /// - The hitobjects should be in the order of their time, like they are in the file.
+    /// - Times and positions that aren't numbers are treated as `0`.
+    pub fn stacking(
+        &self,
+        timing_points: &TimingPoints,
+        difficulty: &Difficulty,
+        stack_leniency: f64,
+        version: Version,
+    ) -> Vec<StackedObject> {
+        let index = timing_points.index();
+        let mut objects = self
+            .0
+            .iter()
+            .map(|hitobject| {
+                let start_time = hitobject.start_time().unwrap_or_default();
+                let position = Vec2::from_position(&hitobject.position).unwrap_or_default();
+                let end_time = match &index {
+                    Some(index) => hitobject.end_time(index, difficulty),
+                    None => hitobject.end_time(timing_points, difficulty),
+                }
+                .unwrap_or(start_time);
+
+                let (kind, end_position, path_end_position) = match &hitobject.obj_params {
+                    HitObjectParams::HitCircle => (Kind::Circle, position, position),
+                    HitObjectParams::Slider(params) => {
+                        let path_end_position = hitobject
+                            .slider_path()
+                            .map_or(position, |path| path.position_at(1.0));
+                        // an even amount of spans ends at the start of the path
+                        let end_position = if params.slides.max(1) % 2 == 0 {
+                            position
+                        } else {
+                            path_end_position
+                        };
+
+                        (Kind::Slider, end_position, path_end_position)
+                    }
+                    HitObjectParams::Spinner { .. } | HitObjectParams::OsuManiaHold { .. } => {
+                        (Kind::Spinner, position, position)
+                    }
+                };
+
+                StackObject {
+                    kind,
+                    start_time,
+                    end_time,
+                    position,
+                    end_position,
+                    path_end_position,
+                    stack_height: 0,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let osu_difficulty = difficulty.values().osu();
+        let stack_threshold = osu_difficulty.preempt * stack_leniency;
+
+        if version >= 6 {
+            apply_stacking(&mut objects, stack_threshold);
+        } else {
+            apply_stacking_old(&mut objects, stack_threshold);
+        }
+
+        objects
+            .iter()
+            .map(|object| {
+                let offset = object.stack_height as f64 * osu_difficulty.scale * STACK_OFFSET;
+                let offset = Vec2::new(offset, offset);
+
+                StackedObject {
+                    stack_height: object.stack_height,
+                    position: object.position + offset,
+                    end_position: object.end_position + offset,
+                }
+            })
+            .collect()
+    }
+}
+
+impl OsuFile {
+    /// Computes the stack of each hitobject with the settings of the file.
+    /// - Same as [`HitObjects::stacking`], using the default settings for sections that aren't in the file.
+    pub fn stacking(&self) -> Vec<StackedObject> {
+        let hitobjects = match &self.hitobjects {
+            Some(hitobjects) => hitobjects,
+            None => return Vec::new(),
+        };
+        let stack_leniency = self
+            .general
+            .as_ref()
+            .map_or(DEFAULT_STACK_LENIENCY, |general| {
+                difficulty_value(&general.stack_leniency, DEFAULT_STACK_LENIENCY)
+            });
+
+        hitobjects.stacking(
+            self.timing_points.as_ref().unwrap_or(&EMPTY_TIMING_POINTS),
+            self.difficulty.as_ref().unwrap_or(&EMPTY_DIFFICULTY),
+            stack_leniency,
+            self.version,
+        )
+    }
+}
+
+fn is_stacked(a: Vec2, b: Vec2) -> bool {
+    a.distance(b) < STACK_DISTANCE
+}
+
+/// Stacking used from version 6, going backwards from the last hitobject.
+fn apply_stacking(objects: &mut [StackObject], stack_threshold: f64) {
+    for i in (1..objects.len()).rev() {
+        if objects[i].stack_height != 0 || objects[i].kind == Kind::Spinner {
+            continue;
+        }
+
+        // the hitobject at the top of the stack so far
+        let mut current = i;
+
+        match objects[i].kind {
+            Kind::Circle => {
+                for n in (0..i).rev() {
+                    if objects[n].kind == Kind::Spinner {
+                        continue;
+                    }
+                    if objects[current].start_time - objects[n].end_time > stack_threshold {
+                        break;
+                    }
+
+                    // hitobjects stacked on the end of a slider are moved the other way
+                    if objects[n].kind == Kind::Slider
+                        && is_stacked(objects[n].end_position, objects[current].position)
+                    {
+                        let offset = objects[current].stack_height - objects[n].stack_height + 1;
+
+                        for j in n + 1..=i {
+                            if is_stacked(objects[n].end_position, objects[j].position) {
+                                objects[j].stack_height -= offset;
+                            }
+                        }
+
+                        break;
+                    }
+
+                    if is_stacked(objects[n].position, objects[current].position) {
+                        objects[n].stack_height = objects[current].stack_height + 1;
+                        current = n;
+                    }
+                }
+            }
+            Kind::Slider => {
+                for n in (0..i).rev() {
+                    if objects[n].kind == Kind::Spinner {
+                        continue;
+                    }
+                    if objects[current].start_time - objects[n].start_time > stack_threshold {
+                        break;
+                    }
+
+                    if is_stacked(objects[n].end_position, objects[current].position) {
+                        objects[n].stack_height = objects[current].stack_height + 1;
+                        current = n;
+                    }
+                }
+            }
+            Kind::Spinner => (),
+        }
+    }
+}
+
+/// Stacking used before version 6, going forwards from the first hitobject.
+fn apply_stacking_old(objects: &mut [StackObject], stack_threshold: f64) {
+    for i in 0..objects.len() {
+        if objects[i].stack_height != 0 && objects[i].kind != Kind::Slider {
+            continue;
+        }
+
+        let mut start_time = objects[i].end_time;
+        let mut slider_stack = 0;
+
+        for j in i + 1..objects.len() {
+            if objects[j].start_time - stack_threshold > start_time {
+                break;
+            }
+
+            // osu! uses the start time of the later hitobjects here, since their end times weren't computed yet
+            if is_stacked(objects[j].position, objects[i].position) {
+                objects[i].stack_height += 1;
+                start_time = objects[j].start_time;
+            } else if is_stacked(objects[j].position, objects[i].path_end_position) {
+                // hitobjects on the end of a slider are moved the other way
+                slider_stack += 1;
+                objects[j].stack_height -= slider_stack;
+                start_time = objects[j].start_time;
+            }
+        }
+    }
+}
@@ -0,0 +1,125 @@
+//! Timing of hitobjects, computed with the timing points and the difficulty settings.
+
+use super::{HitObject, HitObjectParams};
+use crate::osu_file::{timingpoints::TimingLookup, Difficulty};
+
+/// Distance a slider travels in a beat at a slider velocity multiplier of `1`, in `osu!pixels`.
+const BASE_SCORING_DISTANCE: f64 = 100.0;
+
+/// A span of a slider, going from one end of the slider's path to the other.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SliderSpan {
+    /// Time in milliseconds when the span starts.
+    pub start_time: f64,
+    /// Time in milliseconds when the span ends.
+    pub end_time: f64,
+    /// If the span goes from the end of the path back to the start.
+    pub reversed: bool,
+}
+
+impl HitObject {
+    /// Time of the hitobject in milliseconds.
+    /// - Returns `None` if the time isn't a number.
+    pub fn start_time(&self) -> Option<f64> {
+        self.time.to_f64()
+    }
+
+    /// Time in milliseconds when the hitobject ends.
+    /// - Same as the start time for hit circles.
+    /// - Spinners and osu!mania holds use their `end_time`, and sliders are computed from the timing points and the slider multiplier.
+    /// - Returns `None` if the times aren't numbers, or if a slider has no uninherited timing point to get the beat length from.
+    pub fn end_time(
+        &self,
+        timing_points: &impl TimingLookup,
+        difficulty: &Difficulty,
+    ) -> Option<f64> {
+        match &self.obj_params {
+            HitObjectParams::HitCircle => self.start_time(),
+            HitObjectParams::Slider(_) => self
+                .slider_spans(timing_points, difficulty)?
+                .last()
+                .map(|span| span.end_time),
+            HitObjectParams::Spinner { end_time } | HitObjectParams::OsuManiaHold { end_time } => {
+                end_time.to_f64()
+            }
+        }
+    }
+
+    /// Velocity of the slider in `osu!pixels` per millisecond.
+    /// - Returns `None` if the hitobject isn't a slider, or for the same reasons as [`end_time`][Self::end_time].
+    pub fn slider_velocity(
+        &self,
+        timing_points: &impl TimingLookup,
+        difficulty: &Difficulty,
+    ) -> Option<f64> {
+        let time = self.start_time()?;
+        let state = timing_points.timing_state_at(time)?;
+
+        Some(self.slider_scoring_distance(timing_points, difficulty)? / state.beat_length)
+    }
+
+    /// Distance the slider travels in a beat, in `osu!pixels`.
+    pub(super) fn slider_scoring_distance(
+        &self,
+        timing_points: &impl TimingLookup,
+        difficulty: &Difficulty,
+    ) -> Option<f64> {
+        if !matches!(self.obj_params, HitObjectParams::Slider(_)) {
+            return None;
+        }
+
+        let time = self.start_time()?;
+        let state = timing_points.timing_state_at(time)?;
+
+        Some(BASE_SCORING_DISTANCE * difficulty.values().slider_multiplier * state.slider_velocity)
+    }
+
+    /// Duration of a single span of the slider in milliseconds.
+    /// - The distance of the span is the length of the [slider's path][HitObject::slider_path], or the `length` field if the path can't be computed.
+    /// - Returns `None` for the same reasons as [`slider_velocity`][Self::slider_velocity].
+    pub fn slider_span_duration(
+        &self,
+        timing_points: &impl TimingLookup,
+        difficulty: &Difficulty,
+    ) -> Option<f64> {
+        let params = match &self.obj_params {
+            HitObjectParams::Slider(params) => params,
+            _ => return None,
+        };
+
+        let distance = match self.slider_path() {
+            Some(path) => path.distance(),
+            None => params.length.to_f64()?,
+        };
+
+        Some(distance / self.slider_velocity(timing_points, difficulty)?)
+    }
+
+    /// Start and end times of each span of the slider.
+    /// - Sliders with `slides` being less than `1` have a single span.
+    /// - Returns `None` for the same reasons as [`slider_velocity`][Self::slider_velocity].
+    pub fn slider_spans(
+        &self,
+        timing_points: &impl TimingLookup,
+        difficulty: &Difficulty,
+    ) -> Option<Vec<SliderSpan>> {
+        let params = match &self.obj_params {
+            HitObjectParams::Slider(params) => params,
+            _ => return None,
+        };
+
+        let start_time = self.start_time()?;
+        let span_duration = self.slider_span_duration(timing_points, difficulty)?;
+
+        Some(
+            (0..params.slides.max(1))
+                .map(|i| SliderSpan {
+                    start_time: start_time + span_duration * i as f64,
+                    end_time: start_time + span_duration * (i + 1) as f64,
+                    reversed: i % 2 == 1,
+                })
+                .collect(),
+        )
+    }
+}
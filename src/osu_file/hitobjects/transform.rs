@@ -0,0 +1,293 @@
+//! Geometric transforms of hitobjects, like mirroring or rotating a selection of them in the editor.
+
+use std::f64::consts::FRAC_PI_2;
+
+use rust_decimal::prelude::FromPrimitive;
+
+use super::path::CircularArc;
+use super::{CurvePoint, CurveType, HitObject, HitObjectParams, HitObjects, Position, Vec2};
+use crate::osu_file::Integer;
+
+/// Size of the playfield in `osu!pixels`.
+const PLAYFIELD_WIDTH: f64 = 512.0;
+const PLAYFIELD_HEIGHT: f64 = 384.0;
+/// Precision used when checking if a transform keeps the shapes of curves.
+const TRANSFORM_EPSILON: f64 = 1e-9;
+
+/// An affine transform of positions in `osu!pixels`.
+/// - A point is multiplied by the `matrix`, then moved by the `translation`.
+/// - The y axis points down in the playfield, so positive angles rotate clockwise on the screen.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transform {
+    /// Rows of the 2x2 matrix.
+    pub matrix: [[f64; 2]; 2],
+    pub translation: Vec2,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Transform {
+    /// Transform that doesn't change the positions.
+    pub const IDENTITY: Self = Self {
+        matrix: [[1.0, 0.0], [0.0, 1.0]],
+        translation: Vec2 { x: 0.0, y: 0.0 },
+    };
+
+    /// Moves the positions by the `offset`.
+    pub fn translate(offset: Vec2) -> Self {
+        Self {
+            translation: offset,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Scales the positions from the top left of the playfield.
+    /// - Use [`around`][Self::around] to scale from another point.
+    pub fn scale(x: f64, y: f64) -> Self {
+        Self {
+            matrix: [[x, 0.0], [0.0, y]],
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Rotates the positions by the `angle` in radians around the top left of the playfield.
+    /// - Use [`around`][Self::around] to rotate around another point.
+    pub fn rotate(angle: f64) -> Self {
+        let (sin, cos) = angle.sin_cos();
+
+        Self {
+            matrix: [[cos, -sin], [sin, cos]],
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Mirrors the positions from left to right in the playfield, like flipping horizontally in the editor.
+    pub fn mirror_horizontally() -> Self {
+        Self {
+            matrix: [[-1.0, 0.0], [0.0, 1.0]],
+            translation: Vec2::new(PLAYFIELD_WIDTH, 0.0),
+        }
+    }
+
+    /// Mirrors the positions upside down in the playfield, like flipping vertically in the editor.
+    pub fn mirror_vertically() -> Self {
+        Self {
+            matrix: [[1.0, 0.0], [0.0, -1.0]],
+            translation: Vec2::new(0.0, PLAYFIELD_HEIGHT),
+        }
+    }
+
+    /// The transform with the `origin` as the centre it's applied from, instead of the top left of the playfield.
+    /// - `Transform::rotate(angle).around(Vec2::new(256.0, 192.0))` rotates around the centre of the playfield.
+    pub fn around(self, origin: Vec2) -> Self {
+        Self::translate(origin * -1.0)
+            .then(self)
+            .then(Self::translate(origin))
+    }
+
+    /// The transform that applies this transform, then the `next` one.
+    pub fn then(self, next: Self) -> Self {
+        let [[a, b], [c, d]] = next.matrix;
+        let [[e, f], [g, h]] = self.matrix;
+
+        Self {
+            matrix: [
+                [a * e + b * g, a * f + b * h],
+                [c * e + d * g, c * f + d * h],
+            ],
+            translation: next.apply(self.translation),
+        }
+    }
+
+    /// Transforms the `point`.
+    pub fn apply(&self, point: Vec2) -> Vec2 {
+        let [[a, b], [c, d]] = self.matrix;
+
+        Vec2::new(
+            a * point.x + b * point.y + self.translation.x,
+            c * point.x + d * point.y + self.translation.y,
+        )
+    }
+
+    /// How much the transform scales lengths by, if it keeps the shapes of curves.
+    /// - Returns `None` if it stretches or skews them, where circles become ellipses.
+    fn uniform_scale(&self) -> Option<f64> {
+        let [[a, b], [c, d]] = self.matrix;
+        let (x_length, y_length) = (a * a + c * c, b * b + d * d);
+
+        let orthogonal = (a * b + c * d).abs() <= TRANSFORM_EPSILON;
+        let same_length = (x_length - y_length).abs() <= TRANSFORM_EPSILON;
+
+        (orthogonal && same_length).then(|| x_length.sqrt())
+    }
+}
+
+/// Options for [`HitObject::transform`].
+/// - Construct with `..Default::default()` for the options that aren't set, which are all off.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TransformOptions {
+    /// Clamps the positions and curve points to the playfield, from `0,0` to `512,384`.
+    pub clamp: bool,
+    /// Keeps perfect circle curves the shape they are transformed to.
+    /// - A curve that would no longer be a circle, from being stretched or skewed, or that would have its points on a line after being rounded, is converted to a bezier curve of the transformed arc.
+    pub keep_perfect_circles: bool,
+}
+
+impl HitObject {
+    /// Applies the `transform` to the position of the hitobject and the curve points of a slider.
+    /// - Positions are rounded to whole `osu!pixels` like osu! saves them, and positions that aren't numbers are left as they are.
+    /// - The length of a slider is scaled with the transform, so the slider path stays the same shape.
+    /// - Every hitobject is transformed, which only makes sense for osu!standard and osu!catch.
+    pub fn transform(&mut self, transform: &Transform, options: &TransformOptions) {
+        let path = self.slider_path();
+        let start = Vec2::from_position(&self.position);
+
+        transform_position(&mut self.position, transform, options);
+
+        let HitObjectParams::Slider(params) = &mut self.obj_params else {
+            return;
+        };
+
+        if options.keep_perfect_circles && params.curve_type == CurveType::PerfectCircle {
+            let control_points = std::iter::once(start)
+                .chain(
+                    params
+                        .curve_points
+                        .iter()
+                        .map(|CurvePoint(position)| Vec2::from_position(position)),
+                )
+                .collect::<Option<Vec<_>>>();
+
+            if let Some(curve_points) = control_points.and_then(|control_points| {
+                perfect_circle_to_bezier(&control_points, transform, options)
+            }) {
+                params.curve_type = CurveType::Bezier;
+                params.curve_points = curve_points;
+            } else {
+                transform_curve_points(&mut params.curve_points, transform, options);
+            }
+        } else {
+            transform_curve_points(&mut params.curve_points, transform, options);
+        }
+
+        let length = match (transform.uniform_scale(), path) {
+            (Some(scale), _) if (scale - 1.0).abs() <= TRANSFORM_EPSILON => return,
+            (Some(scale), _) => params.length.to_f64().map(|length| length * scale),
+            // the length of a stretched path is measured from its transformed points
+            (None, Some(path)) => Some(
+                path.points()
+                    .windows(2)
+                    .map(|pair| transform.apply(pair[0]).distance(transform.apply(pair[1])))
+                    .sum(),
+            ),
+            (None, None) => None,
+        };
+
+        if let Some(length) = length.and_then(rust_decimal::Decimal::from_f64) {
+            params.length = length.into();
+        }
+    }
+}
+
+impl HitObjects {
+    /// Applies the `transform` to every hitobject, like [`HitObject::transform`].
+    /// - Transform a selection of hitobjects by calling [`HitObject::transform`] on each of them instead.
+    pub fn transform(&mut self, transform: &Transform, options: &TransformOptions) {
+        for hitobject in &mut self.0 {
+            hitobject.transform(transform, options);
+        }
+    }
+}
+
+/// Transforms the `point`, clamping it to the playfield if set and rounding it to whole `osu!pixels`.
+fn transform_point(point: Vec2, transform: &Transform, options: &TransformOptions) -> Vec2 {
+    let mut point = transform.apply(point);
+
+    if options.clamp {
+        point.x = point.x.clamp(0.0, PLAYFIELD_WIDTH);
+        point.y = point.y.clamp(0.0, PLAYFIELD_HEIGHT);
+    }
+
+    Vec2::new(point.x.round(), point.y.round())
+}
+
+fn to_position(point: Vec2) -> Position {
+    Position {
+        x: (point.x as Integer).into(),
+        y: (point.y as Integer).into(),
+    }
+}
+
+fn transform_position(position: &mut Position, transform: &Transform, options: &TransformOptions) {
+    if let Some(point) = Vec2::from_position(position) {
+        *position = to_position(transform_point(point, transform, options));
+    }
+}
+
+fn transform_curve_points(
+    curve_points: &mut [CurvePoint],
+    transform: &Transform,
+    options: &TransformOptions,
+) {
+    for CurvePoint(position) in curve_points {
+        transform_position(position, transform, options);
+    }
+}
+
+/// Curve points of a bezier curve that follows the transformed arc of a perfect circle curve.
+/// - Returns `None` if the transformed curve is still a valid perfect circle, or if the curve isn't drawn as one.
+fn perfect_circle_to_bezier(
+    control_points: &[Vec2],
+    transform: &Transform,
+    options: &TransformOptions,
+) -> Option<Vec<CurvePoint>> {
+    // osu! draws the curve as a bezier curve already if it's not a valid arc
+    if control_points.len() != 3 {
+        return None;
+    }
+    let arc = CircularArc::new(control_points)?;
+
+    let transformed = control_points
+        .iter()
+        .map(|point| transform_point(*point, transform, options))
+        .collect::<Vec<_>>();
+    if transform.uniform_scale().is_some() && CircularArc::new(&transformed).is_some() {
+        return None;
+    }
+
+    // cubic bezier segments of up to a quarter of the circle each
+    let segment_count = (arc.theta_range.abs() / FRAC_PI_2).ceil().max(1.0) as usize;
+    let segment_range = arc.theta_range / segment_count as f64;
+    let handle_length = 4.0 / 3.0 * (segment_range / 4.0).tan() * arc.radius;
+    let tangent = |theta: f64| Vec2::new(-theta.sin(), theta.cos());
+
+    let mut curve_points = Vec::with_capacity(segment_count * 4);
+    for i in 0..segment_count {
+        let theta_start = arc.theta_start + segment_range * i as f64;
+        let theta_end = theta_start + segment_range;
+        let (start, end) = (arc.point_at(theta_start), arc.point_at(theta_end));
+
+        // the end of the last segment is repeated to start the next one
+        if i > 0 {
+            curve_points.push(*curve_points.last().unwrap());
+        }
+        curve_points.extend([
+            start + tangent(theta_start) * handle_length,
+            end - tangent(theta_end) * handle_length,
+            end,
+        ]);
+    }
+
+    Some(
+        curve_points
+            .into_iter()
+            .map(|point| CurvePoint(to_position(transform_point(point, transform, options))))
+            .collect(),
+    )
+}
@@ -1,5 +1,6 @@
 //! Module defining misc types used for different hitobjects, such as [CurveType] used for [`Slider`][super::SlideParams] curve types.
 
+use std::fmt::{self, Write};
 use std::num::{NonZeroUsize, ParseIntError};
 
 use nom::{
@@ -12,7 +13,7 @@ use nom::{
 };
 
 use crate::{
-    helper::nth_bit_state_i64,
+    helper::{nth_bit_state_i64, write_versioned_to_string, VersionedWrite},
     osu_file::*,
     parsers::{consume_rest_type, consume_rest_versioned_type, nothing},
 };
@@ -20,6 +21,8 @@ use crate::{
 use super::error::*;
 
 #[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "u8", into = "u8"))]
 pub struct ComboSkipCount(u8);
 
 impl ComboSkipCount {
@@ -57,7 +60,25 @@ impl VersionedFrom<ComboSkipCount> for u8 {
     }
 }
 
+#[cfg(feature = "serde")]
+impl TryFrom<u8> for ComboSkipCount {
+    type Error = ComboSkipCountTooHigh;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        <ComboSkipCount as VersionedTryFrom<u8>>::try_from(value, LATEST_VERSION)
+            .map(|count| count.unwrap())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<ComboSkipCount> for u8 {
+    fn from(count: ComboSkipCount) -> Self {
+        count.0
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Sample sets used for the `edgeSounds`.
 pub struct EdgeSet {
     /// Sample set of the normal sound.
@@ -95,15 +116,21 @@ impl VersionedFromStr for EdgeSet {
 
 impl VersionedToString for EdgeSet {
     fn to_string(&self, version: Version) -> Option<String> {
-        Some(format!(
-            "{}:{}",
-            self.normal_set.to_string(version).unwrap(),
-            self.addition_set.to_string(version).unwrap()
-        ))
+        write_versioned_to_string(self, version)
+    }
+}
+
+impl VersionedWrite for EdgeSet {
+    fn write_versioned<W: Write>(&self, f: &mut W, version: Version) -> Option<fmt::Result> {
+        let normal_set = <usize as VersionedFrom<SampleSet>>::from(self.normal_set, version)?;
+        let addition_set = <usize as VersionedFrom<SampleSet>>::from(self.addition_set, version)?;
+
+        Some(write!(f, "{normal_set}:{addition_set}"))
     }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Anchor point used to construct the [`slider`][super::SlideParams].
 pub struct CurvePoint(pub Position);
 
@@ -132,13 +159,20 @@ impl VersionedFromStr for CurvePoint {
 }
 
 impl VersionedToString for CurvePoint {
-    fn to_string(&self, _: Version) -> Option<String> {
-        Some(format!("{}:{}", self.0.x, self.0.y))
+    fn to_string(&self, version: Version) -> Option<String> {
+        write_versioned_to_string(self, version)
+    }
+}
+
+impl VersionedWrite for CurvePoint {
+    fn write_versioned<W: Write>(&self, f: &mut W, _: Version) -> Option<fmt::Result> {
+        Some(write!(f, "{}:{}", self.0.x, self.0.y))
     }
 }
 
 /// Used for `normal_set` and `addition_set` for the `[hitobject]`[super::HitObject].
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum SampleSet {
     /// No custom sample set.
@@ -173,11 +207,15 @@ impl VersionedDefault for SampleSet {
 
 impl VersionedToString for SampleSet {
     fn to_string(&self, version: Version) -> Option<String> {
-        Some(
-            <usize as VersionedFrom<SampleSet>>::from(*self, version)
-                .unwrap()
-                .to_string(),
-        )
+        write_versioned_to_string(self, version)
+    }
+}
+
+impl VersionedWrite for SampleSet {
+    fn write_versioned<W: Write>(&self, f: &mut W, version: Version) -> Option<fmt::Result> {
+        let set = <usize as VersionedFrom<SampleSet>>::from(*self, version)?;
+
+        Some(write!(f, "{set}"))
     }
 }
 
@@ -198,6 +236,8 @@ impl VersionedFromStr for SampleSet {
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "Option<u8>", into = "Option<u8>"))]
 /// Volume of the sample from `1` to `100`. If [volume][Self::volume] returns `None`, the timing point's volume will be used instead.
 pub struct Volume(Option<u8>);
 
@@ -218,6 +258,22 @@ impl VersionedFrom<Volume> for Integer {
     }
 }
 
+#[cfg(feature = "serde")]
+impl TryFrom<Option<u8>> for Volume {
+    type Error = VolumeSetError;
+
+    fn try_from(volume: Option<u8>) -> Result<Self, Self::Error> {
+        Volume::new(volume)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Volume> for Option<u8> {
+    fn from(volume: Volume) -> Self {
+        volume.0
+    }
+}
+
 impl Volume {
     /// Creates a new instance of `Volume`.
     /// - Requires the `volume` to be in range of `1` ~ `100`.
@@ -278,6 +334,7 @@ impl VersionedFromStr for Volume {
 }
 
 #[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Flags that determine which sounds will play when the object is hit.
 /// # Possible sounds
 /// [`normal`][Self::normal] [`whistle`][Self::whistle] [`finish`][Self::finish] [`clap`][Self::clap]
@@ -291,7 +348,13 @@ pub struct HitSound {
 }
 
 impl VersionedToString for HitSound {
-    fn to_string(&self, _: Version) -> Option<String> {
+    fn to_string(&self, version: Version) -> Option<String> {
+        write_versioned_to_string(self, version)
+    }
+}
+
+impl VersionedWrite for HitSound {
+    fn write_versioned<W: Write>(&self, f: &mut W, _: Version) -> Option<fmt::Result> {
         let mut bit_mask = 0;
 
         if self.normal {
@@ -307,7 +370,7 @@ impl VersionedToString for HitSound {
             bit_mask |= 8;
         }
 
-        Some(bit_mask.to_string())
+        Some(write!(f, "{bit_mask}"))
     }
 }
 
@@ -385,6 +448,7 @@ impl VersionedFrom<u8> for HitSound {
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 /// Type of curve used to construct the [`Slider`][super::SlideParams].
 pub enum CurveType {
@@ -413,7 +477,13 @@ impl VersionedFromStr for CurveType {
 }
 
 impl VersionedToString for CurveType {
-    fn to_string(&self, _: Version) -> Option<String> {
+    fn to_string(&self, version: Version) -> Option<String> {
+        write_versioned_to_string(self, version)
+    }
+}
+
+impl VersionedWrite for CurveType {
+    fn write_versioned<W: Write>(&self, f: &mut W, _: Version) -> Option<fmt::Result> {
         let curve = match self {
             CurveType::Bezier => "B",
             CurveType::Centripetal => "C",
@@ -421,11 +491,12 @@ impl VersionedToString for CurveType {
             CurveType::PerfectCircle => "P",
         };
 
-        Some(curve.to_string())
+        Some(f.write_str(curve))
     }
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum SampleIndex {
     TimingPointSampleIndex,
@@ -463,7 +534,15 @@ impl VersionedFrom<SampleIndex> for usize {
 
 impl VersionedToString for SampleIndex {
     fn to_string(&self, version: Version) -> Option<String> {
-        <usize as VersionedFrom<SampleIndex>>::from(*self, version).map(|i| i.to_string())
+        write_versioned_to_string(self, version)
+    }
+}
+
+impl VersionedWrite for SampleIndex {
+    fn write_versioned<W: Write>(&self, f: &mut W, version: Version) -> Option<fmt::Result> {
+        let index = <usize as VersionedFrom<SampleIndex>>::from(*self, version)?;
+
+        Some(write!(f, "{index}"))
     }
 }
 
@@ -479,6 +558,7 @@ impl VersionedFromStr for SampleIndex {
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Information about which samples are played when the object is hit.
 /// It is closely related to [`hitSound`][HitSound].
 pub struct HitSample {
@@ -595,22 +675,24 @@ impl VersionedFromStr for HitSample {
 
 impl VersionedToString for HitSample {
     fn to_string(&self, version: Version) -> Option<String> {
+        write_versioned_to_string(self, version)
+    }
+}
+
+impl VersionedWrite for HitSample {
+    fn write_versioned<W: Write>(&self, f: &mut W, version: Version) -> Option<fmt::Result> {
         let volume: Integer = <i32 as VersionedFrom<Volume>>::from(self.volume, version).unwrap();
         let filename = &self.filename;
+        let normal_set = <usize as VersionedFrom<SampleSet>>::from(self.normal_set, version)?;
+        let addition_set = <usize as VersionedFrom<SampleSet>>::from(self.addition_set, version)?;
+        let index = <usize as VersionedFrom<SampleIndex>>::from(self.index, version)?;
 
         match version {
             MIN_VERSION..=9 => None,
-            10..=11 => Some(format!(
-                "{}:{}:{}",
-                self.normal_set.to_string(version).unwrap(),
-                self.addition_set.to_string(version).unwrap(),
-                self.index.to_string(version).unwrap()
-            )),
-            _ => Some(format!(
-                "{}:{}:{}:{volume}:{filename}",
-                self.normal_set.to_string(version).unwrap(),
-                self.addition_set.to_string(version).unwrap(),
-                self.index.to_string(version).unwrap()
+            10..=11 => Some(write!(f, "{normal_set}:{addition_set}:{index}")),
+            _ => Some(write!(
+                f,
+                "{normal_set}:{addition_set}:{index}:{volume}:{filename}"
             )),
         }
     }
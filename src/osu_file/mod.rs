@@ -1,16 +1,26 @@
+pub mod borrowed;
 pub mod colours;
+pub mod convert;
 pub mod difficulty;
+pub mod difficulty_calc;
 pub mod editor;
 pub mod events;
 pub mod general;
 pub mod hitobjects;
 pub mod metadata;
+pub mod mods;
+pub mod offset;
 pub mod osb;
+pub mod performance;
+pub mod rate;
 pub mod timingpoints;
+pub mod trivia;
 pub mod types;
 
-use std::fmt::{Debug, Display};
+use std::fmt::{self, Debug, Display, Write};
 use std::hash::Hash;
+use std::io::{self, Read};
+use std::path::Path;
 use std::str::FromStr;
 
 use nom::branch::alt;
@@ -21,22 +31,45 @@ use nom::multi::many0;
 use nom::sequence::{preceded, tuple};
 use thiserror::Error;
 
+use crate::helper::{
+    decode_file_bytes, order_unknown_after, ErrorCollector, IoWriter, SectionsWriter,
+    VersionedWrite,
+};
 use crate::parsers::square_section;
+use trivia::SectionSpan;
 
+pub use borrowed::{FieldsRef, LinesRef, OsuFileRef, UnknownSectionRef, ValueRef};
 pub use colours::Colours;
+pub use convert::{ConversionLoss, ConvertError};
 pub use difficulty::Difficulty;
 pub use editor::Editor;
 pub use events::Events;
 pub use general::General;
 pub use hitobjects::HitObjects;
 pub use metadata::Metadata;
+pub use mods::Mods;
 pub use osb::Osb;
+pub use rate::{RenamedField, ScaleTimeError};
 pub use timingpoints::TimingPoints;
+pub use trivia::Trivia;
 
 pub use types::*;
 
+/// Names of the sections in the order they are written in.
+pub(crate) const SECTION_NAMES: [&str; 8] = [
+    "General",
+    "Editor",
+    "Metadata",
+    "Difficulty",
+    "Events",
+    "TimingPoints",
+    "Colours",
+    "HitObjects",
+];
+
 /// An .osu file represented as a struct.
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub struct OsuFile {
     /// Version of the file format.
@@ -67,172 +100,148 @@ pub struct OsuFile {
     /// Hit objects.
     /// Comma-separated lists.
     pub hitobjects: Option<HitObjects>,
+    /// Sections with names that aren't known, which are kept as they are.
+    pub unknown_sections: Vec<UnknownSection>,
+    /// Lines that were skipped from errors when parsing with [`ParseOptions::lenient`].
+    /// - Not included in the output.
+    pub unparsed_lines: Vec<UnparsedLine>,
+    /// Formatting of the input, kept when parsing with [`ParseOptions::lossless`].
+    /// - Set to `None` to write the file in the normal format.
+    pub trivia: Option<Trivia>,
 }
 
-impl OsuFile {
-    /// New `OsuFile` with no data.
-    pub fn new(version: Version) -> Self {
+/// Options for parsing an .osu file with [`OsuFile::from_str_with_options`].
+/// - Construct with `..Default::default()` for the options that aren't set.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParseOptions {
+    /// Skips lines with errors instead of stopping at the first error.
+    /// - The skipped lines are stored in [`OsuFile::unparsed_lines`], and the errors are returned with the `OsuFile`.
+    /// - Errors with the file version are still returned as an error.
+    pub lenient: bool,
+    /// Keeps the formatting of the input in [`OsuFile::trivia`].
+    /// - Writing the file with the same version gives back the input as it was, with only the changed parts being written in the normal format.
+    /// - Comments, blank lines, line endings and the spacing of values are kept.
+    pub lossless: bool,
+    /// Sections to parse, with the other sections being skipped without parsing their contents.
+    /// - `None` parses all of the sections.
+    /// - Skipped sections are left as `None`, and unknown sections are skipped as well.
+    /// - With [`lossless`][Self::lossless], the text of the skipped sections is kept in the trivia and written back as it was.
+    pub sections: Option<Vec<Section>>,
+}
+
+impl ParseOptions {
+    /// Options that only parse the `sections`.
+    pub fn sections(sections: &[Section]) -> Self {
         Self {
-            version,
-            general: None,
-            editor: None,
-            metadata: None,
-            difficulty: None,
-            events: None,
-            timing_points: None,
-            colours: None,
-            hitobjects: None,
-            osb: None,
+            sections: Some(sections.to_vec()),
+            ..Default::default()
         }
     }
 
-    /// Appends .osb file.
-    pub fn append_osb(&mut self, s: &str) -> Result<(), Error<osb::ParseError>> {
-        self.osb = Osb::from_str(s, self.version)?;
-
-        Ok(())
+    /// If `//` comment lines are skipped in the sections, which is with [`lenient`][Self::lenient] or [`lossless`][Self::lossless].
+    /// - Otherwise comments are parsed like any other line, which is an error in most sections.
+    pub(crate) fn skips_comments(&self) -> bool {
+        self.lenient || self.lossless
     }
 
-    /// Generates .osb file contents.
-    pub fn osb_to_string(&self) -> Option<String> {
-        match &self.osb {
-            Some(osb) => osb.to_string(self.version),
-            None => None,
+    /// If the section with the `name` is parsed.
+    fn parses_section(&self, name: &str) -> bool {
+        match &self.sections {
+            Some(sections) => sections.iter().any(|section| section.name() == name),
+            None => true,
         }
     }
-
-    pub fn default(version: Version) -> OsuFile {
-        OsuFile::new(version)
-    }
 }
 
-impl Display for OsuFile {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut sections = Vec::new();
+/// A section of an .osu file.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Section {
+    General,
+    Editor,
+    Metadata,
+    Difficulty,
+    Events,
+    TimingPoints,
+    Colours,
+    HitObjects,
+}
 
-        if let Some(general) = &self.general {
-            if let Some(general) = general.to_string(self.version) {
-                sections.push(("General", general));
-            }
-        }
-        if let Some(editor) = &self.editor {
-            if let Some(editor) = editor.to_string(self.version) {
-                sections.push(("Editor", editor));
-            }
-        }
-        if let Some(metadata) = &self.metadata {
-            if let Some(metadata) = metadata.to_string(self.version) {
-                sections.push(("Metadata", metadata));
-            }
-        }
-        if let Some(difficulty) = &self.difficulty {
-            if let Some(difficulty) = difficulty.to_string(self.version) {
-                sections.push(("Difficulty", difficulty));
-            }
-        }
-        if let Some(events) = &self.events {
-            if let Some(events) = events.to_string(self.version) {
-                sections.push(("Events", events));
-            }
-        }
-        if let Some(timing_points) = &self.timing_points {
-            if let Some(timing_points) = timing_points.to_string(self.version) {
-                sections.push(("TimingPoints", timing_points));
-            }
-        }
-        if let Some(colours) = &self.colours {
-            if let Some(colours) = colours.to_string(self.version) {
-                sections.push(("Colours", colours));
-            }
-        }
-        if let Some(hitobjects) = &self.hitobjects {
-            if let Some(hitobjects) = hitobjects.to_string(self.version) {
-                sections.push(("HitObjects", hitobjects));
-            }
+impl Section {
+    /// Name of the section, as written in the file.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Section::General => "General",
+            Section::Editor => "Editor",
+            Section::Metadata => "Metadata",
+            Section::Difficulty => "Difficulty",
+            Section::Events => "Events",
+            Section::TimingPoints => "TimingPoints",
+            Section::Colours => "Colours",
+            Section::HitObjects => "HitObjects",
         }
-
-        write!(
-            f,
-            "osu file format v{}\n\n{}",
-            self.version,
-            sections
-                .iter()
-                .map(|(name, content)| format!("[{name}]\n{content}"))
-                .collect::<Vec<_>>()
-                .join("\n\n")
-        )
     }
 }
 
-impl FromStr for OsuFile {
-    type Err = Error<ParseError>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let version_text = preceded(
-            alt((tag("\u{feff}"), success(""))),
-            tag::<_, _, nom::error::Error<_>>("osu file format v"),
-        );
-        let version_number = map_res(take_till(|c| c == '\r' || c == '\n'), |s: &str| s.parse());
-
-        let (s, (trailing_ws, version)) = match tuple((
-            multispace0,
-            preceded(version_text, version_number),
-        ))(s)
-        {
-            Ok(ok) => ok,
-            Err(err) => {
-                // wrong line?
-                let err = if let nom::Err::Error(err) = err {
-                    // can find out error by checking the error type
-                    match err.code {
-                        nom::error::ErrorKind::Tag => ParseError::FileVersionDefinedWrong,
-                        nom::error::ErrorKind::MapRes => ParseError::InvalidFileVersion,
-                        _ => {
-                            unreachable!("Not possible to have the error kind {:#?}", err.code)
-                        }
-                    }
-                } else {
-                    unreachable!("Not possible to reach when the errors are already handled, error type is {:#?}", err)
-                };
-
-                return Err(err.into());
-            }
-        };
+/// A line that was skipped from an error when parsing leniently.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnparsedLine {
+    /// Name of the section the line is in.
+    /// - `None` if the line is before any section.
+    pub section: Option<String>,
+    /// Line index in the input.
+    pub line_index: usize,
+    /// The line as it was in the input.
+    pub line: String,
+}
 
-        if !(MIN_VERSION..=LATEST_VERSION).contains(&version) {
-            return Err(ParseError::InvalidFileVersion.into());
+impl OsuFile {
+    /// New `OsuFile` with no data.
+    pub fn new(version: Version) -> Self {
+        Self {
+            version,
+            general: None,
+            editor: None,
+            metadata: None,
+            difficulty: None,
+            events: None,
+            timing_points: None,
+            colours: None,
+            hitobjects: None,
+            osb: None,
+            unknown_sections: Vec::new(),
+            unparsed_lines: Vec::new(),
+            trivia: None,
         }
+    }
 
-        let pre_section_count = s
-            .lines()
-            .take_while(|s| {
-                let s = s.trim();
-                !s.trim().starts_with('[') && !s.trim().ends_with(']')
-            })
-            .count();
-
-        for (i, line) in s.lines().take(pre_section_count).enumerate() {
-            let line = line.trim();
-
-            if line.is_empty() {
-                continue;
-            }
-
-            if line.starts_with("//") {
-                continue;
-            }
-
-            return Err(Error::new(ParseError::UnexpectedLine, i));
+    /// Parses an .osu file with the given options.
+    /// - Returns the errors collected if [`ParseOptions::lenient`] is set, which is empty otherwise.
+    pub fn from_str_with_options(
+        s: &str,
+        options: &ParseOptions,
+    ) -> Result<(Self, Vec<Error<ParseError>>), Error<ParseError>> {
+        let input = s;
+        let (s, trailing_ws, version) = parse_version_line(s)?;
+
+        let header_line_index = trailing_ws.matches('\n').count();
+        let (pre_section_lines, sections) = split_sections(s, header_line_index);
+
+        let mut errors = ErrorCollector::new(options.lenient);
+        let mut unparsed_lines = Vec::new();
+
+        for (line_index, line) in pre_section_lines {
+            errors.collect::<()>(Err(Error::new(ParseError::UnexpectedLine, line_index)))?;
+            unparsed_lines.push(UnparsedLine {
+                section: None,
+                line_index,
+                line: line.to_string(),
+            });
         }
 
-        let s = s
-            .lines()
-            .skip(pre_section_count)
-            .collect::<Vec<_>>()
-            .join("\n");
-
-        let (_, sections) = many0(square_section())(&s).unwrap();
-
         let mut section_parsed = Vec::with_capacity(8);
 
         let (
@@ -245,66 +254,154 @@ impl FromStr for OsuFile {
             mut colours,
             mut hitobjects,
         ) = (None, None, None, None, None, None, None, None);
+        let mut unknown_sections = Vec::new();
 
-        let mut line_number = trailing_ws.lines().count() + pre_section_count;
+        let mut section_spans = Vec::new();
 
-        for (ws, section_name, ws2, section) in sections {
-            line_number += ws.lines().count();
-
-            if section_parsed.contains(&section_name) {
-                return Err(Error::new(ParseError::DuplicateSections, line_number));
-            }
-
-            let section_name_line = line_number;
-            line_number += ws2.lines().count();
+        for RawSection {
+            name: section_name,
+            name_line: section_name_line,
+            body_line: line_number,
+            body: section,
+        } in sections
+        {
+            let error_count = errors.errors().len();
+            let mut section_error = None;
+            let mut not_requested = false;
 
             match section_name {
+                _ if section_parsed.contains(&section_name) => {
+                    section_error = Some(ParseError::DuplicateSections);
+                }
+                _ if !options.parses_section(section_name) => {
+                    not_requested = true;
+                }
                 "General" => {
-                    general =
-                        Error::processing_line(General::from_str(section, version), line_number)?;
+                    let (section, section_errors) = Error::processing_line(
+                        General::from_str_errors(section, version, options),
+                        line_number,
+                    )?;
+                    general = section;
+                    errors.extend(section_errors, line_number);
                 }
                 "Editor" => {
-                    editor =
-                        Error::processing_line(Editor::from_str(section, version), line_number)?;
+                    let (section, section_errors) = Error::processing_line(
+                        Editor::from_str_errors(section, version, options),
+                        line_number,
+                    )?;
+                    editor = section;
+                    errors.extend(section_errors, line_number);
                 }
                 "Metadata" => {
-                    metadata =
-                        Error::processing_line(Metadata::from_str(section, version), line_number)?;
+                    let (section, section_errors) = Error::processing_line(
+                        Metadata::from_str_errors(section, version, options),
+                        line_number,
+                    )?;
+                    metadata = section;
+                    errors.extend(section_errors, line_number);
                 }
                 "Difficulty" => {
-                    difficulty = Error::processing_line(
-                        Difficulty::from_str(section, version),
+                    let (section, section_errors) = Error::processing_line(
+                        Difficulty::from_str_errors(section, version, options),
                         line_number,
                     )?;
+                    difficulty = section;
+                    errors.extend(section_errors, line_number);
                 }
                 "Events" => {
-                    events =
-                        Error::processing_line(Events::from_str(section, version), line_number)?;
+                    let (section, section_errors) = Error::processing_line(
+                        Events::from_str_errors(section, version, &[], options.lenient),
+                        line_number,
+                    )?;
+                    events = section;
+                    errors.extend(section_errors, line_number);
                 }
                 "TimingPoints" => {
-                    timing_points = Error::processing_line(
-                        TimingPoints::from_str(section, version),
+                    let (section, section_errors) = Error::processing_line(
+                        TimingPoints::from_str_errors(section, version, options),
                         line_number,
                     )?;
+                    timing_points = section;
+                    errors.extend(section_errors, line_number);
                 }
                 "Colours" => {
-                    colours =
-                        Error::processing_line(Colours::from_str(section, version), line_number)?;
+                    let (section, section_errors) = Error::processing_line(
+                        Colours::from_str_errors(section, version, options),
+                        line_number,
+                    )?;
+                    colours = section;
+                    errors.extend(section_errors, line_number);
                 }
                 "HitObjects" => {
-                    hitobjects = Error::processing_line(
-                        HitObjects::from_str(section, version),
+                    let (section, section_errors) = Error::processing_line(
+                        HitObjects::from_str_errors(section, version, options),
                         line_number,
                     )?;
+                    hitobjects = section;
+                    errors.extend(section_errors, line_number);
+                }
+                _ => {
+                    unknown_sections.push(UnknownSection {
+                        name: section_name.to_string(),
+                        lines: section
+                            .lines()
+                            .filter(|line| !line.trim().is_empty())
+                            .map(|line| line.to_string())
+                            .collect(),
+                        after: section_parsed.last().map(|name: &&str| name.to_string()),
+                    });
                 }
-                _ => return Err(Error::new(ParseError::UnknownSection, section_name_line)),
             }
 
+            let skipped = section_error.is_some() || not_requested;
+
+            match section_error {
+                // the whole section is skipped
+                Some(section_error) => {
+                    errors.collect::<()>(Err(Error::new(section_error, section_name_line)))?;
+
+                    unparsed_lines.push(UnparsedLine {
+                        section: Some(section_name.to_string()),
+                        line_index: section_name_line,
+                        line: format!("[{section_name}]"),
+                    });
+                    unparsed_lines.extend(
+                        section
+                            .lines()
+                            .enumerate()
+                            .filter(|(_, line)| !line.trim().is_empty())
+                            .map(|(i, line)| UnparsedLine {
+                                section: Some(section_name.to_string()),
+                                line_index: line_number + i,
+                                line: line.to_string(),
+                            }),
+                    );
+                }
+                None => {
+                    unparsed_lines.extend(errors.errors()[error_count..].iter().map(|err| {
+                        UnparsedLine {
+                            section: Some(section_name.to_string()),
+                            line_index: err.line_index(),
+                            line: input
+                                .lines()
+                                .nth(err.line_index())
+                                .unwrap_or_default()
+                                .to_string(),
+                        }
+                    }));
+                }
+            }
+
+            section_spans.push(SectionSpan {
+                name: section_name,
+                name_line: section_name_line,
+                body_line: line_number,
+                skipped,
+            });
             section_parsed.push(section_name);
-            line_number += section.lines().count() - 1;
         }
 
-        Ok(OsuFile {
+        let mut osu_file = OsuFile {
             version,
             general,
             editor,
@@ -315,7 +412,329 @@ impl FromStr for OsuFile {
             colours,
             hitobjects,
             osb: None,
+            unknown_sections,
+            unparsed_lines,
+            trivia: None,
+        };
+
+        if options.lossless {
+            let skipped_lines = osu_file
+                .unparsed_lines
+                .iter()
+                .map(|line| line.line_index)
+                .collect::<Vec<_>>();
+
+            osu_file.trivia = Some(Trivia::new(
+                input,
+                &osu_file,
+                &section_spans,
+                &skipped_lines,
+            ));
+        }
+
+        Ok((osu_file, errors.into_errors()))
+    }
+
+    /// Parses an .osu file from a reader.
+    /// - The encoding is detected from the content, supporting UTF-8 (with or without a BOM) and UTF-16 LE / BE.
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self, ReadError<ParseError>> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        let s = decode_file_bytes(bytes)?;
+
+        Ok(s.parse()?)
+    }
+
+    /// Parses an .osu file from the given path.
+    /// - Same as [`from_reader`][Self::from_reader] with the opened file.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, ReadError<ParseError>> {
+        Self::from_reader(std::fs::File::open(path)?)
+    }
+
+    /// Appends .osb file.
+    pub fn append_osb(&mut self, s: &str) -> Result<(), Error<osb::ParseError>> {
+        self.osb = Osb::from_str(s, self.version)?;
+
+        Ok(())
+    }
+
+    /// Appends .osb file from the given path.
+    /// - The encoding is detected the same way as [`from_reader`][Self::from_reader].
+    pub fn append_osb_from_path<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+    ) -> Result<(), ReadError<osb::ParseError>> {
+        self.osb = Osb::from_path(path, self.version)?;
+
+        Ok(())
+    }
+
+    /// Generates .osb file contents.
+    pub fn osb_to_string(&self) -> Option<String> {
+        match &self.osb {
+            Some(osb) => osb.to_string(self.version),
+            None => None,
+        }
+    }
+
+    pub fn default(version: Version) -> OsuFile {
+        OsuFile::new(version)
+    }
+
+    /// Game mode of the beatmap, which is osu!standard if it isn't set.
+    pub fn mode(&self) -> general::Mode {
+        self.general
+            .as_ref()
+            .and_then(|general| general.mode)
+            .unwrap_or(general::Mode::Osu)
+    }
+
+    /// Writes the .osu file to the writer, without building the whole file as a `String` first.
+    /// - Output is the same as the `Display` implementation, other than using `version` as the file version.
+    /// - The writer is buffered internally.
+    pub fn write_to<W: io::Write>(&self, writer: &mut W, version: Version) -> io::Result<()> {
+        let mut writer = IoWriter::new(io::BufWriter::new(writer));
+        let res = self.write_fmt_versioned(&mut writer, version);
+
+        writer.finish(res)
+    }
+
+    /// Writes the .osb file to the writer.
+    /// - Same as [`Osb::write_to`], with nothing being written if there's no .osb file.
+    pub fn osb_write_to<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        match &self.osb {
+            Some(osb) => osb.write_to(writer, self.version),
+            None => Ok(()),
+        }
+    }
+
+    /// Output of the section with the given name, which is `None` if the section isn't set or has no output for the version.
+    pub(crate) fn section_to_string(&self, name: &str, version: Version) -> Option<String> {
+        match name {
+            "General" => self.general.as_ref()?.to_string(version),
+            "Editor" => self.editor.as_ref()?.to_string(version),
+            "Metadata" => self.metadata.as_ref()?.to_string(version),
+            "Difficulty" => self.difficulty.as_ref()?.to_string(version),
+            "Events" => self.events.as_ref()?.to_string(version),
+            "TimingPoints" => self.timing_points.as_ref()?.to_string(version),
+            "Colours" => self.colours.as_ref()?.to_string(version),
+            "HitObjects" => self.hitobjects.as_ref()?.to_string(version),
+            _ => self
+                .unknown_sections
+                .iter()
+                .find(|section| section.name == name)
+                .map(|section| section.lines.join("\n")),
+        }
+    }
+
+    /// Names of the known and unknown sections in the order they are written in.
+    /// - Known sections are included even if they aren't set.
+    pub(crate) fn section_names(&self) -> Vec<&str> {
+        let unknown = self
+            .unknown_sections
+            .iter()
+            .map(|section| (section.name.as_str(), section.after.as_deref()))
+            .collect::<Vec<_>>();
+
+        order_unknown_after(&SECTION_NAMES, &unknown)
+            .into_iter()
+            .map(|entry| entry.either(|i| SECTION_NAMES[i], |i| unknown[i].0))
+            .collect()
+    }
+
+    fn write_fmt_versioned<W: fmt::Write>(&self, f: &mut W, version: Version) -> fmt::Result {
+        if let Some(trivia) = &self.trivia {
+            if trivia.version() == version {
+                return trivia.write(f, self);
+            }
+        }
+
+        write!(f, "osu file format v{version}\n\n")?;
+
+        let mut sections = SectionsWriter::new(f);
+
+        for name in self.section_names() {
+            match name {
+                "General" => {
+                    if let Some(general) = &self.general {
+                        sections.section(name, |f| general.write_versioned(f, version))?;
+                    }
+                }
+                "Editor" => {
+                    if let Some(editor) = &self.editor {
+                        sections.section(name, |f| editor.write_versioned(f, version))?;
+                    }
+                }
+                "Metadata" => {
+                    if let Some(metadata) = &self.metadata {
+                        sections.section(name, |f| metadata.write_versioned(f, version))?;
+                    }
+                }
+                "Difficulty" => {
+                    if let Some(difficulty) = &self.difficulty {
+                        sections.section(name, |f| difficulty.write_versioned(f, version))?;
+                    }
+                }
+                "Events" => {
+                    if let Some(events) = &self.events {
+                        sections.section(name, |f| events.write_versioned(f, version))?;
+                    }
+                }
+                "TimingPoints" => {
+                    if let Some(timing_points) = &self.timing_points {
+                        sections.section(name, |f| timing_points.write_versioned(f, version))?;
+                    }
+                }
+                "Colours" => {
+                    if let Some(colours) = &self.colours {
+                        sections.section(name, |f| colours.write_versioned(f, version))?;
+                    }
+                }
+                "HitObjects" => {
+                    if let Some(hitobjects) = &self.hitobjects {
+                        sections.section(name, |f| hitobjects.write_versioned(f, version))?;
+                    }
+                }
+                _ => {
+                    sections.section(name, |f| {
+                        self.unknown_sections
+                            .iter()
+                            .find(|section| section.name == name)
+                            .map(|section| {
+                                for (i, line) in section.lines.iter().enumerate() {
+                                    if i > 0 {
+                                        f.write_char('\n')?;
+                                    }
+                                    f.write_str(line)?;
+                                }
+
+                                Ok(())
+                            })
+                    })?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for OsuFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_fmt_versioned(f, self.version)
+    }
+}
+
+/// A section of the file split by [`split_sections`], borrowing from the input.
+pub(crate) struct RawSection<'a> {
+    pub name: &'a str,
+    /// Index of the line with the section name.
+    pub name_line: usize,
+    /// Index of the first line of the body, after the blank lines following the name.
+    pub body_line: usize,
+    pub body: &'a str,
+}
+
+/// Splits the input after the version line into the lines before the first section and the sections.
+/// - `header_line_index` is the index of the version line.
+/// - The lines before the first section are the ones that aren't blank or comments, with their index.
+pub(crate) fn split_sections(
+    s: &str,
+    header_line_index: usize,
+) -> (Vec<(usize, &str)>, Vec<RawSection<'_>>) {
+    let pre_section_count = s
+        .lines()
+        .take_while(|s| {
+            let s = s.trim();
+            !s.starts_with('[') && !s.ends_with(']')
+        })
+        .count();
+
+    let pre_section_lines = s
+        .lines()
+        .take(pre_section_count)
+        .enumerate()
+        .map(|(i, line)| (header_line_index + i, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with("//"))
+        .collect();
+
+    let sections_start = s
+        .split_inclusive('\n')
+        .take(pre_section_count)
+        .map(|line| line.len())
+        .sum::<usize>();
+    let (_, sections) = many0(square_section())(&s[sections_start..]).unwrap();
+
+    let mut line_index = header_line_index + pre_section_count;
+    let sections = sections
+        .into_iter()
+        .map(|(ws, name, ws2, body)| {
+            line_index += ws.matches('\n').count();
+            let name_line = line_index;
+            line_index += ws2.matches('\n').count();
+            let body_line = line_index;
+            line_index += body.matches('\n').count();
+
+            RawSection {
+                name,
+                name_line,
+                body_line,
+                // the body ends before the `\n` of a `\r\n`
+                body: body.strip_suffix('\r').unwrap_or(body),
+            }
         })
+        .collect();
+
+    (pre_section_lines, sections)
+}
+
+/// Parses the `osu file format v..` line at the start of the file.
+/// - Returns the input after the version number, with the whitespace before the line.
+pub(crate) fn parse_version_line(s: &str) -> Result<(&str, &str, Version), Error<ParseError>> {
+    let version_text = preceded(
+        alt((tag("\u{feff}"), success(""))),
+        tag::<_, _, nom::error::Error<_>>("osu file format v"),
+    );
+    let version_number = map_res(take_till(|c| c == '\r' || c == '\n'), |s: &str| s.parse());
+
+    let (s, (trailing_ws, version)) = match tuple((
+        multispace0,
+        preceded(version_text, version_number),
+    ))(s)
+    {
+        Ok(ok) => ok,
+        Err(err) => {
+            // wrong line?
+            let err = if let nom::Err::Error(err) = err {
+                // can find out error by checking the error type
+                match err.code {
+                    nom::error::ErrorKind::Tag => ParseError::FileVersionDefinedWrong,
+                    nom::error::ErrorKind::MapRes => ParseError::InvalidFileVersion,
+                    _ => {
+                        unreachable!("Not possible to have the error kind {:#?}", err.code)
+                    }
+                }
+            } else {
+                unreachable!("Not possible to reach when the errors are already handled, error type is {:#?}", err)
+            };
+
+            return Err(err.into());
+        }
+    };
+
+    if !(MIN_VERSION..=LATEST_VERSION).contains(&version) {
+        return Err(ParseError::InvalidFileVersion.into());
+    }
+
+    Ok((s, trailing_ws, version))
+}
+
+impl FromStr for OsuFile {
+    type Err = Error<ParseError>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        OsuFile::from_str_with_options(s, &ParseOptions::default()).map(|(osu_file, _)| osu_file)
     }
 }
 
@@ -0,0 +1,222 @@
+//! Gameplay mods, and how they change the difficulty and hitobjects of a beatmap.
+
+use either::Either;
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal_macros::dec;
+
+use super::{
+    difficulty::{approach_rate_from_preempt, Difficulty},
+    general::Mode,
+    hitobjects::{HitObject, HitObjectParams},
+    Decimal, OsuFile, Position,
+};
+
+/// Highest value the difficulty settings can be raised to by [`Mods::hard_rock`].
+const MAX_DIFFICULTY: f64 = 10.0;
+/// Height of the playfield in `osu!pixels`, which hard rock flips the hitobjects in.
+const PLAYFIELD_HEIGHT: rust_decimal::Decimal = dec!(384);
+
+/// A difficulty setting as a `Decimal`, rounded to the digits a `f64` can keep.
+fn decimal_value(value: f64) -> Option<Decimal> {
+    rust_decimal::Decimal::from_f64(value).map(Decimal::new)
+}
+
+/// Flips the `position` upside down in the playfield.
+/// - Coordinates that aren't numbers are left as they are.
+fn flip_vertically(position: &mut Position) {
+    if let Either::Left(y) = position.y.get_mut() {
+        *y = PLAYFIELD_HEIGHT - *y;
+    }
+}
+
+/// A set of mods, like the ones in a score.
+/// - Construct with `..Default::default()` for the mods that aren't set.
+/// - Mods that don't change the difficulty or performance points, like autoplay, aren't included.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Mods {
+    pub no_fail: bool,
+    pub easy: bool,
+    pub touch_device: bool,
+    pub hidden: bool,
+    pub hard_rock: bool,
+    pub sudden_death: bool,
+    pub double_time: bool,
+    pub relax: bool,
+    pub half_time: bool,
+    /// Same as [`double_time`][Self::double_time] for the difficulty.
+    pub nightcore: bool,
+    pub flashlight: bool,
+    pub spun_out: bool,
+    pub autopilot: bool,
+    pub perfect: bool,
+}
+
+impl Mods {
+    /// Bit of each mod in the mods of osu!stable's scores and replays, in the order of the fields.
+    const BITS: [u32; 14] = [
+        1, 2, 4, 8, 16, 32, 64, 128, 256, 512, 1024, 4096, 8192, 16384,
+    ];
+
+    fn fields(&self) -> [bool; 14] {
+        [
+            self.no_fail,
+            self.easy,
+            self.touch_device,
+            self.hidden,
+            self.hard_rock,
+            self.sudden_death,
+            self.double_time,
+            self.relax,
+            self.half_time,
+            self.nightcore,
+            self.flashlight,
+            self.spun_out,
+            self.autopilot,
+            self.perfect,
+        ]
+    }
+
+    /// Mods from the bits osu!stable stores them as in scores and replays.
+    /// - Bits of mods that aren't included are ignored.
+    pub fn from_bits(bits: u32) -> Self {
+        let [no_fail, easy, touch_device, hidden, hard_rock, sudden_death, double_time, relax, half_time, nightcore, flashlight, spun_out, autopilot, perfect] =
+            Self::BITS.map(|bit| bits & bit != 0);
+
+        Self {
+            no_fail,
+            easy,
+            touch_device,
+            hidden,
+            hard_rock,
+            sudden_death,
+            double_time,
+            relax,
+            half_time,
+            nightcore,
+            flashlight,
+            spun_out,
+            autopilot,
+            perfect,
+        }
+    }
+
+    /// The mods as the bits osu!stable stores them as in scores and replays.
+    pub fn bits(&self) -> u32 {
+        self.fields()
+            .iter()
+            .zip(Self::BITS)
+            .filter(|(set, _)| **set)
+            .fold(0, |bits, (_, bit)| bits | bit)
+    }
+
+    /// How much faster the beatmap is played.
+    /// - `1.5` with double time or nightcore, `0.75` with half time and `1` otherwise.
+    pub fn clock_rate(&self) -> f64 {
+        if self.double_time || self.nightcore {
+            1.5
+        } else if self.half_time {
+            0.75
+        } else {
+            1.0
+        }
+    }
+
+    /// Difficulty settings with hard rock or easy applied in the `mode`.
+    /// - Hard rock multiplies the circle size by `1.3` and the other settings by `1.4`, up to `10`.
+    /// - Easy halves the settings.
+    /// - The circle size and approach rate are only changed in osu!standard and osu!catch, since the circle size is the key count in osu!mania and neither is used in osu!taiko.
+    /// - Settings that aren't set are given their default values before being changed.
+    pub fn difficulty(&self, difficulty: &Difficulty, mode: Mode) -> Difficulty {
+        let mut difficulty = difficulty.clone();
+
+        if !self.hard_rock && !self.easy {
+            return difficulty;
+        }
+
+        let values = difficulty.values();
+        let adjust = |value: f64, hard_rock_multiplier: f64| {
+            let mut value = value;
+            if self.easy {
+                value *= 0.5;
+            }
+            if self.hard_rock {
+                value = (value * hard_rock_multiplier).min(MAX_DIFFICULTY);
+            }
+
+            decimal_value(value)
+        };
+
+        difficulty.hp_drain_rate = adjust(values.hp_drain_rate, 1.4).map(Into::into);
+        difficulty.overall_difficulty = adjust(values.overall_difficulty, 1.4).map(Into::into);
+        if matches!(mode, Mode::Osu | Mode::Catch) {
+            difficulty.circle_size = adjust(values.circle_size, 1.3).map(Into::into);
+            difficulty.approach_rate = adjust(values.approach_rate, 1.4).map(Into::into);
+        }
+
+        difficulty
+    }
+
+    /// Difficulty settings with the mods applied, where the approach rate and overall difficulty are what they play like at the normal speed.
+    /// - Double time and half time change them by the clock rate, so they can go above `10` or below `0`.
+    /// - The overall difficulty is changed with the hit windows of the `mode`, and is left as it is for osu!mania since its hit windows don't change with the speed.
+    /// - The approach rate is only changed in osu!standard and osu!catch, like [`Mods::difficulty`].
+    pub fn effective_difficulty(&self, difficulty: &Difficulty, mode: Mode) -> Difficulty {
+        let mut difficulty = self.difficulty(difficulty, mode);
+        let clock_rate = self.clock_rate();
+
+        if clock_rate == 1.0 {
+            return difficulty;
+        }
+
+        let values = difficulty.values();
+        let overall_difficulty = match mode {
+            Mode::Osu | Mode::Catch => (80.0 - values.osu().hit_window_300 / clock_rate) / 6.0,
+            Mode::Taiko => (50.0 - values.taiko().hit_window_great / clock_rate) / 3.0,
+            Mode::Mania => values.overall_difficulty,
+        };
+
+        if matches!(mode, Mode::Osu | Mode::Catch) {
+            difficulty.approach_rate =
+                decimal_value(approach_rate_from_preempt(values.preempt() / clock_rate))
+                    .map(Into::into);
+        }
+        difficulty.overall_difficulty = decimal_value(overall_difficulty).map(Into::into);
+
+        difficulty
+    }
+}
+
+impl OsuFile {
+    /// Applies the `mods` to the beatmap, giving the changed beatmap and its difficulty settings at the normal speed.
+    /// - Hard rock flips the hitobjects and slider curve points upside down in osu!standard, and changes the difficulty settings with easy like [`Mods::difficulty`] does.
+    /// - The beatmap isn't sped up, so the returned difficulty from [`Mods::effective_difficulty`] has the approach rate and overall difficulty of double time or half time.
+    pub fn with_mods(&self, mods: &Mods) -> (OsuFile, Difficulty) {
+        let mut osu_file = self.clone();
+        let difficulty = self.difficulty.clone().unwrap_or_default();
+        let mode = self.mode();
+
+        if mods.hard_rock || mods.easy {
+            osu_file.difficulty = Some(mods.difficulty(&difficulty, mode));
+        }
+
+        if mods.hard_rock && mode == Mode::Osu {
+            if let Some(hitobjects) = &mut osu_file.hitobjects {
+                hitobjects.0.iter_mut().for_each(flip_hitobject);
+            }
+        }
+
+        (osu_file, mods.effective_difficulty(&difficulty, mode))
+    }
+}
+
+/// Flips the position of the `hitobject` and its slider curve points upside down.
+fn flip_hitobject(hitobject: &mut HitObject) {
+    flip_vertically(&mut hitobject.position);
+
+    if let HitObjectParams::Slider(params) = &mut hitobject.obj_params {
+        for curve_point in &mut params.curve_points {
+            flip_vertically(&mut curve_point.0);
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! Shifting the times of a beatmap, for correcting the offset of its audio.
+
+use either::Either;
+
+use super::{Decimal, Integer, OsuFile};
+
+/// Shifts the time in milliseconds by `delta`.
+/// - Times that aren't numbers are left as they are.
+/// - Times saturate at the bounds of the type instead of overflowing.
+pub(crate) fn shift_decimal(time: &mut Decimal, delta: Integer) {
+    if let Either::Left(time) = time.get_mut() {
+        *time = time.saturating_add(delta.into());
+    }
+}
+
+impl OsuFile {
+    /// Shifts every time in the beatmap by `delta` milliseconds, like correcting the offset of the audio.
+    /// - Hitobjects, timing points, events and storyboard commands, including the .osb storyboard, the preview time and bookmarks are shifted.
+    /// - Commands in loops and triggers aren't shifted, since their times are relative to the loop or trigger.
+    /// - Times saturate at the bounds of their types instead of overflowing.
+    pub fn shift_time(&mut self, delta: Integer) {
+        if let Some(general) = &mut self.general {
+            if let Some(preview_time) = &general.preview_time {
+                let preview_time: Integer = preview_time.clone().into();
+                // -1 is no preview time
+                if preview_time != -1 {
+                    general.preview_time = Some(preview_time.saturating_add(delta).into());
+                }
+            }
+            if let Some(editor_bookmarks) = &general.editor_bookmarks {
+                let editor_bookmarks: Vec<Integer> = editor_bookmarks.clone().into();
+                general.editor_bookmarks = Some(
+                    editor_bookmarks
+                        .into_iter()
+                        .map(|bookmark| bookmark.saturating_add(delta))
+                        .collect::<Vec<_>>()
+                        .into(),
+                );
+            }
+        }
+
+        if let Some(editor) = &mut self.editor {
+            if let Some(bookmarks) = &editor.bookmarks {
+                let bookmarks: Vec<Integer> = bookmarks.clone().into();
+                editor.bookmarks = Some(
+                    bookmarks
+                        .into_iter()
+                        .map(|bookmark| bookmark.saturating_add(delta))
+                        .collect::<Vec<_>>()
+                        .into(),
+                );
+            }
+        }
+
+        if let Some(osb) = &mut self.osb {
+            osb.shift_time(delta);
+        }
+        if let Some(events) = &mut self.events {
+            events.shift_time(delta);
+        }
+        if let Some(timing_points) = &mut self.timing_points {
+            timing_points.shift_time(delta);
+        }
+        if let Some(hitobjects) = &mut self.hitobjects {
+            hitobjects.shift_time(delta);
+        }
+    }
+}
@@ -1,19 +1,82 @@
 pub mod error;
 pub mod types;
 
+use std::fmt::{self, Write};
+use std::io::{self, Read};
+use std::path::Path;
+
+use either::Either;
 use nom::multi::many0;
 
+use crate::helper::{
+    decode_file_bytes, order_unknown_after, write_new_line_separated, write_versioned_to_string,
+    IoWriter, SectionsWriter, VersionedWrite,
+};
 use crate::parsers::square_section;
 
-use super::{Error, Events, Version, VersionedFromStr, VersionedToString};
+use super::{
+    Error, Events, Integer, ReadError, UnknownSection, Version, VersionedFromStr, VersionedToString,
+};
 
 pub use error::*;
 pub use types::*;
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Osb {
     pub variables: Option<Vec<Variable>>,
     pub events: Option<Events>,
+    /// Sections with names that aren't known, which are kept as they are.
+    pub unknown_sections: Vec<UnknownSection>,
+}
+
+impl Osb {
+    /// Parses an .osb file from a reader.
+    /// - The encoding is detected from the content, supporting UTF-8 (with or without a BOM) and UTF-16 LE / BE.
+    pub fn from_reader<R: Read>(
+        mut reader: R,
+        version: Version,
+    ) -> Result<Option<Self>, ReadError<ParseError>> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        let s = decode_file_bytes(bytes)?;
+
+        Ok(<Osb as VersionedFromStr>::from_str(&s, version)?)
+    }
+
+    /// Parses an .osb file from the given path.
+    /// - Same as [`from_reader`][Self::from_reader] with the opened file.
+    pub fn from_path<P: AsRef<Path>>(
+        path: P,
+        version: Version,
+    ) -> Result<Option<Self>, ReadError<ParseError>> {
+        Self::from_reader(std::fs::File::open(path)?, version)
+    }
+
+    /// Writes the .osb file to the writer, without building the whole file as a `String` first.
+    /// - Output is the same as `to_string`, with nothing being written if the version doesn't support .osb files.
+    /// - The writer is buffered internally.
+    pub fn write_to<W: io::Write>(&self, writer: &mut W, version: Version) -> io::Result<()> {
+        let mut writer = IoWriter::new(io::BufWriter::new(writer));
+        let res = self.write_versioned(&mut writer, version).unwrap_or(Ok(()));
+
+        writer.finish(res)
+    }
+
+    /// Changes the speed of the storyboard to the `rate`, like [`Events::scale_time`].
+    pub fn scale_time(&mut self, rate: f64) {
+        if let Some(events) = &mut self.events {
+            events.scale_time(rate);
+        }
+    }
+
+    /// Shifts the times of the storyboard by `delta` milliseconds, like [`Events::shift_time`].
+    pub fn shift_time(&mut self, delta: Integer) {
+        if let Some(events) = &mut self.events {
+            events.shift_time(delta);
+        }
+    }
 }
 
 impl VersionedFromStr for Osb {
@@ -60,6 +123,7 @@ impl VersionedFromStr for Osb {
         let mut line_number = pre_section_count;
 
         let (mut events, mut variables) = (None, None);
+        let mut unknown_sections = Vec::new();
 
         for (ws, section_name, ws2, section) in sections {
             line_number += ws.lines().count();
@@ -68,7 +132,6 @@ impl VersionedFromStr for Osb {
                 return Err(Error::new(ParseError::DuplicateSections, line_number));
             }
 
-            let section_name_line = line_number;
             line_number += ws2.lines().count();
 
             match section_name {
@@ -98,48 +161,79 @@ impl VersionedFromStr for Osb {
                         line_number,
                     )?;
                 }
-                _ => return Err(Error::new(ParseError::UnknownSection, section_name_line)),
+                _ => {
+                    unknown_sections.push(UnknownSection {
+                        name: section_name.to_string(),
+                        lines: section
+                            .lines()
+                            .filter(|line| !line.trim().is_empty())
+                            .map(|line| line.to_string())
+                            .collect(),
+                        after: section_parsed.last().map(|name: &&str| name.to_string()),
+                    });
+                }
             }
 
             section_parsed.push(section_name);
             line_number += section.lines().count().saturating_sub(1);
         }
 
-        Ok(Some(Osb { events, variables }))
+        Ok(Some(Osb {
+            events,
+            variables,
+            unknown_sections,
+        }))
     }
 }
 
 impl VersionedToString for Osb {
     fn to_string(&self, version: Version) -> Option<String> {
+        write_versioned_to_string(self, version)
+    }
+}
+
+impl VersionedWrite for Osb {
+    fn write_versioned<W: fmt::Write>(&self, f: &mut W, version: Version) -> Option<fmt::Result> {
         if version < 14 {
             None
         } else {
-            let mut sections = Vec::new();
-
-            if let Some(variables) = &self.variables {
-                sections.push(format!(
-                    "[Variables]\n{}",
-                    variables
-                        .iter()
-                        .map(|v| v.to_string(version).unwrap())
-                        .collect::<Vec<_>>()
-                        .join("\n"),
-                ));
-            }
-            if let Some(events) = &self.events {
-                // Events existed longer than storyboards I think
-                sections.push(format!(
-                    "[Events]\n{}",
-                    events
-                        .to_string_variables(
-                            version,
-                            self.variables.as_ref().unwrap_or(&Vec::new()),
-                        )
-                        .unwrap()
-                ))
-            }
+            let mut sections = SectionsWriter::new(f);
+
+            let known = ["Variables", "Events"];
+            let unknown = self
+                .unknown_sections
+                .iter()
+                .map(|section| (section.name.as_str(), section.after.as_deref()))
+                .collect::<Vec<_>>();
+
+            let res = order_unknown_after(&known, &unknown)
+                .into_iter()
+                .try_for_each(|entry| match entry {
+                    Either::Left(0) => sections.section("Variables", |f| {
+                        self.variables
+                            .as_ref()
+                            .map(|variables| write_new_line_separated(f, variables, version))
+                    }),
+                    // Events existed longer than storyboards I think
+                    Either::Left(_) => sections.section("Events", |f| {
+                        self.events.as_ref().map(|events| {
+                            events.write_variables(
+                                f,
+                                version,
+                                self.variables.as_deref().unwrap_or_default(),
+                            )
+                        })
+                    }),
+                    Either::Right(i) => {
+                        let section = &self.unknown_sections[i];
+
+                        sections.section(&section.name, |f| {
+                            Some(f.write_str(&section.lines.join("\n")))
+                        })
+                    }
+                });
 
-            Some(sections.join("\n\n"))
+            Some(res)
         }
     }
 }
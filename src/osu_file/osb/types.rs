@@ -9,6 +9,7 @@ use super::*;
 use crate::osu_file::VersionedFromStr;
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Variable {
     pub name: String,
     pub value: String,
@@ -42,7 +43,13 @@ impl VersionedFromStr for Variable {
 }
 
 impl VersionedToString for Variable {
-    fn to_string(&self, _: Version) -> Option<String> {
-        Some(format!("${}={}", self.name, self.value))
+    fn to_string(&self, version: Version) -> Option<String> {
+        write_versioned_to_string(self, version)
+    }
+}
+
+impl VersionedWrite for Variable {
+    fn write_versioned<W: Write>(&self, f: &mut W, _: Version) -> Option<fmt::Result> {
+        Some(write!(f, "${}={}", self.name, self.value))
     }
 }
@@ -0,0 +1,107 @@
+//! Performance points of osu!catch.
+
+use super::{combo_scaling, Score, ScoreHits};
+use crate::osu_file::{
+    difficulty_calc::{self, CatchDifficultyAttributes},
+    OsuFile,
+};
+
+/// Performance points of an osu!catch score.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CatchPerformanceAttributes {
+    pub pp: f64,
+    /// Difficulty of the beatmap with the mods of the score.
+    pub difficulty_attributes: CatchDifficultyAttributes,
+}
+
+/// Computes the performance points of the `score` on the osu!catch beatmap.
+/// - Misses are fruits or droplets that weren't caught.
+pub(crate) fn calculate(osu_file: &OsuFile, score: &Score) -> CatchPerformanceAttributes {
+    let attributes = difficulty_calc::catch::calculate(osu_file, &score.mods);
+    let mods = &score.mods;
+
+    let misses = score.misses.min(attributes.max_combo);
+    let (fruits, droplets, tiny_droplets, tiny_droplet_misses) = match score.hits {
+        ScoreHits::Accuracy(accuracy) => {
+            let total = attributes.max_combo + attributes.tiny_droplet_count;
+            let accuracy = (accuracy / 100.0).clamp(0.0, 1.0);
+            let tiny_droplet_misses = ((total as f64 * (1.0 - accuracy)).round() as usize)
+                .saturating_sub(misses)
+                .min(attributes.tiny_droplet_count);
+
+            // the misses are taken from the fruits first
+            let fruit_misses = misses.min(attributes.fruit_count);
+            (
+                attributes.fruit_count - fruit_misses,
+                attributes
+                    .droplet_count
+                    .saturating_sub(misses - fruit_misses),
+                attributes.tiny_droplet_count - tiny_droplet_misses,
+                tiny_droplet_misses,
+            )
+        }
+        ScoreHits::Counts(counts) => (counts.n300, counts.n100, counts.n50, counts.n_katu),
+    };
+
+    let total_hits = fruits + droplets + tiny_droplets + misses + tiny_droplet_misses;
+    let accuracy = if total_hits > 0 {
+        ((fruits + droplets + tiny_droplets) as f64 / total_hits as f64).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let combo = score
+        .combo
+        .unwrap_or(attributes.max_combo)
+        .min(attributes.max_combo);
+
+    let mut pp = (5.0 * (attributes.star_rating / 0.0049).max(1.0) - 4.0).powi(2) / 100000.0;
+
+    // only the objects that give combo make the beatmap longer
+    let combo_hits = (fruits + droplets + misses) as f64;
+    let length_bonus = 0.95
+        + 0.3 * (combo_hits / 2500.0).min(1.0)
+        + if combo_hits > 2500.0 {
+            (combo_hits / 2500.0).log10() * 0.475
+        } else {
+            0.0
+        };
+    pp *= length_bonus;
+    pp *= 0.97f64.powi(misses as i32);
+    pp *= combo_scaling(combo, attributes.max_combo);
+
+    let approach_rate = attributes.approach_rate;
+    let mut approach_rate_factor = 1.0;
+    if approach_rate > 9.0 {
+        approach_rate_factor += 0.1 * (approach_rate - 9.0);
+    }
+    if approach_rate > 10.0 {
+        approach_rate_factor += 0.1 * (approach_rate - 10.0);
+    } else if approach_rate < 8.0 {
+        approach_rate_factor += 0.025 * (8.0 - approach_rate);
+    }
+    pp *= approach_rate_factor;
+
+    // hidden gives more for the lower approach rates
+    if mods.hidden {
+        if approach_rate <= 10.0 {
+            pp *= 1.05 + 0.075 * (10.0 - approach_rate);
+        } else {
+            pp *= 1.01 + 0.04 * (11.0 - approach_rate.min(11.0));
+        }
+    }
+    if mods.flashlight {
+        pp *= 1.35 * length_bonus;
+    }
+
+    pp *= accuracy.powf(5.5);
+
+    if mods.no_fail {
+        pp *= 0.9;
+    }
+
+    CatchPerformanceAttributes {
+        pp,
+        difficulty_attributes: attributes,
+    }
+}
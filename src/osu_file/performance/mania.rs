@@ -0,0 +1,74 @@
+//! Performance points of osu!mania.
+
+use super::{estimate_hits, Score, ScoreHits};
+use crate::osu_file::{
+    difficulty_calc::{self, ManiaDifficultyAttributes},
+    OsuFile,
+};
+
+/// Performance points of an osu!mania score.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ManiaPerformanceAttributes {
+    pub pp: f64,
+    /// Performance points from the star rating, before the multipliers of the mods.
+    pub difficulty: f64,
+    /// Difficulty of the beatmap with the mods of the score.
+    pub difficulty_attributes: ManiaDifficultyAttributes,
+}
+
+/// Computes the performance points of the `score` on the osu!mania beatmap.
+/// - Each note and hold counts as one judgement, like in osu!stable.
+pub(crate) fn calculate(osu_file: &OsuFile, score: &Score) -> ManiaPerformanceAttributes {
+    let attributes = difficulty_calc::mania::calculate(osu_file, &score.mods);
+    let mods = &score.mods;
+
+    let total = attributes.note_count + attributes.hold_note_count;
+    let misses = score.misses.min(total);
+    let (n320, n300, n200, n100, n50) = match score.hits {
+        ScoreHits::Accuracy(accuracy) => {
+            // 320s are worth the same as 300s for the accuracy
+            let (n320, n100, n50) = estimate_hits(accuracy, total, total - misses);
+            (n320, 0, 0, n100, n50)
+        }
+        ScoreHits::Counts(counts) => (
+            counts.n_geki,
+            counts.n300,
+            counts.n_katu,
+            counts.n100,
+            counts.n50,
+        ),
+    };
+
+    let total_hits = (n320 + n300 + n200 + n100 + n50 + misses) as f64;
+    // the accuracy used here gives more for 320s than 300s
+    let custom_accuracy = if total_hits > 0.0 {
+        (n320 as f64 * 320.0
+            + n300 as f64 * 300.0
+            + n200 as f64 * 200.0
+            + n100 as f64 * 100.0
+            + n50 as f64 * 50.0)
+            / (total_hits * 320.0)
+    } else {
+        0.0
+    };
+
+    let mut multiplier = 8.0;
+    if mods.no_fail {
+        multiplier *= 0.75;
+    }
+    if mods.easy {
+        multiplier *= 0.5;
+    }
+
+    // each percent of accuracy above 80% gives a twentieth of the points
+    let difficulty = (attributes.star_rating - 0.15).max(0.05).powf(2.2)
+        * (5.0 * custom_accuracy - 4.0).max(0.0)
+        * (1.0 + 0.1 * (total_hits / 1500.0).min(1.0));
+
+    ManiaPerformanceAttributes {
+        pp: difficulty * multiplier,
+        difficulty,
+        difficulty_attributes: attributes,
+    }
+}
@@ -0,0 +1,141 @@
+//! Performance points of scores, computed like osu! does for stable.
+
+pub mod catch;
+pub mod mania;
+pub mod osu;
+pub mod taiko;
+
+pub use catch::CatchPerformanceAttributes;
+pub use mania::ManiaPerformanceAttributes;
+pub use osu::OsuPerformanceAttributes;
+pub use taiko::TaikoPerformanceAttributes;
+
+use super::{general::Mode, Mods, OsuFile};
+
+/// A score to compute the performance points of.
+/// - Construct with `..Default::default()` for the values that aren't set, which is a full combo with 100% accuracy and no mods.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Score {
+    pub mods: Mods,
+    /// Highest combo of the score.
+    /// - `None` is the max combo of the beatmap.
+    pub combo: Option<usize>,
+    /// Accuracy or judgements of the score, not counting the misses.
+    pub hits: ScoreHits,
+    /// Amount of misses.
+    pub misses: usize,
+}
+
+/// Judgements of a score, given directly or from the accuracy.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ScoreHits {
+    /// Accuracy in percent from `0` to `100`, which the judgements are estimated from.
+    /// - The judgements that lose the least accuracy are used first, so the accuracy is as close as it can be.
+    Accuracy(f64),
+    /// Amount of each judgement.
+    Counts(HitCounts),
+}
+
+impl Default for ScoreHits {
+    fn default() -> Self {
+        Self::Accuracy(100.0)
+    }
+}
+
+/// Amount of each judgement of a score, like osu!stable stores them.
+/// - osu!taiko: `n300` are greats and `n100` are oks.
+/// - osu!catch: `n300` are fruits, `n100` are droplets, `n50` are tiny droplets and `n_katu` are missed tiny droplets.
+/// - osu!mania: `n_geki` are 320s and `n_katu` are 200s.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HitCounts {
+    pub n300: usize,
+    pub n100: usize,
+    pub n50: usize,
+    pub n_geki: usize,
+    pub n_katu: usize,
+}
+
+/// Performance points of a score, with the attributes of the mode they are computed from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PerformanceAttributes {
+    Osu(OsuPerformanceAttributes),
+    Taiko(TaikoPerformanceAttributes),
+    Catch(CatchPerformanceAttributes),
+    Mania(ManiaPerformanceAttributes),
+}
+
+impl PerformanceAttributes {
+    /// Total performance points of the score.
+    pub fn pp(&self) -> f64 {
+        match self {
+            PerformanceAttributes::Osu(attributes) => attributes.pp,
+            PerformanceAttributes::Taiko(attributes) => attributes.pp,
+            PerformanceAttributes::Catch(attributes) => attributes.pp,
+            PerformanceAttributes::Mania(attributes) => attributes.pp,
+        }
+    }
+
+    /// Star rating of the beatmap with the mods of the score.
+    pub fn star_rating(&self) -> f64 {
+        match self {
+            PerformanceAttributes::Osu(attributes) => attributes.difficulty_attributes.star_rating,
+            PerformanceAttributes::Taiko(attributes) => {
+                attributes.difficulty_attributes.star_rating
+            }
+            PerformanceAttributes::Catch(attributes) => {
+                attributes.difficulty_attributes.star_rating
+            }
+            PerformanceAttributes::Mania(attributes) => {
+                attributes.difficulty_attributes.star_rating
+            }
+        }
+    }
+}
+
+impl OsuFile {
+    /// Computes the performance points of a `score` on the beatmap in its mode, like osu! does for stable.
+    /// - The difficulty attributes are computed with the mods of the score.
+    pub fn performance(&self, score: &Score) -> PerformanceAttributes {
+        match self.mode() {
+            Mode::Osu => PerformanceAttributes::Osu(osu::calculate(self, score)),
+            Mode::Taiko => PerformanceAttributes::Taiko(taiko::calculate(self, score)),
+            Mode::Catch => PerformanceAttributes::Catch(catch::calculate(self, score)),
+            Mode::Mania => PerformanceAttributes::Mania(mania::calculate(self, score)),
+        }
+    }
+}
+
+/// Estimates the amount of 300s, 100s and 50s of the `remaining` objects that weren't missed, for the `accuracy` out of `total` objects.
+/// - A 100 is a third of a 300 and a 50 is a sixth of one.
+pub(crate) fn estimate_hits(
+    accuracy: f64,
+    total: usize,
+    remaining: usize,
+) -> (usize, usize, usize) {
+    let accuracy = (accuracy / 100.0).clamp(0.0, 1.0);
+    // accuracy lost compared to only 300s, in sixths of a 300
+    let lost = (6.0 * remaining as f64 - 6.0 * accuracy * total as f64).max(0.0);
+
+    let (n100, n50) = if lost <= 4.0 * remaining as f64 {
+        (((lost / 4.0).round() as usize).min(remaining), 0)
+    } else {
+        // 50s are needed once the accuracy is lower than only 100s
+        let n50 = ((lost - 4.0 * remaining as f64).round() as usize).min(remaining);
+        (remaining - n50, n50)
+    };
+
+    (remaining - n100 - n50, n100, n50)
+}
+
+/// Combo of the score compared to the `max_combo`, which scales the performance points of the skills.
+pub(crate) fn combo_scaling(combo: usize, max_combo: usize) -> f64 {
+    if max_combo == 0 {
+        1.0
+    } else {
+        ((combo as f64).powf(0.8) / (max_combo as f64).powf(0.8)).min(1.0)
+    }
+}
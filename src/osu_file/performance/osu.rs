@@ -0,0 +1,318 @@
+//! Performance points of osu!standard.
+
+use super::{combo_scaling, estimate_hits, Score, ScoreHits};
+use crate::osu_file::{
+    difficulty_calc::{
+        self,
+        osu::{base_performance, flashlight_performance, PERFORMANCE_BASE_MULTIPLIER},
+        OsuDifficultyAttributes,
+    },
+    OsuFile,
+};
+
+/// Performance points of an osu!standard score, with the points of each skill.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OsuPerformanceAttributes {
+    pub pp: f64,
+    pub aim: f64,
+    pub speed: f64,
+    pub accuracy: f64,
+    pub flashlight: f64,
+    /// Misses with the slider breaks guessed from the combo.
+    pub effective_miss_count: f64,
+    /// Difficulty of the beatmap with the mods of the score.
+    pub difficulty_attributes: OsuDifficultyAttributes,
+}
+
+/// Judgements and combo of a score, as numbers for the formulas.
+struct ScoreState {
+    combo: usize,
+    n300: f64,
+    n100: f64,
+    n50: f64,
+    misses: f64,
+    accuracy: f64,
+    effective_miss_count: f64,
+}
+
+impl ScoreState {
+    fn total_hits(&self) -> f64 {
+        self.n300 + self.n100 + self.n50 + self.misses
+    }
+
+    /// Performance points multiplier of the misses, with each miss making it lower the fewer objects there are.
+    fn miss_penalty(&self, exponent: f64) -> f64 {
+        if self.effective_miss_count > 0.0 {
+            0.97 * (1.0 - (self.effective_miss_count / self.total_hits()).powf(0.775))
+                .powf(self.effective_miss_count.powf(exponent))
+        } else {
+            1.0
+        }
+    }
+
+    /// Bonus for longer beatmaps.
+    fn length_bonus(&self) -> f64 {
+        let total_hits = self.total_hits();
+
+        0.95 + 0.4 * (total_hits / 2000.0).min(1.0)
+            + if total_hits > 2000.0 {
+                (total_hits / 2000.0).log10() * 0.5
+            } else {
+                0.0
+            }
+    }
+}
+
+/// Computes the performance points of the `score` on the osu!standard beatmap.
+pub(crate) fn calculate(osu_file: &OsuFile, score: &Score) -> OsuPerformanceAttributes {
+    let attributes = difficulty_calc::osu::calculate(osu_file, &score.mods);
+    let mods = &score.mods;
+
+    let total = attributes.hit_circle_count + attributes.slider_count + attributes.spinner_count;
+    let misses = score.misses.min(total);
+    let (n300, n100, n50) = match score.hits {
+        ScoreHits::Accuracy(accuracy) => estimate_hits(accuracy, total, total - misses),
+        ScoreHits::Counts(counts) => (counts.n300, counts.n100, counts.n50),
+    };
+    let combo = score
+        .combo
+        .unwrap_or(attributes.max_combo)
+        .min(attributes.max_combo);
+
+    let mut state = ScoreState {
+        combo,
+        n300: n300 as f64,
+        n100: n100 as f64,
+        n50: n50 as f64,
+        misses: misses as f64,
+        accuracy: 0.0,
+        effective_miss_count: 0.0,
+    };
+    let total_hits = state.total_hits();
+    if total_hits > 0.0 {
+        state.accuracy =
+            (state.n300 * 300.0 + state.n100 * 100.0 + state.n50 * 50.0) / (total_hits * 300.0);
+    }
+    state.effective_miss_count = effective_miss_count(&state, &attributes);
+
+    let mut multiplier = PERFORMANCE_BASE_MULTIPLIER;
+
+    if mods.no_fail {
+        multiplier *= (1.0 - 0.02 * state.effective_miss_count).max(0.9);
+    }
+    if mods.spun_out && total_hits > 0.0 {
+        multiplier *= 1.0 - (attributes.spinner_count as f64 / total_hits).powf(0.85);
+    }
+    if mods.relax {
+        // 100s and 50s are treated as misses, since they are likely to be from missed taps
+        let od = attributes.overall_difficulty;
+        let (n100_multiplier, n50_multiplier) = if od > 0.0 {
+            (
+                (1.0 - (od / 13.33).powf(1.8)).max(0.0),
+                (1.0 - (od / 13.33).powi(5)).max(0.0),
+            )
+        } else {
+            (1.0, 1.0)
+        };
+
+        state.effective_miss_count = (state.effective_miss_count
+            + state.n100 * n100_multiplier
+            + state.n50 * n50_multiplier)
+            .min(total_hits);
+    }
+
+    let aim = aim_value(&state, &attributes, score);
+    let speed = speed_value(&state, &attributes, score);
+    let accuracy = accuracy_value(&state, &attributes, score);
+    let flashlight = flashlight_value(&state, &attributes, score);
+
+    let pp = (aim.powf(1.1) + speed.powf(1.1) + accuracy.powf(1.1) + flashlight.powf(1.1))
+        .powf(1.0 / 1.1)
+        * multiplier;
+
+    OsuPerformanceAttributes {
+        pp,
+        aim,
+        speed,
+        accuracy,
+        flashlight,
+        effective_miss_count: state.effective_miss_count,
+        difficulty_attributes: attributes,
+    }
+}
+
+/// Guesses the amount of slider breaks from the combo, which count as misses.
+fn effective_miss_count(state: &ScoreState, attributes: &OsuDifficultyAttributes) -> f64 {
+    let mut combo_based_miss_count = 0.0;
+
+    if attributes.slider_count > 0 {
+        let full_combo_threshold =
+            attributes.max_combo as f64 - 0.1 * attributes.slider_count as f64;
+
+        if (state.combo as f64) < full_combo_threshold {
+            combo_based_miss_count = full_combo_threshold / (state.combo as f64).max(1.0);
+        }
+    }
+
+    // there can't be more breaks than the judgements that aren't 300s
+    combo_based_miss_count = combo_based_miss_count.min(state.n100 + state.n50 + state.misses);
+
+    combo_based_miss_count.max(state.misses)
+}
+
+fn aim_value(state: &ScoreState, attributes: &OsuDifficultyAttributes, score: &Score) -> f64 {
+    let mut value = base_performance(attributes.aim_difficulty);
+
+    let length_bonus = state.length_bonus();
+    value *= length_bonus;
+    value *= state.miss_penalty(1.0);
+    value *= combo_scaling(state.combo, attributes.max_combo);
+
+    let approach_rate = attributes.approach_rate;
+    let mut approach_rate_factor = if approach_rate > 10.33 {
+        0.3 * (approach_rate - 10.33)
+    } else if approach_rate < 8.0 {
+        0.05 * (8.0 - approach_rate)
+    } else {
+        0.0
+    };
+    if score.mods.relax {
+        approach_rate_factor = 0.0;
+    }
+    // longer beatmaps are harder to read with high approach rates
+    value *= 1.0 + approach_rate_factor * length_bonus;
+
+    // lower approach rates are harder to aim with hidden
+    if score.mods.hidden {
+        value *= 1.0 + 0.04 * (12.0 - approach_rate);
+    }
+
+    // 15% of the sliders are guessed to be hard to follow
+    let estimate_difficult_sliders = attributes.slider_count as f64 * 0.15;
+    if attributes.slider_count > 0 {
+        let estimate_slider_ends_dropped = (state.n100 + state.n50 + state.misses)
+            .min(attributes.max_combo as f64 - state.combo as f64)
+            .clamp(0.0, estimate_difficult_sliders);
+        let slider_nerf_factor = (1.0 - attributes.slider_factor)
+            * (1.0 - estimate_slider_ends_dropped / estimate_difficult_sliders).powi(3)
+            + attributes.slider_factor;
+
+        value *= slider_nerf_factor;
+    }
+
+    value *= state.accuracy;
+    value *= 0.98 + attributes.overall_difficulty.powi(2) / 2500.0;
+
+    value
+}
+
+fn speed_value(state: &ScoreState, attributes: &OsuDifficultyAttributes, score: &Score) -> f64 {
+    if score.mods.relax {
+        return 0.0;
+    }
+
+    let mut value = base_performance(attributes.speed_difficulty);
+
+    let length_bonus = state.length_bonus();
+    value *= length_bonus;
+    value *= state.miss_penalty(0.875);
+    value *= combo_scaling(state.combo, attributes.max_combo);
+
+    let approach_rate = attributes.approach_rate;
+    if approach_rate > 10.33 {
+        value *= 1.0 + 0.3 * (approach_rate - 10.33) * length_bonus;
+    }
+
+    if score.mods.hidden {
+        value *= 1.0 + 0.04 * (12.0 - approach_rate);
+    }
+
+    // accuracy of the notes relevant to the speed, guessing that the other notes have the better judgements
+    let relevant_total_diff = state.total_hits() - attributes.speed_note_count;
+    let relevant_n300 = (state.n300 - relevant_total_diff).max(0.0);
+    let relevant_n100 = (state.n100 - (relevant_total_diff - state.n300).max(0.0)).max(0.0);
+    let relevant_n50 =
+        (state.n50 - (relevant_total_diff - state.n300 - state.n100).max(0.0)).max(0.0);
+    let relevant_accuracy = if attributes.speed_note_count == 0.0 {
+        0.0
+    } else {
+        (relevant_n300 * 6.0 + relevant_n100 * 2.0 + relevant_n50)
+            / (attributes.speed_note_count * 6.0)
+    };
+
+    let od = attributes.overall_difficulty;
+    value *= (0.95 + od.powi(2) / 750.0)
+        * ((state.accuracy + relevant_accuracy) / 2.0).powf((14.5 - od.max(8.0)) / 2.0);
+
+    // 50s are likely to be from double tapping
+    let total_hits = state.total_hits();
+    if state.n50 >= total_hits / 500.0 {
+        value *= 0.99f64.powf(state.n50 - total_hits / 500.0);
+    }
+
+    value
+}
+
+fn accuracy_value(state: &ScoreState, attributes: &OsuDifficultyAttributes, score: &Score) -> f64 {
+    if score.mods.relax {
+        return 0.0;
+    }
+
+    // only the hit circles are timed, so the other objects are counted as the 300s
+    let hit_circle_count = attributes.hit_circle_count as f64;
+    let better_accuracy = if hit_circle_count > 0.0 {
+        (((state.n300 - (state.total_hits() - hit_circle_count)) * 6.0
+            + state.n100 * 2.0
+            + state.n50)
+            / (hit_circle_count * 6.0))
+            .max(0.0)
+    } else {
+        0.0
+    };
+
+    let mut value =
+        1.52163f64.powf(attributes.overall_difficulty) * better_accuracy.powi(24) * 2.83;
+
+    // keeping the accuracy up is harder with more hit circles
+    value *= (hit_circle_count / 1000.0).powf(0.3).min(1.15);
+
+    if score.mods.hidden {
+        value *= 1.08;
+    }
+    if score.mods.flashlight {
+        value *= 1.02;
+    }
+
+    value
+}
+
+fn flashlight_value(
+    state: &ScoreState,
+    attributes: &OsuDifficultyAttributes,
+    score: &Score,
+) -> f64 {
+    if !score.mods.flashlight {
+        return 0.0;
+    }
+
+    let mut value = flashlight_performance(attributes.flashlight_difficulty);
+
+    value *= state.miss_penalty(0.875);
+    value *= combo_scaling(state.combo, attributes.max_combo);
+
+    // shorter beatmaps have more of the time with the larger flashlight at lower combos
+    let total_hits = state.total_hits();
+    value *= 0.7
+        + 0.1 * (total_hits / 200.0).min(1.0)
+        + if total_hits > 200.0 {
+            0.2 * ((total_hits - 200.0) / 200.0).min(1.0)
+        } else {
+            0.0
+        };
+
+    value *= 0.5 + state.accuracy / 2.0;
+    value *= 0.98 + attributes.overall_difficulty.powi(2) / 2500.0;
+
+    value
+}
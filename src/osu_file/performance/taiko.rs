@@ -0,0 +1,103 @@
+//! Performance points of osu!taiko.
+
+use super::{Score, ScoreHits};
+use crate::osu_file::{
+    difficulty_calc::{self, TaikoDifficultyAttributes},
+    OsuFile,
+};
+
+/// Performance points of an osu!taiko score.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TaikoPerformanceAttributes {
+    pub pp: f64,
+    /// Performance points from the star rating.
+    pub difficulty: f64,
+    pub accuracy: f64,
+    /// Difficulty of the beatmap with the mods of the score.
+    pub difficulty_attributes: TaikoDifficultyAttributes,
+}
+
+/// Computes the performance points of the `score` on the osu!taiko beatmap.
+pub(crate) fn calculate(osu_file: &OsuFile, score: &Score) -> TaikoPerformanceAttributes {
+    let attributes = difficulty_calc::taiko::calculate(osu_file, &score.mods);
+    let mods = &score.mods;
+
+    let total = attributes.max_combo;
+    let misses = score.misses.min(total);
+    let (n300, n100) = match score.hits {
+        ScoreHits::Accuracy(accuracy) => {
+            let remaining = total - misses;
+            let accuracy = (accuracy / 100.0).clamp(0.0, 1.0);
+            // accuracy lost compared to only greats, in halves of a great
+            let lost = 2.0 * remaining as f64 - 2.0 * accuracy * total as f64;
+            let n100 = (lost.max(0.0).round() as usize).min(remaining);
+
+            (remaining - n100, n100)
+        }
+        ScoreHits::Counts(counts) => (counts.n300, counts.n100),
+    };
+
+    let total_hits = (n300 + n100 + misses) as f64;
+    let accuracy = if total_hits > 0.0 {
+        (n300 as f64 * 300.0 + n100 as f64 * 150.0) / (total_hits * 300.0)
+    } else {
+        0.0
+    };
+
+    let mut multiplier = 1.13;
+    if mods.hidden {
+        multiplier *= 1.075;
+    }
+    if mods.easy {
+        multiplier *= 0.975;
+    }
+
+    let mut difficulty_value =
+        (5.0 * (attributes.star_rating / 0.115).max(1.0) - 4.0).powf(2.25) / 1150.0;
+
+    let length_bonus = 1.0 + 0.1 * (total_hits / 1500.0).min(1.0);
+    difficulty_value *= length_bonus;
+    difficulty_value *= 0.986f64.powi(misses as i32);
+
+    if mods.easy {
+        difficulty_value *= 0.985;
+    }
+    if mods.hidden {
+        difficulty_value *= 1.025;
+    }
+    if mods.hard_rock {
+        difficulty_value *= 1.05;
+    }
+    if mods.flashlight {
+        difficulty_value *= 1.05 * length_bonus;
+    }
+    difficulty_value *= accuracy.powi(2);
+
+    let accuracy_value = if attributes.great_hit_window <= 0.0 {
+        0.0
+    } else {
+        let length_bonus = (total_hits / 1500.0).powf(0.3).min(1.15);
+        let mut value = (60.0 / attributes.great_hit_window).powf(1.1)
+            * accuracy.powi(8)
+            * attributes.star_rating.powf(0.4)
+            * 27.0
+            * length_bonus;
+
+        // hidden with flashlight makes the notes harder to time
+        if mods.flashlight && mods.hidden {
+            value *= (1.075 * length_bonus).max(1.05);
+        }
+
+        value
+    };
+
+    let pp = (difficulty_value.powf(1.1) + accuracy_value.powf(1.1)).powf(1.0 / 1.1) * multiplier;
+
+    TaikoPerformanceAttributes {
+        pp,
+        difficulty: difficulty_value,
+        accuracy: accuracy_value,
+        difficulty_attributes: attributes,
+    }
+}
@@ -0,0 +1,132 @@
+//! Changing the speed of a beatmap, for rate edited difficulties.
+
+use std::path::PathBuf;
+
+use either::Either;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use thiserror::Error;
+
+use super::{Decimal, Integer, OsuFile};
+
+/// A name given to the rate edited beatmap with [`OsuFile::scale_time`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum RenamedField {
+    /// The difficulty name, `Version` in the `Metadata` section.
+    Version,
+    /// The audio file name, `AudioFilename` in the `General` section.
+    AudioFilename,
+}
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+/// Error for when an [`OsuFile`] can't be scaled to a rate.
+pub enum ScaleTimeError {
+    /// The rate isn't a positive finite number.
+    #[error("Invalid rate {0}, expected a positive number")]
+    InvalidRate(f64),
+}
+
+/// Time or length in milliseconds at the `rate`, rounded to whole milliseconds.
+pub(crate) fn scale_integer(value: Integer, rate: f64) -> Integer {
+    (value as f64 / rate).round() as Integer
+}
+
+/// Scales the time or length in milliseconds at the `rate`.
+/// - Rounded to whole milliseconds if `round` is set, and to the digits a `f64` can keep otherwise.
+/// - Values that aren't numbers are left as they are.
+pub(crate) fn scale_decimal(value: &mut Decimal, rate: f64, round: bool) {
+    if let Either::Left(value) = value.get_mut() {
+        let Some(scaled) = value.to_f64().map(|value| value / rate) else {
+            return;
+        };
+        let scaled = if round { scaled.round() } else { scaled };
+
+        if let Some(scaled) = rust_decimal::Decimal::from_f64(scaled) {
+            *value = scaled;
+        }
+    }
+}
+
+impl OsuFile {
+    /// Changes the speed of the beatmap to the `rate`, like a rate edited difficulty.
+    /// - `1.5` plays 1.5 times faster, so every time is divided by the rate.
+    /// - Hitobjects, timing points, events and storyboard commands, including the .osb storyboard, the preview time, audio lead-in and bookmarks are scaled.
+    /// - Beat lengths of uninherited timing points are scaled, while slider velocities aren't.
+    /// - `rename` gives the new difficulty name and audio file name from the current ones, and is only called for the ones that are set.
+    /// - The audio itself isn't changed, so it has to be rendered at the rate separately to the file name given.
+    pub fn scale_time<F>(&mut self, rate: f64, mut rename: F) -> Result<(), ScaleTimeError>
+    where
+        F: FnMut(RenamedField, &str) -> String,
+    {
+        if !rate.is_finite() || rate <= 0.0 {
+            return Err(ScaleTimeError::InvalidRate(rate));
+        }
+
+        if let Some(general) = &mut self.general {
+            if let Some(audio_filename) = &general.audio_filename {
+                let audio_filename: PathBuf = audio_filename.clone().into();
+                let renamed = rename(
+                    RenamedField::AudioFilename,
+                    &audio_filename.display().to_string(),
+                );
+                general.audio_filename = Some(PathBuf::from(renamed).into());
+            }
+            if let Some(audio_lead_in) = &general.audio_lead_in {
+                let audio_lead_in: Integer = audio_lead_in.clone().into();
+                general.audio_lead_in = Some(scale_integer(audio_lead_in, rate).into());
+            }
+            if let Some(preview_time) = &general.preview_time {
+                let preview_time: Integer = preview_time.clone().into();
+                // -1 is no preview time
+                if preview_time != -1 {
+                    general.preview_time = Some(scale_integer(preview_time, rate).into());
+                }
+            }
+            if let Some(editor_bookmarks) = &general.editor_bookmarks {
+                let editor_bookmarks: Vec<Integer> = editor_bookmarks.clone().into();
+                general.editor_bookmarks = Some(
+                    editor_bookmarks
+                        .into_iter()
+                        .map(|bookmark| scale_integer(bookmark, rate))
+                        .collect::<Vec<_>>()
+                        .into(),
+                );
+            }
+        }
+
+        if let Some(editor) = &mut self.editor {
+            if let Some(bookmarks) = &editor.bookmarks {
+                let bookmarks: Vec<Integer> = bookmarks.clone().into();
+                editor.bookmarks = Some(
+                    bookmarks
+                        .into_iter()
+                        .map(|bookmark| scale_integer(bookmark, rate))
+                        .collect::<Vec<_>>()
+                        .into(),
+                );
+            }
+        }
+
+        if let Some(metadata) = &mut self.metadata {
+            if let Some(version) = &metadata.version {
+                let version: String = version.clone().into();
+                metadata.version = Some(rename(RenamedField::Version, &version).into());
+            }
+        }
+
+        if let Some(osb) = &mut self.osb {
+            osb.scale_time(rate);
+        }
+        if let Some(events) = &mut self.events {
+            events.scale_time(rate);
+        }
+        if let Some(timing_points) = &mut self.timing_points {
+            timing_points.scale_time(rate);
+        }
+        if let Some(hitobjects) = &mut self.hitobjects {
+            hitobjects.scale_time(rate);
+        }
+
+        Ok(())
+    }
+}
@@ -1,9 +1,12 @@
 use std::num::ParseIntError;
+use std::ops::Range;
 
 use strum_macros::{EnumString, IntoStaticStr};
 use thiserror::Error;
 
 use crate::helper::macros::verbose_error_to_error;
+use crate::helper::{comma_field_columns, line_end_columns};
+use crate::osu_file::LineError;
 
 #[derive(Debug, Error)]
 #[error(transparent)]
@@ -59,6 +62,29 @@ pub enum ParseTimingPointError {
 
 verbose_error_to_error!(ParseTimingPointError);
 
+impl LineError for ParseTimingPointError {
+    fn columns(&self, line: &str) -> Option<Range<usize>> {
+        let field = match self {
+            ParseTimingPointError::InvalidTime => 0,
+            ParseTimingPointError::InvalidMeter => 2,
+            ParseTimingPointError::InvalidSampleSet => 3,
+            ParseTimingPointError::InvalidSampleIndex => 4,
+            ParseTimingPointError::InvalidVolume => 5,
+            ParseTimingPointError::InvalidUninherited => 6,
+            ParseTimingPointError::InvalidEffects => 7,
+            ParseTimingPointError::MissingBeatLength
+            | ParseTimingPointError::MissingMeter
+            | ParseTimingPointError::MissingSampleSet
+            | ParseTimingPointError::MissingSampleIndex
+            | ParseTimingPointError::MissingVolume
+            | ParseTimingPointError::MissingEffects
+            | ParseTimingPointError::MissingUninherited => return Some(line_end_columns(line)),
+        };
+
+        Some(comma_field_columns(line, field))
+    }
+}
+
 /// There was some problem parsing the [`SampleSet`][super::SampleSet].
 #[derive(Debug, Error, PartialEq, Eq)]
 #[non_exhaustive]
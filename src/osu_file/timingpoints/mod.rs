@@ -1,6 +1,10 @@
 pub mod error;
+pub mod snap;
+pub mod state;
 pub mod types;
 
+use std::fmt::{self, Write};
+
 use crate::osu_file::types::Decimal;
 use either::Either;
 use nom::{
@@ -12,61 +16,136 @@ use nom::{
 };
 use rust_decimal_macros::dec;
 
-use crate::{helper::parse_zero_one_bool, parsers::*};
+use crate::{
+    helper::{
+        item_lines, parse_zero_one_bool, write_new_line_separated, write_versioned_to_string,
+        ErrorCollector, ErrorsResult, VersionedWrite,
+    },
+    parsers::*,
+};
 
 use super::{
-    Error, Integer, Version, VersionedDefault, VersionedFrom, VersionedFromStr, VersionedToString,
+    offset::shift_decimal, rate::scale_decimal, Error, Integer, ParseOptions, Version,
+    VersionedDefault, VersionedFrom, VersionedFromStr, VersionedToString,
 };
 
 pub use error::*;
+pub use snap::{BeatSnap, BEAT_DIVISORS};
+pub use state::{TimingIndex, TimingLookup, TimingState};
 pub use types::*;
 
 #[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TimingPoints(pub Vec<TimingPoint>);
 
+/// Timing points of a beatmap without a `[TimingPoints]` section, for borrowing instead of cloning a default.
+pub(crate) static EMPTY_TIMING_POINTS: TimingPoints = TimingPoints(Vec::new());
+
 impl VersionedFromStr for TimingPoints {
     type Err = Error<ParseError>;
 
     fn from_str(s: &str, version: Version) -> std::result::Result<Option<Self>, Self::Err> {
-        let mut timing_points = Vec::new();
+        TimingPoints::from_str_errors(s, version, &ParseOptions::default())
+            .map(|(timing_points, _)| timing_points)
+    }
+}
 
-        for (line_index, s) in s.lines().enumerate() {
-            if s.trim().is_empty() {
-                continue;
-            }
+impl TimingPoints {
+    /// Parses the timing points, collecting the errors instead of returning the first one if [`lenient`][ParseOptions::lenient] is set.
+    /// - Lines with errors are skipped when collected.
+    /// - Comment lines are skipped if [`skips_comments`][ParseOptions::skips_comments].
+    pub(crate) fn from_str_errors(
+        s: &str,
+        version: Version,
+        options: &ParseOptions,
+    ) -> ErrorsResult<Self, ParseError> {
+        Self::from_lines(
+            item_lines(s, options.skips_comments()),
+            version,
+            options.lenient,
+        )
+    }
+
+    /// Parses the timing points from the lines of the section, with their index in the section.
+    pub(crate) fn from_lines<'a>(
+        lines: impl IntoIterator<Item = (usize, &'a str)>,
+        version: Version,
+        lenient: bool,
+    ) -> ErrorsResult<Self, ParseError> {
+        let mut timing_points = Vec::new();
+        let mut errors = ErrorCollector::new(lenient);
 
-            timing_points.push(Error::new_from_result_into(
+        for (line_index, s) in lines {
+            if let Some(timing_point) = errors.collect(Error::new_from_line_result(
                 TimingPoint::from_str(s, version),
+                s,
                 line_index,
-            )?);
+            ))? {
+                timing_points.push(timing_point);
+            }
         }
 
+        let errors = errors.into_errors();
+
         if let Some(s) = timing_points.first() {
             if s.is_some() {
-                Ok(Some(TimingPoints(
-                    timing_points
-                        .into_iter()
-                        .map(|v| v.unwrap())
-                        .collect::<Vec<_>>(),
-                )))
+                Ok((
+                    Some(TimingPoints(
+                        timing_points
+                            .into_iter()
+                            .map(|v| v.unwrap())
+                            .collect::<Vec<_>>(),
+                    )),
+                    errors,
+                ))
             } else {
-                Ok(None)
+                Ok((None, errors))
             }
         } else {
-            Ok(Some(TimingPoints(Vec::new())))
+            Ok((Some(TimingPoints(Vec::new())), errors))
+        }
+    }
+}
+
+impl TimingPoints {
+    /// Beat length in milliseconds of the uninherited timing point active at the `time`.
+    /// - Same as the beat length of [`state_at`][Self::state_at], which sorts the timing points on each call.
+    pub fn beat_length_at(&self, time: f64) -> Option<f64> {
+        self.state_at(time).map(|state| state.beat_length)
+    }
+
+    /// Slider velocity multiplier at the `time`.
+    /// - Same as the slider velocity of [`state_at`][Self::state_at], or `1` if there's no state.
+    /// - Use the [`index`][Self::index] instead for repeated lookups.
+    pub fn slider_velocity_at(&self, time: f64) -> f64 {
+        self.state_at(time)
+            .map_or(1.0, |state| state.slider_velocity)
+    }
+
+    /// Changes the speed of the timing points to the `rate`, like [`TimingPoint::scale_time`].
+    pub fn scale_time(&mut self, rate: f64) {
+        for timing_point in &mut self.0 {
+            timing_point.scale_time(rate);
+        }
+    }
+
+    /// Shifts the times of the timing points by `delta` milliseconds.
+    pub fn shift_time(&mut self, delta: Integer) {
+        for timing_point in &mut self.0 {
+            timing_point.shift_time(delta);
         }
     }
 }
 
 impl VersionedToString for TimingPoints {
     fn to_string(&self, version: Version) -> Option<String> {
-        Some(
-            self.0
-                .iter()
-                .filter_map(|t| t.to_string(version))
-                .collect::<Vec<_>>()
-                .join("\n"),
-        )
+        write_versioned_to_string(self, version)
+    }
+}
+
+impl VersionedWrite for TimingPoints {
+    fn write_versioned<W: Write>(&self, f: &mut W, version: Version) -> Option<fmt::Result> {
+        Some(write_new_line_separated(f, &self.0, version))
     }
 }
 
@@ -80,6 +159,7 @@ impl VersionedDefault for TimingPoints {
 /// Each timing point influences a specified portion of the map, commonly called a `timing section`.
 /// The .osu file format requires these to be sorted in chronological order.
 #[derive(Clone, Hash, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TimingPoint {
     // for some reason decimal is parsed anyway in the beatmap???
     time: Decimal,
@@ -258,6 +338,27 @@ impl TimingPoint {
     pub fn effects_mut(&mut self) -> &mut Option<Effects> {
         &mut self.effects
     }
+
+    /// Changes the speed of the timing point to the `rate`, dividing its time and the beat length of an uninherited timing point by the rate.
+    /// - Slider velocities of inherited timing points, which have negative beat lengths, aren't changed.
+    /// - The time is rounded to whole milliseconds like [`HitObject::scale_time`][crate::osu_file::hitobjects::HitObject::scale_time], so hitobjects on the timing point stay on it.
+    /// - The beat length isn't rounded, since osu! reads it as a decimal.
+    pub fn scale_time(&mut self, rate: f64) {
+        scale_decimal(&mut self.time, rate, true);
+
+        let positive_beat_length = matches!(
+            self.beat_length.get(),
+            Either::Left(beat_length) if beat_length.is_sign_positive()
+        );
+        if self.uninherited() && positive_beat_length {
+            scale_decimal(&mut self.beat_length, rate, false);
+        }
+    }
+
+    /// Shifts the time of the timing point by `delta` milliseconds.
+    pub fn shift_time(&mut self, delta: Integer) {
+        shift_decimal(&mut self.time, delta);
+    }
 }
 
 const OLD_VERSION_TIME_OFFSET: rust_decimal::Decimal = dec!(24);
@@ -445,38 +546,49 @@ impl VersionedFromStr for TimingPoint {
 
 impl VersionedToString for TimingPoint {
     fn to_string(&self, version: Version) -> Option<String> {
-        let mut fields = vec![
-            if (3..=4).contains(&version) {
-                match self.time.get() {
-                    Either::Left(value) => (value - OLD_VERSION_TIME_OFFSET).to_string(),
-                    Either::Right(value) => value.to_string(),
-                }
-            } else {
-                self.time.to_string()
-            },
-            self.beat_length.to_string(),
-        ];
+        write_versioned_to_string(self, version)
+    }
+}
+
+impl VersionedWrite for TimingPoint {
+    fn write_versioned<W: Write>(&self, f: &mut W, version: Version) -> Option<fmt::Result> {
+        Some(self.write_fields(f, version))
+    }
+}
+
+impl TimingPoint {
+    fn write_fields<W: Write>(&self, f: &mut W, version: Version) -> fmt::Result {
+        match self.time.get() {
+            Either::Left(value) if (3..=4).contains(&version) => {
+                write!(f, "{}", value - OLD_VERSION_TIME_OFFSET)?
+            }
+            _ => write!(f, "{}", self.time)?,
+        }
+        write!(f, ",{}", self.beat_length)?;
 
         if version > 3 {
-            fields.push(self.meter.to_string());
-            fields.push(self.sample_set.to_string(version).unwrap());
-            fields.push(self.sample_index.to_string(version).unwrap());
+            write!(f, ",{},", self.meter)?;
+            self.sample_set.write_versioned(f, version).unwrap()?;
+            f.write_char(',')?;
+            self.sample_index.write_versioned(f, version).unwrap()?;
         }
         if version > 4 {
-            fields.push(self.volume.to_string(version).unwrap());
+            f.write_char(',')?;
+            self.volume.write_versioned(f, version).unwrap()?;
             match self.uninherited {
-                Some(value) => fields.push((value as u8).to_string()),
+                Some(value) => write!(f, ",{}", value as u8)?,
                 None => {
                     if self.effects.is_some() {
-                        fields.push(String::new())
+                        f.write_char(',')?
                     }
                 }
             }
             if let Some(value) = self.effects {
-                fields.push(value.to_string(version).unwrap())
+                f.write_char(',')?;
+                value.write_versioned(f, version).unwrap()?;
             }
         }
 
-        Some(fields.join(","))
+        Ok(())
     }
 }
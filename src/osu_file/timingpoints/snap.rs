@@ -0,0 +1,69 @@
+//! Snapping times to the beat divisors of the timing state.
+
+use super::{TimingIndex, TimingState};
+
+/// Beat divisors that times are snapped to, in the order they're preferred when the error is the same.
+pub const BEAT_DIVISORS: [u32; 11] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 12, 16];
+
+/// Errors smaller than this are treated as the same when comparing divisors, like in the osu! editor.
+const ERROR_EPSILON: f64 = 1e-7;
+
+/// Closest beat divisor of a time, and how far the time is from it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BeatSnap {
+    /// Beat divisor, where `4` is 1/4 of a beat.
+    pub divisor: u32,
+    /// Time the time snaps to with the divisor, in milliseconds.
+    pub snapped_time: f64,
+    /// Time minus the snapped time in milliseconds, being positive if the time is late.
+    pub error: f64,
+}
+
+impl TimingState {
+    /// Time snapped to the closest 1/`divisor` of a beat from the uninherited timing point, like the osu! editor does.
+    /// - Beats are rounded to the nearest one, with halfway going away from the uninherited timing point.
+    /// - Negative times are snapped from `0`, and a snapped time before `0` is moved a beat later.
+    pub fn snapped_time(&self, time: f64, divisor: u32) -> f64 {
+        let beat_length = self.beat_length / divisor as f64;
+        let beats = (time.max(0.0) - self.uninherited_time) / beat_length;
+        let snapped_time = self.uninherited_time + beats.round() * beat_length;
+
+        if snapped_time >= 0.0 {
+            snapped_time
+        } else {
+            snapped_time + beat_length
+        }
+    }
+
+    /// Closest beat divisor of the `time` from [`BEAT_DIVISORS`].
+    /// - A divisor is only used over a smaller divisor if it's closer, so a time on a 1/2 beat is 1/2 instead of 1/4.
+    pub fn beat_snap(&self, time: f64) -> BeatSnap {
+        let mut closest: Option<BeatSnap> = None;
+
+        for divisor in BEAT_DIVISORS {
+            let snapped_time = self.snapped_time(time, divisor);
+            let error = time - snapped_time;
+
+            if closest.map_or(true, |closest| {
+                closest.error.abs() - ERROR_EPSILON > error.abs()
+            }) {
+                closest = Some(BeatSnap {
+                    divisor,
+                    snapped_time,
+                    error,
+                });
+            }
+        }
+
+        closest.unwrap()
+    }
+}
+
+impl TimingIndex {
+    /// Closest beat divisor of the `time`, using the timing state at that time.
+    /// - Same as [`TimingState::beat_snap`].
+    pub fn beat_snap(&self, time: f64) -> BeatSnap {
+        self.state_at(time).beat_snap(time)
+    }
+}
@@ -0,0 +1,236 @@
+//! Resolving the timing points into the timing state at a point in time.
+
+use super::{SampleIndex, SampleSet, TimingPoint, TimingPoints, Volume};
+use crate::osu_file::Integer;
+
+/// Timing state at a point in time, from the uninherited and inherited timing points active at that time.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimingState {
+    /// Beat length in milliseconds, from the uninherited timing point.
+    pub beat_length: f64,
+    /// Amount of beats in a measure, from the uninherited timing point.
+    pub meter: Integer,
+    /// Slider velocity multiplier, from the inherited timing point.
+    /// - `1` after an uninherited timing point without an inherited timing point at the same time.
+    pub slider_velocity: f64,
+    /// Default sample set for hitobjects.
+    pub sample_set: SampleSet,
+    /// Custom sample index for hitobjects.
+    pub sample_index: SampleIndex,
+    /// Volume percentage for hitobjects.
+    pub volume: Volume,
+    /// If kiai time is enabled.
+    pub kiai: bool,
+    /// Index of the uninherited timing point in the [`TimingPoints`].
+    pub uninherited_index: usize,
+    /// Time of the uninherited timing point in milliseconds.
+    pub uninherited_time: f64,
+}
+
+impl TimingState {
+    /// BPM from the beat length.
+    pub fn bpm(&self) -> f64 {
+        60000.0 / self.beat_length
+    }
+}
+
+/// Index of the timing states of [`TimingPoints`], for looking up the state at a time with a binary search.
+/// - Created with [`TimingPoints::index`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimingIndex {
+    /// State before the first timing point.
+    initial: TimingState,
+    /// Times of the timing points with the state from that time.
+    states: Vec<(f64, TimingState)>,
+}
+
+/// Something the [`TimingState`] at a time can be looked up from.
+/// - Implemented for [`TimingPoints`], which sorts the timing points on each lookup, and for [`TimingIndex`] which is faster for repeated lookups.
+/// - Pass a [`TimingIndex`] to the hitobject timing methods when calling them for many hitobjects, since a slider looks up the state more than once.
+pub trait TimingLookup {
+    /// Timing state at the `time`, or `None` if there's no uninherited timing point with a valid beat length.
+    fn timing_state_at(&self, time: f64) -> Option<TimingState>;
+}
+
+impl TimingLookup for TimingPoints {
+    fn timing_state_at(&self, time: f64) -> Option<TimingState> {
+        self.state_at(time)
+    }
+}
+
+impl TimingLookup for TimingIndex {
+    fn timing_state_at(&self, time: f64) -> Option<TimingState> {
+        Some(self.state_at(time))
+    }
+}
+
+impl TimingIndex {
+    /// Timing state at the `time`.
+    /// - Times before the first timing point use the first uninherited timing point, with a slider velocity multiplier of `1`.
+    pub fn state_at(&self, time: f64) -> TimingState {
+        let index = self
+            .states
+            .partition_point(|(state_time, _)| *state_time <= time);
+
+        match index {
+            0 => self.initial,
+            _ => self.states[index - 1].1,
+        }
+    }
+
+    /// Timing states with the time they start from, in the order of their time.
+    pub fn states(&self) -> &[(f64, TimingState)] {
+        &self.states
+    }
+}
+
+impl TimingPoints {
+    /// Creates an index of the timing states, for looking up the state at a time.
+    /// - Timing points at the same time are resolved like osu! does.
+    ///   The first uninherited timing point sets the beat length and meter, and the last inherited timing point sets the slider velocity, sample and kiai.
+    ///   The sample and kiai come from the first uninherited timing point if there's no inherited timing point at that time.
+    /// - Timing points with times that aren't numbers are skipped.
+    /// - Returns `None` if there's no uninherited timing point with a valid beat length.
+    pub fn index(&self) -> Option<TimingIndex> {
+        let points = self.sorted_points(f64::INFINITY);
+        let &first = points.iter().find(|(_, _, point)| is_timing(point))?;
+        let initial = initial_state(first);
+
+        Some(TimingIndex {
+            initial,
+            states: resolve_states(initial, &points).collect(),
+        })
+    }
+
+    /// Timing state at the `time`, resolved like the [`index`][Self::index] does.
+    /// - Sorts the timing points up to the `time` on each call, so create the index instead for repeated lookups.
+    /// - Returns `None` if there's no uninherited timing point with a valid beat length.
+    pub fn state_at(&self, time: f64) -> Option<TimingState> {
+        let first = self
+            .0
+            .iter()
+            .enumerate()
+            .filter(|(_, point)| is_timing(point))
+            .filter_map(|(i, point)| Some((point.time().to_f64()?, i, point)))
+            .min_by(|a, b| a.0.total_cmp(&b.0))?;
+        let initial = initial_state(first);
+
+        let points = self.sorted_points(time);
+        let state = resolve_states(initial, &points)
+            .last()
+            .map_or(initial, |(_, state)| state);
+
+        Some(state)
+    }
+
+    /// `(time, index, point)` of the timing points at or before `until`, sorted by their time.
+    /// - The sort is stable, so timing points at the same time keep their order.
+    /// - Timing points with times that aren't numbers are skipped.
+    fn sorted_points(&self, until: f64) -> Vec<(f64, usize, &TimingPoint)> {
+        let mut points = self
+            .0
+            .iter()
+            .enumerate()
+            .filter_map(|(i, point)| Some((point.time().to_f64()?, i, point)))
+            .filter(|(time, _, _)| *time <= until)
+            .collect::<Vec<_>>();
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        points
+    }
+}
+
+/// State before any timing point, from the first uninherited timing point with a valid beat length.
+fn initial_state((time, index, point): (f64, usize, &TimingPoint)) -> TimingState {
+    TimingState {
+        beat_length: point.beat_length.to_f64().unwrap(),
+        meter: point.meter(),
+        slider_velocity: 1.0,
+        sample_set: point.sample_set(),
+        sample_index: point.sample_index(),
+        volume: *point.volume(),
+        kiai: kiai(point),
+        uninherited_index: index,
+        uninherited_time: time,
+    }
+}
+
+/// Resolves the sorted timing points into the state from the time of each group of timing points at the same time.
+fn resolve_states<'a>(
+    initial: TimingState,
+    points: &'a [(f64, usize, &'a TimingPoint)],
+) -> impl Iterator<Item = (f64, TimingState)> + 'a {
+    let mut state = initial;
+    let mut group_start = 0;
+
+    std::iter::from_fn(move || {
+        let time = points.get(group_start)?.0;
+        let group_end = group_start
+            + points[group_start..]
+                .iter()
+                .take_while(|(point_time, _, _)| *point_time == time)
+                .count();
+        let group = &points[group_start..group_end];
+        group_start = group_end;
+
+        let timing = group.iter().find(|(_, _, point)| is_timing(point));
+        let inherited = group
+            .iter()
+            .rev()
+            .find(|(_, _, point)| !point.uninherited());
+
+        if let Some(&(_, index, point)) = timing {
+            state.beat_length = point.beat_length.to_f64().unwrap();
+            state.meter = point.meter();
+            state.uninherited_index = index;
+            state.uninherited_time = time;
+            state.slider_velocity = 1.0;
+        }
+
+        let sample_point = match inherited {
+            Some(&(_, _, point)) => {
+                state.slider_velocity = slider_velocity(point);
+                Some(point)
+            }
+            None => group
+                .iter()
+                .find(|(_, _, point)| point.uninherited())
+                .map(|(_, _, point)| *point),
+        };
+
+        if let Some(point) = sample_point {
+            state.sample_set = point.sample_set();
+            state.sample_index = point.sample_index();
+            state.volume = *point.volume();
+            state.kiai = kiai(point);
+        }
+
+        Some((time, state))
+    })
+}
+
+/// If the timing point sets the beat length, being uninherited with a valid beat length.
+fn is_timing(point: &TimingPoint) -> bool {
+    point.uninherited()
+        && point
+            .beat_length
+            .to_f64()
+            .is_some_and(|beat_length| beat_length > 0.0)
+}
+
+fn kiai(point: &TimingPoint) -> bool {
+    point
+        .effects()
+        .is_some_and(|effects| effects.kiai_time_enabled())
+}
+
+/// Slider velocity multiplier of an inherited timing point.
+/// - Clamped to `0.1` ~ `10` like in osu!, and `1` for invalid beat lengths.
+fn slider_velocity(point: &TimingPoint) -> f64 {
+    point
+        .beat_length
+        .to_f64()
+        .filter(|beat_length| *beat_length < 0.0)
+        .map_or(1.0, |beat_length| (-100.0 / beat_length).clamp(0.1, 10.0))
+}
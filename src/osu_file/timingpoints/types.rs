@@ -5,6 +5,7 @@ use crate::osu_file::VersionedFromStr;
 
 /// Default sample set for hitobjects.
 #[derive(Clone, Copy, Hash, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum SampleSet {
     /// Beatmap's default.
@@ -60,11 +61,17 @@ impl VersionedFromStr for SampleSet {
 
 impl VersionedToString for SampleSet {
     fn to_string(&self, version: Version) -> Option<String> {
-        Some(
-            <Integer as VersionedFrom<SampleSet>>::from(*self, version)
-                .unwrap()
-                .to_string(),
-        )
+        write_versioned_to_string(self, version)
+    }
+}
+
+impl VersionedWrite for SampleSet {
+    fn write_versioned<W: Write>(&self, f: &mut W, version: Version) -> Option<fmt::Result> {
+        Some(write!(
+            f,
+            "{}",
+            <Integer as VersionedFrom<SampleSet>>::from(*self, version)?
+        ))
     }
 }
 
@@ -73,8 +80,41 @@ impl VersionedToString for SampleSet {
 /// - The unused bits will come in effect when to_string is called.
 /// - You can clear the unused bits by calling [`Effects::clear_unused_bits`].
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "EffectsFlags", into = "EffectsFlags"))]
 pub struct Effects(u32);
 
+/// `Effects` with the flags as named fields, used for serializing.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct EffectsFlags {
+    kiai_time_enabled: bool,
+    no_first_barline_in_taiko_mania: bool,
+    /// Bits that aren't used by the flags, kept to write the value back as it was.
+    #[serde(default)]
+    unused_bits: u32,
+}
+
+#[cfg(feature = "serde")]
+impl From<EffectsFlags> for Effects {
+    fn from(flags: EffectsFlags) -> Self {
+        let effects = Effects::new(flags.kiai_time_enabled, flags.no_first_barline_in_taiko_mania);
+
+        Self(effects.0 | flags.unused_bits & !0b1001)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Effects> for EffectsFlags {
+    fn from(effects: Effects) -> Self {
+        Self {
+            kiai_time_enabled: effects.kiai_time_enabled(),
+            no_first_barline_in_taiko_mania: effects.no_first_barline_in_taiko_mania(),
+            unused_bits: effects.0 & !0b1001,
+        }
+    }
+}
+
 impl VersionedFromStr for Effects {
     type Err = ParseEffectsError;
 
@@ -97,7 +137,13 @@ impl VersionedFrom<Effects> for u32 {
 
 impl VersionedToString for Effects {
     fn to_string(&self, version: Version) -> Option<String> {
-        <u32 as VersionedFrom<Effects>>::from(*self, version).map(|effects| effects.to_string())
+        write_versioned_to_string(self, version)
+    }
+}
+
+impl VersionedWrite for Effects {
+    fn write_versioned<W: Write>(&self, f: &mut W, version: Version) -> Option<fmt::Result> {
+        <u32 as VersionedFrom<Effects>>::from(*self, version).map(|effects| write!(f, "{effects}"))
     }
 }
 
@@ -146,6 +192,7 @@ impl Effects {
 
 /// Custom sample index for hitobjects.
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum SampleIndex {
     /// Osu!'s default hitsounds.
@@ -190,7 +237,13 @@ impl VersionedFrom<SampleIndex> for Integer {
 
 impl VersionedToString for SampleIndex {
     fn to_string(&self, version: Version) -> Option<String> {
-        <i32 as VersionedFrom<SampleIndex>>::from(*self, version).map(|index| index.to_string())
+        write_versioned_to_string(self, version)
+    }
+}
+
+impl VersionedWrite for SampleIndex {
+    fn write_versioned<W: Write>(&self, f: &mut W, version: Version) -> Option<fmt::Result> {
+        <i32 as VersionedFrom<SampleIndex>>::from(*self, version).map(|index| write!(f, "{index}"))
     }
 }
 
@@ -202,6 +255,7 @@ impl VersionedDefault for SampleIndex {
 
 /// The volume percentage in the range of 0 ~ 100.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Volume(Integer);
 
 impl VersionedFromStr for Volume {
@@ -225,8 +279,14 @@ impl VersionedFrom<Volume> for Integer {
 }
 
 impl VersionedToString for Volume {
-    fn to_string(&self, _: Version) -> Option<String> {
-        Some(self.0.to_string())
+    fn to_string(&self, version: Version) -> Option<String> {
+        write_versioned_to_string(self, version)
+    }
+}
+
+impl VersionedWrite for Volume {
+    fn write_versioned<W: Write>(&self, f: &mut W, _: Version) -> Option<fmt::Result> {
+        Some(write!(f, "{}", self.0))
     }
 }
 
@@ -0,0 +1,486 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Write};
+
+use crate::helper::is_comment_line;
+
+use super::{OsuFile, Version, SECTION_NAMES};
+
+/// Sections with `key: value` pairs, which are matched by the key.
+const KEYED_SECTIONS: [&str; 4] = ["General", "Editor", "Metadata", "Difficulty"];
+
+/// Max size of the table used for matching lines that changed in a section.
+/// - Bigger changes are written without looking for unchanged lines in between.
+const MAX_MATCH_TABLE_SIZE: usize = 1 << 20;
+
+/// Formatting of the input the `OsuFile` was parsed from.
+/// - Kept when parsing with [`ParseOptions::lossless`][super::ParseOptions::lossless].
+/// - Used when writing with the same version as the input, so the parts of the file that didn't change are written as they were.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Trivia {
+    version: Version,
+    newline: String,
+    /// Text before the first section.
+    header: String,
+    sections: Vec<SectionTrivia>,
+    ends_with_newline: bool,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct SectionTrivia {
+    name: String,
+    /// The section name line, with the blank lines after it.
+    header: String,
+    lines: Vec<Line>,
+    kind: SectionKind,
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum SectionKind {
+    /// Lines are paired with the output of the section.
+    Paired,
+    /// Lines couldn't be paired with the output of the section, so it's written as normal.
+    Unpaired,
+    /// Section that isn't a part of the parsed `OsuFile`, which is written as it was.
+    Skipped,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Line {
+    /// The line with its line ending.
+    raw: String,
+    /// Output of what the line was parsed into.
+    /// - `None` for blank lines, comments and skipped lines.
+    item: Option<String>,
+}
+
+/// Where a section is in the input.
+pub(crate) struct SectionSpan<'a> {
+    pub name: &'a str,
+    /// Line index of the section name.
+    pub name_line: usize,
+    /// Line index of the section contents.
+    pub body_line: usize,
+    /// If the section was skipped from an error, or from not being one of the sections to parse.
+    pub skipped: bool,
+}
+
+impl Trivia {
+    /// Creates the trivia from the input, using the output of the parsed `osu_file` to pair lines with what they were parsed into.
+    /// - `skipped_lines` are the line indices of lines that were skipped from errors.
+    pub(crate) fn new(
+        input: &str,
+        osu_file: &OsuFile,
+        spans: &[SectionSpan],
+        skipped_lines: &[usize],
+    ) -> Self {
+        let lines = input.split_inclusive('\n').collect::<Vec<_>>();
+        let newline = if input.contains("\r\n") { "\r\n" } else { "\n" };
+
+        let first_section_line = spans.first().map_or(lines.len(), |span| span.name_line);
+        let header = lines[..first_section_line].concat();
+
+        let sections = spans
+            .iter()
+            .enumerate()
+            .map(|(i, span)| {
+                let end = spans.get(i + 1).map_or(lines.len(), |next| next.name_line);
+                let body_line = span.body_line.max(span.name_line + 1).min(end);
+
+                let content = if span.skipped {
+                    None
+                } else {
+                    osu_file.section_to_string(span.name, osu_file.version)
+                };
+
+                let mut section_lines = lines[body_line..end]
+                    .iter()
+                    .enumerate()
+                    .map(|(j, raw)| {
+                        let line = trim_line_ending(raw);
+                        let is_item = !line.trim().is_empty()
+                            && !skipped_lines.contains(&(body_line + j))
+                            && (span.name == "Events"
+                                || !SECTION_NAMES.contains(&span.name)
+                                || !is_comment_line(line));
+
+                        Line {
+                            raw: raw.to_string(),
+                            item: is_item.then(|| line.to_string()),
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                let kind = match content {
+                    Some(content) => {
+                        if pair_lines(span.name, &mut section_lines, &content) {
+                            SectionKind::Paired
+                        } else {
+                            SectionKind::Unpaired
+                        }
+                    }
+                    None => SectionKind::Skipped,
+                };
+
+                SectionTrivia {
+                    name: span.name.to_string(),
+                    header: lines[span.name_line..body_line].concat(),
+                    lines: section_lines,
+                    kind,
+                }
+            })
+            .collect();
+
+        Self {
+            version: osu_file.version,
+            newline: newline.to_string(),
+            header,
+            sections,
+            ends_with_newline: input.ends_with('\n'),
+        }
+    }
+
+    /// Version of the input.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Writes the `osu_file`, using the trivia for the parts that didn't change.
+    pub(crate) fn write<W: Write>(&self, f: &mut W, osu_file: &OsuFile) -> fmt::Result {
+        let mut writer = LineWriter {
+            f,
+            newline: &self.newline,
+            open_line: false,
+        };
+        let mut written_sections = Vec::new();
+
+        writer.raw(&self.header)?;
+
+        for section in &self.sections {
+            if section.kind == SectionKind::Skipped {
+                writer.raw(&section.header)?;
+                for line in &section.lines {
+                    writer.raw(&line.raw)?;
+                }
+
+                continue;
+            }
+
+            if written_sections.contains(&section.name.as_str()) {
+                continue;
+            }
+
+            // the section could have been removed
+            let content = match osu_file.section_to_string(&section.name, self.version) {
+                Some(content) => content,
+                None => continue,
+            };
+            let content = content.lines().collect::<Vec<_>>();
+
+            written_sections.push(section.name.as_str());
+            writer.raw(&section.header)?;
+
+            match section.kind {
+                SectionKind::Paired if KEYED_SECTIONS.contains(&section.name.as_str()) => {
+                    write_keyed_lines(&mut writer, &section.lines, &content)?
+                }
+                SectionKind::Paired => write_list_lines(&mut writer, &section.lines, &content)?,
+                _ => {
+                    // keep the blank lines at the end
+                    let trailing_start = section
+                        .lines
+                        .iter()
+                        .rposition(|line| !line.raw.trim().is_empty())
+                        .map_or(0, |i| i + 1);
+
+                    for line in content {
+                        writer.line(line)?;
+                    }
+                    for line in &section.lines[trailing_start..] {
+                        writer.raw(&line.raw)?;
+                    }
+                }
+            }
+        }
+
+        for name in osu_file.section_names() {
+            if written_sections.contains(&name) {
+                continue;
+            }
+
+            if let Some(content) = osu_file.section_to_string(name, self.version) {
+                writer.line("")?;
+                writer.line(&format!("[{name}]"))?;
+                for line in content.lines() {
+                    writer.line(line)?;
+                }
+            }
+        }
+
+        if writer.open_line && self.ends_with_newline {
+            writer.f.write_str(writer.newline)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes lines, keeping track of whether the last line ended with a line ending.
+struct LineWriter<'a, W> {
+    f: &'a mut W,
+    newline: &'a str,
+    /// The last line written didn't end with a line ending.
+    open_line: bool,
+}
+
+impl<W: Write> LineWriter<'_, W> {
+    /// Writes raw text, which is a line with its line ending.
+    fn raw(&mut self, s: &str) -> fmt::Result {
+        if s.is_empty() {
+            return Ok(());
+        }
+
+        if self.open_line {
+            self.f.write_str(self.newline)?;
+        }
+        self.open_line = !s.ends_with('\n');
+
+        self.f.write_str(s)
+    }
+
+    /// Writes a line without the line ending.
+    fn line(&mut self, s: &str) -> fmt::Result {
+        if self.open_line {
+            self.f.write_str(self.newline)?;
+        }
+        self.open_line = true;
+
+        self.f.write_str(s)
+    }
+}
+
+fn trim_line_ending(s: &str) -> &str {
+    s.trim_end_matches('\n').trim_end_matches('\r')
+}
+
+fn key(line: &str) -> &str {
+    line.trim_start()
+        .split_once(':')
+        .map_or(line, |(key, _)| key)
+}
+
+/// Pairs the lines with the output of the section, by replacing the line items with the output.
+/// - Returns `false` if the lines can't be paired.
+fn pair_lines(name: &str, lines: &mut [Line], content: &str) -> bool {
+    let content = content.lines().collect::<Vec<_>>();
+    let item_count = lines.iter().filter(|line| line.item.is_some()).count();
+
+    if item_count != content.len() {
+        return false;
+    }
+
+    if KEYED_SECTIONS.contains(&name) {
+        let content = content
+            .iter()
+            .map(|line| (key(line), *line))
+            .collect::<HashMap<_, _>>();
+
+        for line in lines.iter_mut() {
+            if let Some(item) = &mut line.item {
+                match content.get(key(item)) {
+                    Some(content_line) => *item = content_line.to_string(),
+                    None => return false,
+                }
+            }
+        }
+
+        // keys has to be unique
+        let keys = lines
+            .iter()
+            .filter_map(|line| line.item.as_deref().map(key))
+            .collect::<HashSet<_>>();
+
+        keys.len() == item_count
+    } else {
+        let items = lines.iter_mut().filter_map(|line| line.item.as_mut());
+
+        for (item, content_line) in items.zip(content) {
+            *item = content_line.to_string();
+        }
+
+        true
+    }
+}
+
+/// Writes the `key: value` lines, in the order of the input.
+/// - Keys that are new are written after the last key in the input.
+fn write_keyed_lines<W: Write>(
+    writer: &mut LineWriter<W>,
+    lines: &[Line],
+    content: &[&str],
+) -> fmt::Result {
+    let content_by_key = content
+        .iter()
+        .map(|line| (key(line), *line))
+        .collect::<HashMap<_, _>>();
+    let mut written_keys = HashSet::new();
+
+    let last_item = lines.iter().rposition(|line| line.item.is_some());
+    let write_new_keys =
+        |writer: &mut LineWriter<W>, written_keys: &HashSet<&str>| -> fmt::Result {
+            for line in content {
+                if !written_keys.contains(key(line)) {
+                    writer.line(line)?;
+                }
+            }
+
+            Ok(())
+        };
+
+    if last_item.is_none() {
+        write_new_keys(writer, &written_keys)?;
+    }
+
+    for (i, line) in lines.iter().enumerate() {
+        match &line.item {
+            Some(item) => {
+                let key = key(item);
+
+                if let Some(content_line) = content_by_key.get(key) {
+                    if content_line == item {
+                        writer.raw(&line.raw)?;
+                    } else {
+                        writer.line(content_line)?;
+                    }
+
+                    written_keys.insert(key);
+                }
+            }
+            None => writer.raw(&line.raw)?,
+        }
+
+        if Some(i) == last_item {
+            write_new_keys(writer, &written_keys)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the list lines, matching the unchanged lines with the input.
+/// - New lines are written after the line before it in the output.
+fn write_list_lines<W: Write>(
+    writer: &mut LineWriter<W>,
+    lines: &[Line],
+    content: &[&str],
+) -> fmt::Result {
+    let items = lines
+        .iter()
+        .filter_map(|line| line.item.as_deref())
+        .collect::<Vec<_>>();
+    let matches = match_lines(&items, content);
+
+    let last_item = lines.iter().rposition(|line| line.item.is_some());
+    let mut content_index = 0;
+    let mut item_index = 0;
+
+    if last_item.is_none() {
+        for line in content {
+            writer.line(line)?;
+        }
+        content_index = content.len();
+    }
+
+    for (i, line) in lines.iter().enumerate() {
+        match &line.item {
+            Some(_) => {
+                if let Some(matched) = matches[item_index] {
+                    for line in &content[content_index..matched] {
+                        writer.line(line)?;
+                    }
+
+                    writer.raw(&line.raw)?;
+                    content_index = matched + 1;
+                }
+
+                item_index += 1;
+            }
+            None => writer.raw(&line.raw)?,
+        }
+
+        if Some(i) == last_item {
+            for line in &content[content_index..] {
+                writer.line(line)?;
+            }
+            content_index = content.len();
+        }
+    }
+
+    Ok(())
+}
+
+/// Matches the unchanged lines from `old` in `new`, returning the index in `new` for each line in `old`.
+fn match_lines(old: &[&str], new: &[&str]) -> Vec<Option<usize>> {
+    let mut matches = vec![None; old.len()];
+
+    let prefix = old
+        .iter()
+        .zip(new)
+        .take_while(|(old, new)| old == new)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(old, new)| old == new)
+        .count();
+
+    for (i, matched) in matches.iter_mut().enumerate().take(prefix) {
+        *matched = Some(i);
+    }
+    for i in 0..suffix {
+        matches[old.len() - 1 - i] = Some(new.len() - 1 - i);
+    }
+
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    if old_middle.is_empty()
+        || new_middle.is_empty()
+        || (old_middle.len() + 1) * (new_middle.len() + 1) > MAX_MATCH_TABLE_SIZE
+    {
+        return matches;
+    }
+
+    // longest common subsequence of the lines in between
+    let width = new_middle.len() + 1;
+    let mut table = vec![0u32; (old_middle.len() + 1) * width];
+
+    for i in (0..old_middle.len()).rev() {
+        for j in (0..new_middle.len()).rev() {
+            table[i * width + j] = if old_middle[i] == new_middle[j] {
+                table[(i + 1) * width + j + 1] + 1
+            } else {
+                table[(i + 1) * width + j].max(table[i * width + j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < old_middle.len() && j < new_middle.len() {
+        if old_middle[i] == new_middle[j] {
+            matches[prefix + i] = Some(prefix + j);
+            i += 1;
+            j += 1;
+        } else if table[(i + 1) * width + j] >= table[i * width + j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    matches
+}
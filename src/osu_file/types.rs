@@ -1,5 +1,6 @@
 use std::{
     fmt::{Debug, Display},
+    ops::Range,
     path::{Path, PathBuf},
     str::FromStr,
 };
@@ -17,6 +18,7 @@ pub const MIN_VERSION: Version = 3;
 pub type Version = u8;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// The position of something in `osu!pixels` with the `x` `y` form.
 pub struct Position {
     /// x coordinate.
@@ -39,10 +41,40 @@ impl Default for Position {
 pub struct Error<E> {
     /// Line index of the error.
     line_index: usize,
+    /// Byte range of the error in the line, if it's known.
+    columns: Option<Range<usize>>,
     /// The error.
     error: E,
 }
 
+/// Location of an error in the input.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Span {
+    /// Line index of the error.
+    pub line_index: usize,
+    /// Byte range of the error in the line.
+    pub columns: Range<usize>,
+}
+
+impl Span {
+    /// Byte range of the error in the input.
+    /// - Returns `None` if the line doesn't exist in `file_input`.
+    pub fn byte_range(&self, file_input: &str) -> Option<Range<usize>> {
+        let mut line_start = 0;
+
+        for (i, line) in file_input.split_inclusive('\n').enumerate() {
+            if i == self.line_index {
+                return Some(line_start + self.columns.start..line_start + self.columns.end);
+            }
+
+            line_start += line.len();
+        }
+
+        None
+    }
+}
+
 impl<E: std::error::Error + 'static> std::error::Error for Error<E> {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         self.error.source()
@@ -62,10 +94,55 @@ impl<E> Error<E> {
         format!("Line {}: {}, {}", self.line_index + 1, line, self.error)
     }
 
+    /// Returns an error message with the affected line, and the part of the line with the error underlined.
+    /// - The whole line is underlined if the span of the error isn't known.
+    ///
+    /// ```text
+    /// error: Invalid `y` value
+    ///  --> 6:5
+    ///   |
+    /// 6 | 256,foo,0,1,0
+    ///   |     ^^^
+    /// ```
+    pub fn display_error_with_span(&self, file_input: &str) -> String
+    where
+        E: std::fmt::Display,
+    {
+        let line = file_input.lines().nth(self.line_index).unwrap_or_default();
+        let line = line.strip_prefix('\u{feff}').unwrap_or(line);
+
+        let columns = match &self.columns {
+            Some(columns) => columns.start.min(line.len())..columns.end.min(line.len()),
+            None => {
+                let start = line.len() - line.trim_start().len();
+                start..line.trim_end().len().max(start)
+            }
+        };
+        // columns could be in the middle of a character
+        let prefix = line.get(..columns.start).unwrap_or(line);
+        let underlined = line.get(columns.clone()).unwrap_or_default();
+
+        let line_number = (self.line_index + 1).to_string();
+        let margin = " ".repeat(line_number.len());
+        let padding = " ".repeat(prefix.chars().count());
+        let carets = "^".repeat(underlined.chars().count().max(1));
+
+        format!(
+            "error: {}\n{margin}--> {}:{}\n{margin} |\n{line_number} | {line}\n{margin} | {padding}{carets}",
+            self.error,
+            line_number,
+            prefix.chars().count() + 1,
+        )
+    }
+
     /// Creates a new `Error` instance with the given line index and error.
     /// - If you have a higher error that `E` needs to convert from, use `new_into` instead.
     pub fn new(error: E, line_index: usize) -> Self {
-        Self { line_index, error }
+        Self {
+            line_index,
+            columns: None,
+            error,
+        }
     }
 
     /// Creates a new `Error` instance with the given line index and error.
@@ -76,6 +153,7 @@ impl<E> Error<E> {
     {
         Error {
             line_index,
+            columns: None,
             error: error.into(),
         }
     }
@@ -83,10 +161,7 @@ impl<E> Error<E> {
     /// Creates a new `Error` instance with the given line index and error.
     /// - For use when you have some Result<T, E> and want to convert it to a `Error`.
     pub fn new_from_result<T>(result: Result<T, E>, line_index: usize) -> Result<T, Error<E>> {
-        result.map_err(|err| Error {
-            line_index,
-            error: err,
-        })
+        result.map_err(|err| Error::new(err, line_index))
     }
 
     /// Creates a new `Error` instance with the given line index and error.
@@ -98,12 +173,31 @@ impl<E> Error<E> {
     where
         E2: From<E>,
     {
-        result.map_err(|err| Error {
-            line_index,
-            error: err.into(),
+        result.map_err(|err| Error::new_into(err, line_index))
+    }
+
+    /// Same as `new_from_result_into`, with the span of the error found from the line that was parsed.
+    pub(crate) fn new_from_line_result<T, E2>(
+        result: Result<T, E>,
+        line: &str,
+        line_index: usize,
+    ) -> Result<T, Error<E2>>
+    where
+        E: LineError,
+        E2: From<E>,
+    {
+        result.map_err(|err| {
+            let columns = err.columns(line);
+            Error::new_into(err, line_index).with_columns(columns)
         })
     }
 
+    /// Sets the byte range of the error in the line.
+    pub(crate) fn with_columns(mut self, columns: Option<Range<usize>>) -> Self {
+        self.columns = columns;
+        self
+    }
+
     /// Uses `Into` to convert the inner error into `E2`.
     pub fn error_into<E2>(self) -> Error<E2>
     where
@@ -111,6 +205,7 @@ impl<E> Error<E> {
     {
         Error {
             line_index: self.line_index,
+            columns: self.columns,
             error: self.error.into(),
         }
     }
@@ -120,10 +215,7 @@ impl<E> Error<E> {
     where
         E2: From<E>,
     {
-        result.map_err(|err| Error {
-            line_index: err.line_index,
-            error: err.error.into(),
-        })
+        result.map_err(|err| err.error_into())
     }
 
     /// Increases `Error`'s processing line using the `Result<_, Error<E>>` type.
@@ -136,10 +228,20 @@ impl<E> Error<E> {
     where
         E2: From<E>,
     {
-        result.map_err(|err| Error {
-            line_index: err.line_index + line_index,
-            error: err.error.into(),
-        })
+        result.map_err(|err| err.offset_line(line_index))
+    }
+
+    /// Increases the line index of the error by `line_index`.
+    /// - This will also convert the inner error into `E2`.
+    pub(crate) fn offset_line<E2>(self, line_index: usize) -> Error<E2>
+    where
+        E2: From<E>,
+    {
+        Error {
+            line_index: self.line_index + line_index,
+            columns: self.columns,
+            error: self.error.into(),
+        }
     }
 
     /// Get the error's line index.
@@ -147,12 +249,28 @@ impl<E> Error<E> {
         self.line_index
     }
 
+    /// Get the location of the error in the input.
+    /// - `None` if only the line of the error is known.
+    pub fn span(&self) -> Option<Span> {
+        self.columns.clone().map(|columns| Span {
+            line_index: self.line_index,
+            columns,
+        })
+    }
+
     /// Get a reference to the error's error.
     pub fn error(&self) -> &E {
         &self.error
     }
 }
 
+/// Error from parsing a single line, which knows the part of the line it's from.
+pub(crate) trait LineError {
+    /// Byte range of the part of `line` the error is from.
+    /// - `None` if the error is for the whole line.
+    fn columns(&self, line: &str) -> Option<Range<usize>>;
+}
+
 impl<E> Display for Error<E>
 where
     E: std::fmt::Display,
@@ -165,13 +283,41 @@ where
 impl<E> From<E> for Error<E> {
     /// Error from parsing a single line can be converted to a `ParseError` directly.
     fn from(error: E) -> Self {
-        Self {
-            line_index: 0,
-            error,
-        }
+        Self::new(error, 0)
     }
 }
 
+#[derive(Debug, Error)]
+#[non_exhaustive]
+/// Error used when the bytes of a file couldn't be decoded into text.
+pub enum DecodeError {
+    /// The file is neither UTF-16 nor valid UTF-8.
+    #[error(transparent)]
+    InvalidUtf8(#[from] std::string::FromUtf8Error),
+    /// The file is UTF-16 but contains invalid data.
+    #[error(transparent)]
+    InvalidUtf16(#[from] std::string::FromUtf16Error),
+    /// The file is UTF-16 but has an odd number of bytes.
+    #[error("UTF-16 encoded file has an odd number of bytes")]
+    OddUtf16Length,
+}
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+/// Error used when reading a file from a path or a reader.
+/// - Wraps the parse error of the file with the I/O and decoding errors.
+pub enum ReadError<E> {
+    /// There was a problem reading from the source.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// There was a problem decoding the bytes into text.
+    #[error(transparent)]
+    Decode(#[from] DecodeError),
+    /// There was a problem parsing the file.
+    #[error(transparent)]
+    Parse(#[from] Error<E>),
+}
+
 /// Contains `to_string` that provides version specific output.
 pub trait VersionedToString {
     /// Returns a string representation of the object.
@@ -199,6 +345,32 @@ pub trait VersionedDefault: Sized {
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A `key: value` pair with a key that isn't known by the crate.
+/// - Written back after the field it was after in the input.
+pub struct UnknownField {
+    pub key: String,
+    pub value: String,
+    /// Key of the field before this one in the input.
+    /// - `None` if this was the first field.
+    pub after: Option<String>,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A section with a name that isn't known by the crate.
+/// - Written back after the section it was after in the input.
+pub struct UnknownSection {
+    pub name: String,
+    /// Lines in the section, without the blank lines.
+    pub lines: Vec<String>,
+    /// Name of the section before this one in the input.
+    /// - `None` if this was the first section.
+    pub after: Option<String>,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// File path type that is used in most of the crate.
 pub struct FilePath(PathBuf);
 
@@ -304,6 +476,17 @@ impl Decimal {
     pub fn get_mut(&mut self) -> &mut Either<rust_decimal::Decimal, String> {
         &mut self.0
     }
+
+    /// Converts the value to a `f64`, parsing the `Right` value.
+    /// - Returns `None` if the value isn't a finite number.
+    pub fn to_f64(&self) -> Option<f64> {
+        let value = match &self.0 {
+            Either::Left(value) => rust_decimal::prelude::ToPrimitive::to_f64(value)?,
+            Either::Right(value) => value.trim().parse().ok()?,
+        };
+
+        value.is_finite().then_some(value)
+    }
 }
 
 impl FromStr for Decimal {
@@ -320,6 +503,53 @@ impl Display for Decimal {
     }
 }
 
+/// Serialized as a string, which keeps the value exactly as it was written for both `Left` and `Right`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Decimal {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Deserialized from a string the same way as `FromStr`, or from a number.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Decimal {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct DecimalVisitor;
+
+        impl serde::de::Visitor<'_> for DecimalVisitor {
+            type Value = Decimal;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a decimal as a string or a number")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(Decimal::from(v))
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Self::Value, E> {
+                Ok(rust_decimal::Decimal::from(v).into())
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                Ok(rust_decimal::Decimal::from(v).into())
+            }
+
+            fn visit_f64<E: serde::de::Error>(self, v: f64) -> Result<Self::Value, E> {
+                Ok(Decimal::from(v.to_string().as_str()))
+            }
+        }
+
+        // `Serialize` writes the value as a string, and formats that don't describe their types can only read it back as one
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(DecimalVisitor)
+        } else {
+            deserializer.deserialize_str(DecimalVisitor)
+        }
+    }
+}
+
 impl From<rust_decimal::Decimal> for Decimal {
     fn from(value: rust_decimal::Decimal) -> Self {
         Self(Either::Left(value))
@@ -0,0 +1,94 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::{
+    hitobjects::{HitObjectSnap, HitObjects, SnapPoint},
+    timingpoints::{BeatSnap, TimingPoints},
+    Difficulty, VersionedFromStr,
+};
+use crate::tests::fixtures::timing_points_offset;
+
+#[test]
+fn beat_snap() {
+    let index = timing_points_offset().index().unwrap();
+
+    let snap = |divisor, snapped_time, error| BeatSnap {
+        divisor,
+        snapped_time,
+        error,
+    };
+
+    assert_eq!(index.beat_snap(700.0), snap(1, 700.0, 0.0));
+    // 1/2 is used over 1/4 and 1/16
+    assert_eq!(index.beat_snap(400.0), snap(2, 400.0, 0.0));
+    assert_eq!(index.beat_snap(300.0), snap(3, 300.0, 0.0));
+    assert_eq!(index.beat_snap(250.0), snap(4, 250.0, 0.0));
+    assert_eq!(index.beat_snap(220.0), snap(5, 220.0, 0.0));
+    assert_eq!(index.beat_snap(150.0), snap(12, 150.0, 0.0));
+    assert_eq!(index.beat_snap(137.5), snap(16, 137.5, 0.0));
+    // 2ms late from 1/4, which is closer than any other divisor
+    assert_eq!(index.beat_snap(252.0), snap(4, 250.0, 2.0));
+    // before the timing point, where negative times snap from 0
+    assert_eq!(index.beat_snap(-500.0), snap(6, 0.0, -500.0));
+    assert_eq!(index.beat_snap(40.0).divisor, 9);
+}
+
+#[test]
+fn snapped_time() {
+    let state = timing_points_offset().state_at(0.0).unwrap();
+
+    assert_eq!(state.snapped_time(349.0, 4), 400.0);
+    // halfway rounds away from the timing point
+    assert_eq!(state.snapped_time(325.0, 4), 400.0);
+    assert_eq!(state.snapped_time(-125.0, 4), 100.0);
+}
+
+#[test]
+fn hitobject_beat_snaps() {
+    let hitobjects = HitObjects::from_str(
+        "0,0,400,1,0,0:0:0:0:
+0,0,701,2,0,L|100:0,2,100
+256,192,1900,12,0,2801,0:0:0:0:",
+        14,
+    )
+    .unwrap()
+    .unwrap();
+    let difficulty = Difficulty::from_str("SliderMultiplier:1", 14)
+        .unwrap()
+        .unwrap();
+
+    let snaps = hitobjects.beat_snaps(&timing_points_offset(), &difficulty);
+    let snap = |index, point, time: f64, divisor, snapped_time: f64| HitObjectSnap {
+        index,
+        point,
+        time,
+        snap: BeatSnap {
+            divisor,
+            snapped_time,
+            error: time - snapped_time,
+        },
+    };
+
+    assert_eq!(
+        snaps,
+        vec![
+            snap(0, SnapPoint::Start, 400.0, 2, 400.0),
+            snap(1, SnapPoint::Start, 701.0, 1, 700.0),
+            snap(1, SnapPoint::Repeat(0), 1301.0, 1, 1300.0),
+            snap(1, SnapPoint::End, 1901.0, 1, 1900.0),
+            snap(2, SnapPoint::Start, 1900.0, 1, 1900.0),
+            snap(2, SnapPoint::End, 2801.0, 2, 2800.0),
+        ]
+    );
+}
+
+#[test]
+fn beat_snaps_without_timing_points() {
+    let hitobjects = HitObjects::from_str("0,0,400,1,0,0:0:0:0:", 14)
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        hitobjects.beat_snaps(&TimingPoints::default(), &Difficulty::default()),
+        Vec::new()
+    );
+}
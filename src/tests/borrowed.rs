@@ -0,0 +1,186 @@
+use pretty_assertions::assert_eq;
+use rust_decimal_macros::dec;
+
+use crate::osu_file::*;
+
+#[test]
+fn sections_and_values() {
+    let i = "osu file format v14
+
+[General]
+AudioFilename: audio.mp3
+Mode: 3
+
+[Metadata]
+Title:LOVE IS ORANGE
+Version:Bittersweet Love
+
+[Difficulty]
+CircleSize:4.5
+
+[Events]
+//Background and Video events
+0,0,\"bg.jpg\",0,0
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+
+[Unknown]
+foo
+";
+    let osu_file = OsuFileRef::parse(i).unwrap();
+
+    assert_eq!(osu_file.version, 14);
+
+    let general = osu_file.general.as_ref().unwrap();
+    assert_eq!(
+        general.fields().collect::<Vec<_>>(),
+        vec![("AudioFilename", "audio.mp3"), ("Mode", "3")]
+    );
+    assert_eq!(general.audio_filename().unwrap().as_str(), "audio.mp3");
+    assert_eq!(
+        general.mode().unwrap().parse().unwrap(),
+        Some(general::Mode::Mania)
+    );
+
+    let metadata = osu_file.metadata.as_ref().unwrap();
+    assert_eq!(metadata.title().unwrap().as_str(), "LOVE IS ORANGE");
+    assert_eq!(metadata.value("Version"), Some("Bittersweet Love"));
+    assert!(metadata.artist().is_none());
+
+    let circle_size = osu_file.difficulty.as_ref().unwrap().circle_size().unwrap();
+    assert_eq!(circle_size.as_str(), "4.5");
+    assert_eq!(
+        Decimal::from(circle_size.parse().unwrap().unwrap()).to_f64(),
+        Some(4.5)
+    );
+
+    assert_eq!(osu_file.background(), Some("bg.jpg"));
+
+    let timing_points = osu_file.timing_points.as_ref().unwrap();
+    assert_eq!(
+        timing_points
+            .timing_points()
+            .map(|timing_point| timing_point.parse().unwrap().unwrap().calc_bpm())
+            .collect::<Vec<_>>(),
+        vec![Some(dec!(120))]
+    );
+
+    assert_eq!(
+        osu_file.unknown_sections,
+        vec![UnknownSectionRef {
+            name: "Unknown",
+            lines: vec!["foo"],
+            after: Some("TimingPoints"),
+        }]
+    );
+}
+
+#[test]
+fn errors() {
+    let i = "osu file format v14
+
+[General]
+Mode: 0
+
+[General]
+Mode: 1";
+    let err = OsuFileRef::parse(i).unwrap_err();
+
+    assert_eq!(err.line_index(), 5);
+
+    let err = OsuFileRef::parse("osu file format v14\nfoo\n[General]").unwrap_err();
+
+    assert_eq!(err.line_index(), 1);
+    assert!(OsuFileRef::parse("osu file format v100").is_err());
+}
+
+#[test]
+fn into_owned_errors() {
+    let i = "osu file format v14
+
+[HitObjects]
+foo
+
+[General]
+
+Mode: foo
+";
+    let osu_file = OsuFileRef::parse(i).unwrap();
+
+    // values are only checked when they're parsed
+    assert!(osu_file
+        .general
+        .as_ref()
+        .unwrap()
+        .mode()
+        .unwrap()
+        .parse()
+        .is_err());
+
+    let err = osu_file.clone().into_owned().unwrap_err();
+    let owned_err = i.parse::<OsuFile>().unwrap_err();
+
+    assert_eq!(err.line_index(), 3);
+    assert_eq!(err.to_string(), owned_err.to_string());
+    assert_eq!(err.line_index(), owned_err.line_index());
+
+    let err = osu_file.general.unwrap().into_owned().unwrap_err();
+
+    assert_eq!(err.line_index(), 7);
+    assert_eq!(err.span().unwrap().columns, 6..9);
+}
+
+#[test]
+fn into_owned_files() {
+    let files = std::fs::read_dir("./src/tests/osu_files/files").unwrap();
+
+    for file in files {
+        let path = file.unwrap().path();
+
+        if path.extension().unwrap() != "osu" {
+            continue;
+        }
+
+        let i = std::fs::read_to_string(&path).unwrap();
+        let osu_file_ref = OsuFileRef::parse(&i);
+
+        // some files are for testing errors
+        let osu_file = match i.parse::<OsuFile>() {
+            Ok(osu_file) => osu_file,
+            Err(err) => {
+                if let Ok(osu_file_ref) = osu_file_ref {
+                    let ref_err = osu_file_ref.into_owned().unwrap_err();
+
+                    assert_eq!(err.line_index(), ref_err.line_index(), "{}", path.display());
+                    assert_eq!(err.to_string(), ref_err.to_string(), "{}", path.display());
+                }
+                continue;
+            }
+        };
+
+        let osu_file_ref = osu_file_ref.unwrap();
+
+        assert_eq!(osu_file.version, osu_file_ref.version, "{}", path.display());
+        assert_eq!(
+            osu_file
+                .general
+                .as_ref()
+                .and_then(|general| general.audio_filename.as_ref())
+                .map(|audio_filename| audio_filename.to_string(osu_file.version).unwrap()),
+            osu_file_ref
+                .general
+                .as_ref()
+                .and_then(|general| general.audio_filename())
+                .map(|audio_filename| audio_filename.as_str().to_string()),
+            "{}",
+            path.display()
+        );
+        assert_eq!(
+            osu_file,
+            osu_file_ref.into_owned().unwrap(),
+            "{}",
+            path.display()
+        );
+    }
+}
@@ -0,0 +1,152 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::*;
+
+#[test]
+fn convert_down() {
+    let i = "osu file format v14
+
+[General]
+AudioFilename: audio.mp3
+PreviewTime: 1000
+Mode: 0
+
+[Editor]
+Bookmarks: 100,200
+DistanceSpacing: 0.8
+BeatDivisor: 4
+
+[TimingPoints]
+350,333.33,4,2,1,60,1,1
+
+[Colours]
+Combo1 : 255,0,0
+
+[HitObjects]
+256,192,350,1,0,1:0:0:0:hit.wav
+256,192,500,1,0,0:0:0:0:";
+    let o = "osu file format v4
+
+[General]
+AudioFilename: audio.mp3
+PreviewTime: 976
+Mode: 0
+EditorBookmarks: 100,200
+EditorDistanceSpacing: 0.8
+
+[TimingPoints]
+326,333.33,4,2,1
+
+[HitObjects]
+256,192,326,1,0
+256,192,476,1,0";
+    let mut osu_file = i.parse::<OsuFile>().unwrap();
+
+    let losses = osu_file.convert_to_version(4).unwrap();
+
+    assert_eq!(
+        losses,
+        vec![
+            ConversionLoss::Field {
+                section: "Editor",
+                key: "BeatDivisor".to_string()
+            },
+            ConversionLoss::TimingPointField {
+                index: 0,
+                field: "volume"
+            },
+            ConversionLoss::TimingPointField {
+                index: 0,
+                field: "uninherited"
+            },
+            ConversionLoss::TimingPointField {
+                index: 0,
+                field: "effects"
+            },
+            ConversionLoss::HitObjectField {
+                index: 0,
+                field: "hitsample"
+            },
+            ConversionLoss::Section("Colours"),
+        ]
+    );
+    assert_eq!(osu_file.version, 4);
+    assert_eq!(o, osu_file.to_string());
+}
+
+#[test]
+fn convert_up() {
+    let i = "osu file format v4
+
+[General]
+AudioFilename: audio.mp3
+AudioHash: a37859a4a0919cd369a1612762cb0c38
+PreviewTime: 976
+EditorBookmarks: 100,200
+
+[Events]
+3,100,0,0,0
+
+[HitObjects]
+256,192,326,1,0
+256,192,1e3,1,0";
+    let o = "osu file format v14
+
+[General]
+AudioFilename: audio.mp3
+PreviewTime: 1000
+
+[Editor]
+Bookmarks: 100,200
+
+[Events]
+
+
+[HitObjects]
+256,192,350,1,0
+256,192,1e3,1,0";
+    let mut osu_file = i.parse::<OsuFile>().unwrap();
+
+    let losses = osu_file.convert_to_version(14).unwrap();
+
+    assert_eq!(
+        losses,
+        vec![
+            ConversionLoss::Field {
+                section: "General",
+                key: "AudioHash".to_string()
+            },
+            ConversionLoss::Event(0),
+            ConversionLoss::TimeOffset {
+                section: "HitObjects",
+                index: 1
+            },
+        ]
+    );
+    assert_eq!(o, osu_file.to_string());
+}
+
+#[test]
+fn convert_no_preview_time() {
+    let mut osu_file = "osu file format v4
+
+[General]
+PreviewTime: -1"
+        .parse::<OsuFile>()
+        .unwrap();
+
+    osu_file.convert_to_version(14).unwrap();
+
+    assert_eq!(
+        osu_file.general.unwrap().preview_time,
+        Some(general::PreviewTime::from(-1))
+    );
+}
+
+#[test]
+fn convert_invalid_version() {
+    let mut osu_file = "osu file format v14".parse::<OsuFile>().unwrap();
+
+    assert!(osu_file.convert_to_version(15).is_err());
+    assert_eq!(osu_file.version, 14);
+}
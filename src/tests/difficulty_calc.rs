@@ -0,0 +1,329 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::{
+    difficulty_calc::{catch, mania, osu, taiko},
+    Mods, OsuFile,
+};
+
+pub(super) fn osu_file(mode: u8, hitobjects: &str) -> OsuFile {
+    format!(
+        "osu file format v14
+
+[General]
+Mode: {mode}
+
+[Difficulty]
+HPDrainRate:5
+CircleSize:4
+OverallDifficulty:8
+ApproachRate:9
+SliderMultiplier:1.4
+SliderTickRate:1
+
+[TimingPoints]
+0,300,4,2,0,100,1,0
+
+[HitObjects]
+{hitobjects}"
+    )
+    .parse()
+    .unwrap()
+}
+
+/// Beatmap from the test files.
+pub(super) fn beatmap(name: &str) -> OsuFile {
+    OsuFile::from_path(format!("./src/tests/osu_files/files/{name}.osu")).unwrap()
+}
+
+pub(super) fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() <= expected.abs() * 1e-9,
+        "{actual} != {expected}"
+    );
+}
+
+/// Circles jumping between `x` positions every `delta_time`.
+pub(super) fn jumps(distance: i32, delta_time: i32, count: i32) -> String {
+    (0..count)
+        .map(|i| {
+            let x = 256
+                + if i % 2 == 0 {
+                    -distance / 2
+                } else {
+                    distance / 2
+                };
+            format!("{x},192,{},1,0,0:0:0:0:", 1000 + i * delta_time)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn object_counts() {
+    let hitobjects = "100,100,1000,1,0,0:0:0:0:
+100,100,1300,2,0,L|100:200,1,140
+300,100,2000,2,0,L|300:380,2,280
+256,192,3000,12,0,4000,0:0:0:0:";
+    let attributes = osu_file(0, hitobjects).osu_difficulty().unwrap();
+
+    assert_eq!(attributes.hit_circle_count, 1);
+    assert_eq!(attributes.slider_count, 2);
+    assert_eq!(attributes.spinner_count, 1);
+    // circle, head and tail, head, tick, repeat, tick and tail, spinner
+    assert_eq!(attributes.max_combo, 1 + 2 + 5 + 1);
+    assert_eq!(attributes.approach_rate, 9.0);
+    assert_eq!(attributes.overall_difficulty, 8.0);
+    assert_eq!(attributes.drain_rate, 5.0);
+}
+
+#[test]
+fn jumps_and_streams() {
+    let jumps_attributes = osu_file(0, &jumps(300, 150, 200)).osu_difficulty().unwrap();
+    let stream_attributes = osu_file(0, &jumps(20, 75, 200)).osu_difficulty().unwrap();
+
+    assert!(jumps_attributes.star_rating > 0.0);
+    assert!(stream_attributes.star_rating > 0.0);
+    assert!(jumps_attributes.aim_difficulty > stream_attributes.aim_difficulty);
+    assert!(stream_attributes.speed_difficulty > jumps_attributes.speed_difficulty);
+    assert_eq!(jumps_attributes.slider_factor, 1.0);
+    assert_eq!(jumps_attributes.max_combo, 200);
+}
+
+// the star ratings of the test beatmaps are the ones this crate gives, which aren't checked against osu-tools yet
+// they catch changes to the strains and how they're weighted, so update them only when the calculation is meant to change
+
+#[test]
+fn osu_star_ratings() {
+    let v14_3 = beatmap("v14_3");
+    let attributes = osu::calculate(&v14_3, &Mods::default());
+
+    assert_close(attributes.star_rating, 3.5635715836881223);
+    assert_close(attributes.aim_difficulty, 1.7689983128691658);
+    assert_close(attributes.speed_difficulty, 1.6361363396655977);
+    assert_close(attributes.slider_factor, 0.9267517462170147);
+    assert_eq!(attributes.max_combo, 490);
+
+    let star_rating = |osu_file, bits| osu::calculate(osu_file, &Mods::from_bits(bits)).star_rating;
+
+    assert_close(star_rating(&v14_3, 64), 4.934114931808504);
+    assert_close(star_rating(&v14_3, 16), 3.819207148952458);
+    assert_close(star_rating(&v14_3, 256), 2.86773852703108);
+
+    let aspire27 = beatmap("aspire27");
+    let attributes = osu::calculate(&aspire27, &Mods::default());
+
+    assert_close(attributes.star_rating, 7.294871192406543);
+    assert_close(attributes.aim_difficulty, 4.068795632314889);
+    assert_close(attributes.speed_difficulty, 2.5128487504990193);
+    assert_close(attributes.slider_factor, 0.8742086737319676);
+    assert_eq!(attributes.max_combo, 975);
+
+    assert_close(star_rating(&aspire27, 64), 10.073342315176754);
+    assert_close(star_rating(&aspire27, 16), 8.05767844965689);
+    assert_close(star_rating(&aspire27, 1024), 7.907672989211993);
+}
+
+#[test]
+fn other_mode_star_ratings() {
+    let match_test = beatmap("match_test");
+    let star_rating = |bits| taiko::calculate(&match_test, &Mods::from_bits(bits)).star_rating;
+
+    assert_close(star_rating(0), 3.4950911424045663);
+    assert_close(star_rating(64), 4.706265443448353);
+    // hard rock doesn't change the star rating in osu!taiko
+    assert_close(star_rating(16), 3.4950911424045663);
+    assert_close(star_rating(256), 2.8358082412270607);
+
+    let aspire1 = beatmap("aspire1");
+    let star_rating = |bits| catch::calculate(&aspire1, &Mods::from_bits(bits)).star_rating;
+
+    assert_close(star_rating(0), 9.438187167416663);
+    assert_close(star_rating(64), 11.53252742902793);
+    assert_close(star_rating(16), 10.46435435296409);
+    assert_close(star_rating(2), 8.246825451719243);
+
+    let v14 = beatmap("v14");
+    let star_rating = |bits| mania::calculate(&v14, &Mods::from_bits(bits)).star_rating;
+
+    assert_close(star_rating(0), 4.137806370651467);
+    assert_close(star_rating(64), 5.755016956174079);
+    assert_close(star_rating(16), 4.137806370651467);
+    assert_close(star_rating(256), 3.281822171684953);
+}
+
+#[test]
+fn sliders_add_aim() {
+    let hitobjects = (0..100)
+        .map(|i| {
+            let x = if i % 2 == 0 { 100 } else { 400 };
+            format!("{x},100,{},2,0,L|{x}:300,1,210", 1000 + i * 600)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let attributes = osu_file(0, &hitobjects).osu_difficulty().unwrap();
+
+    assert!(attributes.slider_factor < 1.0);
+    assert_eq!(attributes.slider_count, 100);
+}
+
+#[test]
+fn no_hitobjects() {
+    let attributes = osu_file(0, "").osu_difficulty().unwrap();
+
+    assert_eq!(attributes.star_rating, 0.0);
+    assert_eq!(attributes.max_combo, 0);
+}
+
+#[test]
+fn other_modes() {
+    assert_eq!(osu_file(1, &jumps(100, 150, 10)).osu_difficulty(), None);
+    assert_eq!(osu_file(3, &jumps(100, 150, 10)).osu_difficulty(), None);
+    assert_eq!(osu_file(0, &jumps(100, 150, 10)).taiko_difficulty(), None);
+    assert_eq!(osu_file(0, &jumps(100, 150, 10)).catch_difficulty(), None);
+    assert_eq!(osu_file(0, &jumps(100, 150, 10)).mania_difficulty(), None);
+}
+
+/// Taiko notes every `delta_time`, with kats where the `pattern` is `k`.
+pub(super) fn taiko_notes(pattern: &str, delta_time: usize, count: usize) -> String {
+    pattern
+        .chars()
+        .cycle()
+        .take(count)
+        .enumerate()
+        .map(|(i, colour)| {
+            let hitsound = if colour == 'k' { 2 } else { 0 };
+            format!("256,192,{},1,{hitsound},0:0:0:0:", 1000 + i * delta_time)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn taiko() {
+    let mono = osu_file(1, &taiko_notes("d", 83, 400))
+        .taiko_difficulty()
+        .unwrap();
+    let alternating = osu_file(1, &taiko_notes("ddkdkkdk", 83, 400))
+        .taiko_difficulty()
+        .unwrap();
+    let slow = osu_file(1, &taiko_notes("ddkdkkdk", 166, 400))
+        .taiko_difficulty()
+        .unwrap();
+
+    assert!(mono.star_rating > 0.0);
+    assert!(alternating.colour_difficulty > mono.colour_difficulty);
+    assert!(alternating.star_rating > mono.star_rating);
+    assert!(alternating.stamina_difficulty > slow.stamina_difficulty);
+    assert_eq!(alternating.max_combo, 400);
+    assert_eq!(alternating.great_hit_window, 26.0);
+}
+
+#[test]
+fn taiko_rhythm() {
+    let hitobjects = (0..300)
+        .map(|i| {
+            let time = 1000 + i / 3 * 300 + [0, 100, 150][i % 3];
+            format!("256,192,{time},1,0,0:0:0:0:")
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let attributes = osu_file(1, &hitobjects).taiko_difficulty().unwrap();
+
+    assert!(attributes.rhythm_difficulty > 0.0);
+    assert_eq!(
+        osu_file(1, &taiko_notes("d", 100, 300))
+            .taiko_difficulty()
+            .unwrap()
+            .rhythm_difficulty,
+        0.0
+    );
+}
+
+#[test]
+fn taiko_combo() {
+    let hitobjects = "256,192,1000,1,0,0:0:0:0:
+256,192,1300,2,0,L|400:192,1,140
+256,192,2000,12,0,3000,0:0:0:0:
+256,192,3500,1,8,0:0:0:0:";
+    let attributes = osu_file(1, hitobjects).taiko_difficulty().unwrap();
+
+    assert_eq!(attributes.max_combo, 2);
+}
+
+#[test]
+fn catch_counts() {
+    let hitobjects = "100,192,1000,2,0,L|380:192,1,280
+256,192,2000,1,0,0:0:0:0:
+256,192,3000,12,0,4000,0:0:0:0:";
+    let attributes = osu_file(2, hitobjects).catch_difficulty().unwrap();
+
+    // head, tail and the circle
+    assert_eq!(attributes.fruit_count, 3);
+    assert_eq!(attributes.droplet_count, 1);
+    // 3 between the head and tick, and 3 between the tick and the legacy last tick
+    assert_eq!(attributes.tiny_droplet_count, 6);
+    assert_eq!(attributes.max_combo, 4);
+    assert_eq!(attributes.approach_rate, 9.0);
+}
+
+#[test]
+fn catch_movement() {
+    let fruits = |distance: i32| {
+        (0..300)
+            .map(|i| {
+                let x = 256 + if i % 2 == 0 { -distance } else { distance } / 2;
+                format!("{x},192,{},1,0,0:0:0:0:", 1000 + i * 300)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let far = osu_file(2, &fruits(400)).catch_difficulty().unwrap();
+    let close = osu_file(2, &fruits(100)).catch_difficulty().unwrap();
+    let still = osu_file(2, &fruits(0)).catch_difficulty().unwrap();
+
+    assert!(far.star_rating > close.star_rating);
+    assert!(close.star_rating > still.star_rating);
+    assert_eq!(still.star_rating, 0.0);
+}
+
+/// Mania notes every `delta_time`, going through the `columns` of a 4 key beatmap.
+pub(super) fn mania_notes(columns: &[usize], delta_time: usize, count: usize) -> String {
+    (0..count)
+        .map(|i| {
+            let x = columns[i % columns.len()] * 128 + 64;
+            format!("{x},192,{},1,0,0:0:0:0:", 1000 + i * delta_time)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn mania() {
+    let trill = osu_file(3, &mania_notes(&[0, 1], 100, 800))
+        .mania_difficulty()
+        .unwrap();
+    let jack = osu_file(3, &mania_notes(&[0], 100, 800))
+        .mania_difficulty()
+        .unwrap();
+    let slow = osu_file(3, &mania_notes(&[0, 1], 200, 800))
+        .mania_difficulty()
+        .unwrap();
+
+    assert!(trill.star_rating > slow.star_rating);
+    assert!(jack.star_rating > trill.star_rating);
+    assert_eq!(trill.max_combo, 800);
+    assert_eq!(trill.great_hit_window, 40.0);
+}
+
+#[test]
+fn mania_holds() {
+    let hitobjects = "64,192,1000,128,0,1550:0:0:0:0:
+192,192,1000,1,0,0:0:0:0:
+320,192,1200,128,0,1400:0:0:0:0:";
+    let attributes = osu_file(3, hitobjects).mania_difficulty().unwrap();
+
+    assert_eq!(attributes.note_count, 1);
+    assert_eq!(attributes.hold_note_count, 2);
+    assert_eq!(attributes.max_combo, 6 + 1 + 3);
+    assert!(attributes.star_rating > 0.0);
+}
@@ -0,0 +1,107 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::{
+    difficulty::{difficulty_range, DifficultyValues, ModeDifficulty},
+    general::Mode,
+    Difficulty,
+};
+
+#[test]
+fn values() {
+    let difficulty = Difficulty::from_str(
+        "HPDrainRate:6\nCircleSize:4\nOverallDifficulty:8\nApproachRate:9.5",
+        14,
+    )
+    .unwrap()
+    .unwrap();
+
+    assert_eq!(
+        difficulty.values(),
+        DifficultyValues {
+            hp_drain_rate: 6.0,
+            circle_size: 4.0,
+            overall_difficulty: 8.0,
+            approach_rate: 9.5,
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn approach_rate_fallback() {
+    let difficulty = Difficulty::from_str("OverallDifficulty:7", 7)
+        .unwrap()
+        .unwrap();
+    let values = difficulty.values();
+
+    assert_eq!(values.approach_rate, 7.0);
+    assert_eq!(values.circle_size, 5.0);
+    assert_eq!(values.slider_multiplier, 1.4);
+}
+
+#[test]
+fn range() {
+    assert_eq!(difficulty_range(0.0, 1800.0, 1200.0, 450.0), 1800.0);
+    assert_eq!(difficulty_range(5.0, 1800.0, 1200.0, 450.0), 1200.0);
+    assert_eq!(difficulty_range(10.0, 1800.0, 1200.0, 450.0), 450.0);
+    assert_eq!(difficulty_range(9.0, 1800.0, 1200.0, 450.0), 600.0);
+}
+
+#[test]
+fn mode_conversions() {
+    let values = DifficultyValues {
+        circle_size: 5.0,
+        overall_difficulty: 5.0,
+        approach_rate: 10.0,
+        ..Default::default()
+    };
+
+    let osu = values.osu();
+    assert_eq!(osu.preempt, 450.0);
+    assert_eq!(osu.fade_in, 400.0);
+    assert_eq!(
+        (osu.hit_window_300, osu.hit_window_100, osu.hit_window_50),
+        (50.0, 100.0, 150.0)
+    );
+    assert_eq!(osu.circle_radius, 32.0 * 1.00041);
+
+    let taiko = values.taiko();
+    assert_eq!(
+        (
+            taiko.hit_window_great,
+            taiko.hit_window_ok,
+            taiko.hit_window_miss
+        ),
+        (35.0, 80.0, 95.0)
+    );
+
+    let catch = values.catch();
+    assert_eq!(catch.scale, 1.0);
+    assert_eq!(catch.catcher_width, 106.75 * 0.8);
+
+    let mania = values.mania();
+    assert_eq!(
+        (
+            mania.hit_window_320,
+            mania.hit_window_300,
+            mania.hit_window_200,
+            mania.hit_window_100,
+            mania.hit_window_50
+        ),
+        (16.0, 49.0, 82.0, 112.0, 136.0)
+    );
+
+    assert_eq!(values.mode(Mode::Taiko), ModeDifficulty::Taiko(taiko));
+}
+
+#[test]
+fn fade_in_high_approach_rate() {
+    let values = DifficultyValues {
+        approach_rate: 11.0,
+        ..Default::default()
+    };
+    let osu = values.osu();
+
+    assert_eq!(osu.preempt, 300.0);
+    assert_eq!(osu.fade_in, 400.0 * (300.0 / 450.0));
+}
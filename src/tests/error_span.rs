@@ -0,0 +1,87 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::*;
+
+#[test]
+fn hitobject_field() {
+    let i = "osu file format v14\r\n\r\n[HitObjects]\r\n256,192,0,1,0,0:0:0:0:\r\n256,192,500,foo,0,0:0:0:0:";
+    let err = i.parse::<OsuFile>().unwrap_err();
+    let span = err.span().unwrap();
+
+    assert_eq!(
+        span,
+        Span {
+            line_index: 4,
+            columns: 12..15,
+        }
+    );
+    assert_eq!(&i[span.byte_range(i).unwrap()], "foo");
+    assert_eq!(
+        err.display_error_with_span(i),
+        "error: Invalid `obj_type` value
+ --> 5:13
+  |
+5 | 256,192,500,foo,0,0:0:0:0:
+  |             ^^^"
+    );
+}
+
+#[test]
+fn hitobject_missing_field() {
+    let i = "256,192";
+    let err = HitObjects::from_str(i, 14).unwrap_err();
+
+    assert_eq!(err.span().unwrap().columns, 7..7);
+    assert_eq!(
+        err.display_error_with_span(i),
+        "error: Missing `time` field
+ --> 1:8
+  |
+1 | 256,192
+  |        ^"
+    );
+}
+
+#[test]
+fn general_value() {
+    let i = "osu file format v14\n\n[General]\nAudioFilename: audio.mp3\nAudioLeadIn: foo";
+    let err = i.parse::<OsuFile>().unwrap_err();
+    let span = err.span().unwrap();
+
+    assert_eq!(span.line_index, 4);
+    assert_eq!(&i[span.byte_range(i).unwrap()], "foo");
+}
+
+#[test]
+fn colour_value() {
+    let i = "Combo1 : 255,128,255\nCombo2 : 255,foo,255";
+    let err = Colours::from_str(i, 14).unwrap_err();
+    let span = err.span().unwrap();
+
+    assert_eq!(span.line_index, 1);
+    assert_eq!(&i[span.byte_range(i).unwrap()], "foo");
+}
+
+#[test]
+fn timing_point_field() {
+    let i = "10000,333.33,4,0,0,foo,1,1";
+    let err = TimingPoints::from_str(i, 14).unwrap_err();
+
+    assert_eq!(err.span().unwrap().columns, 19..22);
+}
+
+#[test]
+fn whole_line() {
+    let i = "[General]\n  foobar";
+    let err = General::from_str("AudioFilename: audio.mp3\nfoobar", 14).unwrap_err();
+
+    assert_eq!(err.span(), None);
+    assert_eq!(
+        err.display_error_with_span(i),
+        "error: Invalid colon set, expected format of `key: value`
+ --> 2:3
+  |
+2 |   foobar
+  |   ^^^^^^"
+    );
+}
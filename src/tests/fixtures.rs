@@ -0,0 +1,53 @@
+use crate::osu_file::{Difficulty, TimingPoints, VersionedFromStr};
+
+fn timing_points(s: &str) -> TimingPoints {
+    TimingPoints::from_str(s, 14).unwrap().unwrap()
+}
+
+fn difficulty(s: &str) -> Difficulty {
+    Difficulty::from_str(s, 14).unwrap().unwrap()
+}
+
+/// 120 BPM from `0`.
+pub(crate) fn timing_points_120_bpm() -> TimingPoints {
+    timing_points("0,500,4,2,0,100,1,0")
+}
+
+/// 120 BPM from `0`, a slider velocity of `2` from `1000`, then 240 BPM from `2000`.
+pub(crate) fn timing_points_sv_change() -> TimingPoints {
+    timing_points(
+        "0,500,4,2,0,100,1,0
+1000,-50,4,2,0,100,0,0
+2000,250,4,2,0,100,1,0",
+    )
+}
+
+/// 100 BPM from `100`, with a slider velocity of `2` from `2500`.
+pub(crate) fn timing_points_offset() -> TimingPoints {
+    timing_points(
+        "100,600,4,2,0,100,1,0
+2500,-50,4,2,0,100,0,0",
+    )
+}
+
+/// Uninherited and inherited timing points at the same times, with different samples, volumes and kiai.
+pub(crate) fn timing_points_same_time() -> TimingPoints {
+    timing_points(
+        "0,500,4,2,0,60,1,0
+1000,-50,4,1,0,70,0,1
+1000,400,3,3,0,80,1,0
+2000,300,4,2,0,90,1,0
+2000,350,4,2,0,95,1,0
+3000,-200,4,2,0,50,0,0",
+    )
+}
+
+/// Slider multiplier of `2` with a slider tick every beat.
+pub(crate) fn difficulty_sliders() -> Difficulty {
+    difficulty("SliderMultiplier:2\nSliderTickRate:1")
+}
+
+/// Circle size `5` and approach rate `9`, which set the stack distance and time, with a slider multiplier of `1`.
+pub(crate) fn difficulty_stacking() -> Difficulty {
+    difficulty("CircleSize:5\nApproachRate:9\nSliderMultiplier:1")
+}
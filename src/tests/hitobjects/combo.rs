@@ -0,0 +1,114 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::{colours::Rgb, hitobjects::HitObjects, OsuFile, VersionedFromStr};
+
+const HITOBJECTS: &str = "0,0,0,1,0,0:0:0:0:
+0,0,100,1,0,0:0:0:0:
+0,0,200,5,0,0:0:0:0:
+0,0,300,37,0,0:0:0:0:
+256,192,400,8,0,500,0:0:0:0:
+0,0,600,1,0,0:0:0:0:
+256,192,700,28,0,800,0:0:0:0:
+0,0,900,1,0,0:0:0:0:";
+
+fn rgb(red: u8) -> Rgb {
+    Rgb {
+        red,
+        green: 0,
+        blue: 0,
+    }
+}
+
+#[test]
+fn combos() {
+    let hitobjects = HitObjects::from_str(HITOBJECTS, 14).unwrap().unwrap();
+    let combos = hitobjects.combos(&[rgb(0), rgb(1), rgb(2)], 14);
+
+    assert_eq!(
+        combos
+            .iter()
+            .map(|combo| (combo.combo_index, combo.index_in_combo, combo.colour_index))
+            .collect::<Vec<_>>(),
+        vec![
+            (0, 0, 1),
+            (0, 1, 1),
+            (1, 0, 2),
+            // skips 2 colours
+            (2, 0, 5),
+            // spinners continue the combo
+            (2, 1, 5),
+            (2, 2, 5),
+            (2, 3, 5),
+            // new combo from the spinner, with its skipped colour
+            (3, 0, 7),
+        ]
+    );
+    assert_eq!(
+        combos
+            .iter()
+            .map(|combo| combo.colour.unwrap().red)
+            .collect::<Vec<_>>(),
+        vec![1, 1, 2, 2, 2, 2, 2, 1]
+    );
+}
+
+#[test]
+fn combos_old_spinner() {
+    let hitobjects = HitObjects::from_str(HITOBJECTS, 8).unwrap().unwrap();
+    let combos = hitobjects.combos(&[], 8);
+
+    assert_eq!(
+        combos
+            .iter()
+            .map(|combo| (combo.combo_index, combo.index_in_combo, combo.colour_index))
+            .collect::<Vec<_>>(),
+        vec![
+            (0, 0, 1),
+            (0, 1, 1),
+            (1, 0, 2),
+            (2, 0, 5),
+            (2, 1, 5),
+            // spinners always start a new combo after them
+            (3, 0, 6),
+            (3, 1, 6),
+            (4, 0, 8),
+        ]
+    );
+    assert!(combos.iter().all(|combo| combo.colour.is_none()));
+}
+
+#[test]
+fn osu_file_combos() {
+    let osu_file = |colours: &str| {
+        format!(
+            "osu file format v14
+
+[Colours]
+{colours}
+
+[HitObjects]
+{HITOBJECTS}"
+        )
+        .parse::<OsuFile>()
+        .unwrap()
+    };
+    let reds = |osu_file: &OsuFile| {
+        osu_file
+            .combos(&[rgb(10), rgb(20)])
+            .iter()
+            .map(|combo| combo.colour.unwrap().red)
+            .collect::<Vec<_>>()
+    };
+
+    // combo colours are in the order of their number
+    assert_eq!(
+        reds(&osu_file(
+            "Combo2 : 1,0,0\nCombo1 : 0,0,0\nSliderBorder : 5,0,0"
+        )),
+        vec![1, 1, 0, 1, 1, 1, 1, 1]
+    );
+    assert_eq!(
+        reds(&osu_file("SliderBorder : 5,0,0")),
+        vec![20, 20, 10, 20, 20, 20, 20, 20]
+    );
+}
@@ -1,2 +1,8 @@
+mod combo;
 mod error;
+mod nested;
 mod parse;
+mod path;
+mod stacking;
+mod timing;
+mod transform;
@@ -0,0 +1,76 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::{
+    hitobjects::{HitObject, NestedObjectKind},
+    VersionedFromStr,
+};
+use crate::tests::fixtures::{difficulty_sliders, timing_points_120_bpm};
+
+#[test]
+fn slider_nested_objects() {
+    let slider = HitObject::from_str("0,0,0,2,0,L|400:0,2,400", 14)
+        .unwrap()
+        .unwrap();
+
+    let nested = slider
+        .nested_objects(&timing_points_120_bpm(), &difficulty_sliders(), 14)
+        .unwrap();
+
+    assert_eq!(
+        nested
+            .iter()
+            .map(|object| (object.kind, object.span_index, object.time))
+            .collect::<Vec<_>>(),
+        vec![
+            (NestedObjectKind::Head, 0, 0.0),
+            (NestedObjectKind::Tick, 0, 500.0),
+            (NestedObjectKind::Repeat, 0, 1000.0),
+            (NestedObjectKind::Tick, 1, 1500.0),
+            (NestedObjectKind::LegacyLastTick, 1, 1964.0),
+            (NestedObjectKind::Tail, 1, 2000.0),
+        ]
+    );
+
+    let x = nested
+        .iter()
+        .map(|object| object.position.x)
+        .collect::<Vec<_>>();
+    let expected = [0.0, 200.0, 400.0, 200.0, 14.4, 0.0];
+
+    for (x, expected) in x.iter().zip(expected) {
+        assert!((x - expected).abs() < 1e-6, "{x} != {expected}");
+    }
+}
+
+#[test]
+fn short_slider() {
+    // the legacy last tick is at least at the middle of the slider
+    let slider = HitObject::from_str("0,0,0,2,0,L|400:0,1,20", 14)
+        .unwrap()
+        .unwrap();
+
+    let nested = slider
+        .nested_objects(&timing_points_120_bpm(), &difficulty_sliders(), 14)
+        .unwrap();
+
+    assert_eq!(
+        nested
+            .iter()
+            .map(|object| (object.kind, object.time))
+            .collect::<Vec<_>>(),
+        vec![
+            (NestedObjectKind::Head, 0.0),
+            (NestedObjectKind::LegacyLastTick, 25.0),
+            (NestedObjectKind::Tail, 50.0),
+        ]
+    );
+}
+
+#[test]
+fn not_slider() {
+    let hitcircle = HitObject::from_str("256,192,100,1,0", 14).unwrap().unwrap();
+
+    assert!(hitcircle
+        .nested_objects(&timing_points_120_bpm(), &difficulty_sliders(), 14)
+        .is_none());
+}
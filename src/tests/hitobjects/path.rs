@@ -0,0 +1,109 @@
+use crate::osu_file::{
+    hitobjects::{CurveType, HitObject, SliderPath, Vec2},
+    VersionedFromStr,
+};
+
+fn path(s: &str) -> SliderPath {
+    HitObject::from_str(s, 14)
+        .unwrap()
+        .unwrap()
+        .slider_path()
+        .unwrap()
+}
+
+fn assert_close(a: Vec2, b: Vec2) {
+    assert!(a.distance(b) < 0.5, "{a:?} != {b:?}");
+}
+
+#[test]
+fn linear() {
+    let path = path("100,100,0,2,0,L|200:100,1,50");
+
+    assert_eq!(path.distance(), 50.0);
+    assert_eq!(path.position_at(0.5), Vec2::new(125.0, 100.0));
+    assert_eq!(path.position_at(1.0), Vec2::new(150.0, 100.0));
+    assert_eq!(path.position_at(2.0), Vec2::new(150.0, 100.0));
+}
+
+#[test]
+fn linear_extended() {
+    let path = path("100,100,0,2,0,L|200:100,1,150");
+
+    assert_eq!(path.distance(), 150.0);
+    assert_eq!(path.position_at(1.0), Vec2::new(250.0, 100.0));
+}
+
+#[test]
+fn perfect_circle() {
+    let path = path("0,0,0,2,0,P|50:50|100:0,1,157.0796");
+
+    assert_close(path.position_at(0.0), Vec2::new(0.0, 0.0));
+    assert_close(path.position_at(0.5), Vec2::new(50.0, 50.0));
+    assert_close(path.position_at(1.0), Vec2::new(100.0, 0.0));
+}
+
+#[test]
+fn perfect_circle_collinear() {
+    let path = path("0,0,0,2,0,P|50:0|100:0,1,100");
+
+    assert_close(path.position_at(0.5), Vec2::new(50.0, 0.0));
+    assert_close(path.position_at(1.0), Vec2::new(100.0, 0.0));
+}
+
+#[test]
+fn perfect_circle_red_anchor() {
+    let perfect_circle = path("0,0,0,2,0,P|50:50|100:0|100:0|150:50|200:0,1,150");
+    let bezier = path("0,0,0,2,0,B|50:50|100:0|100:0|150:50|200:0,1,150");
+
+    assert_eq!(perfect_circle.points(), bezier.points());
+    assert_close(
+        perfect_circle.position_at_distance(57.4),
+        Vec2::new(50.0, 25.0),
+    );
+}
+
+#[test]
+fn bezier_red_anchor() {
+    let path = path("0,0,0,2,0,B|100:0|100:0|100:100,1,200");
+
+    assert_close(path.position_at(0.25), Vec2::new(50.0, 0.0));
+    assert_close(path.position_at(0.5), Vec2::new(100.0, 0.0));
+    assert_close(path.position_at(1.0), Vec2::new(100.0, 100.0));
+}
+
+#[test]
+fn bezier_curve() {
+    let path = path("0,0,0,2,0,B|50:100|100:0,1,100");
+
+    assert!((path.distance() - 100.0).abs() < 1e-9);
+    assert_close(path.position_at(0.0), Vec2::new(0.0, 0.0));
+    // the curve is symmetric, so the middle is at the peak
+    let middle = SliderPath::new(
+        CurveType::Bezier,
+        &[
+            Vec2::new(0.0, 0.0),
+            Vec2::new(50.0, 100.0),
+            Vec2::new(100.0, 0.0),
+        ],
+        None,
+    )
+    .position_at(0.5);
+    assert_close(middle, Vec2::new(50.0, 50.0));
+}
+
+#[test]
+fn catmull() {
+    let path = path("0,0,0,2,0,C|50:50|100:0,1,50");
+
+    assert!((path.distance() - 50.0).abs() < 1e-9);
+    assert_eq!(path.points()[0], Vec2::new(0.0, 0.0));
+}
+
+#[test]
+fn not_slider() {
+    let hitcircle = HitObject::from_str("221,350,9780,1,0", 14)
+        .unwrap()
+        .unwrap();
+
+    assert!(hitcircle.slider_path().is_none());
+}
@@ -0,0 +1,123 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::{hitobjects::HitObjects, OsuFile, Version, VersionedFromStr};
+use crate::tests::fixtures::{difficulty_stacking, timing_points_120_bpm};
+
+fn stack_heights(hitobjects: &str, version: Version) -> Vec<i32> {
+    let hitobjects = HitObjects::from_str(hitobjects, 14).unwrap().unwrap();
+
+    hitobjects
+        .stacking(
+            &timing_points_120_bpm(),
+            &difficulty_stacking(),
+            0.7,
+            version,
+        )
+        .iter()
+        .map(|object| object.stack_height)
+        .collect()
+}
+
+#[test]
+fn stack_circles() {
+    let hitobjects = "100,100,0,1,0,0:0:0:0:
+100,100,100,1,0,0:0:0:0:
+101,101,200,1,0,0:0:0:0:
+100,100,1000,1,0,0:0:0:0:";
+
+    assert_eq!(stack_heights(hitobjects, 14), vec![2, 1, 0, 0]);
+    assert_eq!(stack_heights(hitobjects, 5), vec![2, 1, 0, 0]);
+
+    let stacking = HitObjects::from_str(hitobjects, 14)
+        .unwrap()
+        .unwrap()
+        .stacking(&timing_points_120_bpm(), &difficulty_stacking(), 0.7, 14);
+    let offset = 2.0 * (0.5 * 1.00041) * -6.4;
+
+    assert_eq!(stacking[0].position.x, 100.0 + offset);
+    assert_eq!(stacking[0].position.y, 100.0 + offset);
+    assert_eq!(stacking[0].end_position, stacking[0].position);
+}
+
+#[test]
+fn stack_slider_end() {
+    // the slider ends at 500ms on 100,0
+    let hitobjects = "0,0,0,2,0,L|100:0,1,100
+100,0,600,1,0,0:0:0:0:
+100,0,700,1,0,0:0:0:0:";
+
+    assert_eq!(stack_heights(hitobjects, 14), vec![0, -1, -2]);
+    assert_eq!(stack_heights(hitobjects, 5), vec![0, -1, -2]);
+
+    let stacking = HitObjects::from_str(hitobjects, 14)
+        .unwrap()
+        .unwrap()
+        .stacking(&timing_points_120_bpm(), &difficulty_stacking(), 0.7, 14);
+
+    assert!(stacking[2].position.x > 100.0);
+    assert_eq!(stacking[0].end_position.x, 100.0);
+}
+
+#[test]
+fn stack_leniency() {
+    let hitobjects = "100,100,0,1,0,0:0:0:0:
+100,100,300,1,0,0:0:0:0:";
+
+    let hitobjects = HitObjects::from_str(hitobjects, 14).unwrap().unwrap();
+    let heights = |stack_leniency| {
+        hitobjects
+            .stacking(
+                &timing_points_120_bpm(),
+                &difficulty_stacking(),
+                stack_leniency,
+                14,
+            )
+            .iter()
+            .map(|object| object.stack_height)
+            .collect::<Vec<_>>()
+    };
+
+    // the approach time is 600ms
+    assert_eq!(heights(0.7), vec![1, 0]);
+    assert_eq!(heights(0.4), vec![0, 0]);
+}
+
+#[test]
+fn stack_spinner_old() {
+    let hitobjects = "256,192,0,12,0,1000,0:0:0:0:
+256,192,1100,1,0,0:0:0:0:";
+
+    // spinners are only stacked by the old algorithm
+    assert_eq!(stack_heights(hitobjects, 14), vec![0, 0]);
+    assert_eq!(stack_heights(hitobjects, 5), vec![1, 0]);
+}
+
+#[test]
+fn osu_file_stacking() {
+    let osu_file = "osu file format v14
+
+[General]
+StackLeniency: 0.2
+
+[Difficulty]
+ApproachRate:5
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+
+[HitObjects]
+100,100,0,1,0,0:0:0:0:
+100,100,250,1,0,0:0:0:0:
+100,100,300,1,0,0:0:0:0:"
+        .parse::<OsuFile>()
+        .unwrap();
+
+    assert_eq!(
+        osu_file
+            .stacking()
+            .iter()
+            .map(|object| object.stack_height)
+            .collect::<Vec<_>>(),
+        vec![0, 1, 0]
+    );
+}
@@ -0,0 +1,82 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::{
+    hitobjects::{HitObject, SliderSpan},
+    TimingPoints, VersionedFromStr,
+};
+use crate::tests::fixtures::{difficulty_sliders, timing_points_sv_change};
+
+fn hitobject(s: &str) -> HitObject {
+    HitObject::from_str(s, 14).unwrap().unwrap()
+}
+
+#[test]
+fn timing_point_state() {
+    let timing_points = timing_points_sv_change();
+
+    assert_eq!(timing_points.beat_length_at(-100.0), Some(500.0));
+    assert_eq!(timing_points.beat_length_at(1500.0), Some(500.0));
+    assert_eq!(timing_points.beat_length_at(2000.0), Some(250.0));
+    assert_eq!(timing_points.slider_velocity_at(500.0), 1.0);
+    assert_eq!(timing_points.slider_velocity_at(1000.0), 2.0);
+    // uninherited timing points reset the slider velocity
+    assert_eq!(timing_points.slider_velocity_at(2500.0), 1.0);
+}
+
+#[test]
+fn slider_spans() {
+    let slider = hitobject("0,0,0,2,0,L|200:0,2,200");
+
+    assert_eq!(
+        slider.slider_spans(&timing_points_sv_change(), &difficulty_sliders()),
+        Some(vec![
+            SliderSpan {
+                start_time: 0.0,
+                end_time: 500.0,
+                reversed: false,
+            },
+            SliderSpan {
+                start_time: 500.0,
+                end_time: 1000.0,
+                reversed: true,
+            },
+        ])
+    );
+    assert_eq!(
+        slider.end_time(&timing_points_sv_change(), &difficulty_sliders()),
+        Some(1000.0)
+    );
+}
+
+#[test]
+fn slider_velocity_multiplier() {
+    let slider = hitobject("0,0,1000,2,0,L|200:0,1,200");
+
+    assert_eq!(
+        slider.end_time(&timing_points_sv_change(), &difficulty_sliders()),
+        Some(1250.0)
+    );
+}
+
+#[test]
+fn end_times() {
+    let timing_points = timing_points_sv_change();
+    let difficulty = difficulty_sliders();
+
+    assert_eq!(
+        hitobject("256,192,100,1,0").end_time(&timing_points, &difficulty),
+        Some(100.0)
+    );
+    assert_eq!(
+        hitobject("256,192,100,12,0,2000").end_time(&timing_points, &difficulty),
+        Some(2000.0)
+    );
+    assert_eq!(
+        hitobject("51,192,100,128,2,849:0:0:0:0:").end_time(&timing_points, &difficulty),
+        Some(849.0)
+    );
+    assert_eq!(
+        hitobject("0,0,0,2,0,L|200:0,1,140").end_time(&TimingPoints::default(), &difficulty),
+        None
+    );
+}
@@ -0,0 +1,197 @@
+use std::f64::consts::FRAC_PI_2;
+
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::{
+    hitobjects::{CurveType, HitObject, HitObjectParams, Transform, TransformOptions, Vec2},
+    HitObjects, VersionedFromStr, VersionedToString,
+};
+
+fn transformed(s: &str, transform: Transform, options: TransformOptions) -> HitObject {
+    let mut hitobject = HitObject::from_str(s, 14).unwrap().unwrap();
+    hitobject.transform(&transform, &options);
+    hitobject
+}
+
+fn transformed_str(s: &str, transform: Transform, options: TransformOptions) -> String {
+    transformed(s, transform, options).to_string(14).unwrap()
+}
+
+fn curve_type(hitobject: &HitObject) -> CurveType {
+    match &hitobject.obj_params {
+        HitObjectParams::Slider(params) => params.curve_type,
+        _ => panic!("expected a slider"),
+    }
+}
+
+#[test]
+fn transform_composition() {
+    let point = Vec2::new(356.0, 192.0);
+    let centre = Vec2::new(256.0, 192.0);
+
+    assert_eq!(Transform::IDENTITY.apply(point), point);
+    assert_eq!(
+        Transform::translate(Vec2::new(10.0, -20.0)).apply(point),
+        Vec2::new(366.0, 172.0)
+    );
+    assert_eq!(
+        Transform::mirror_horizontally()
+            .then(Transform::mirror_vertically())
+            .apply(point),
+        Vec2::new(156.0, 192.0)
+    );
+    assert_eq!(
+        Transform::scale(2.0, 0.5).around(centre).apply(point),
+        Vec2::new(456.0, 192.0)
+    );
+
+    let rotated = Transform::rotate(FRAC_PI_2).around(centre).apply(point);
+    assert!(rotated.distance(Vec2::new(256.0, 292.0)) < 1e-9);
+}
+
+#[test]
+fn mirror() {
+    let options = TransformOptions::default();
+
+    assert_eq!(
+        transformed_str(
+            "100,100,1000,1,0,0:0:0:0:",
+            Transform::mirror_horizontally(),
+            options
+        ),
+        "412,100,1000,1,0,0:0:0:0:"
+    );
+    assert_eq!(
+        transformed_str(
+            "100,100,1000,2,0,B|200:150|300:100,1,250",
+            Transform::mirror_vertically(),
+            options
+        ),
+        "100,284,1000,2,0,B|200:234|300:284,1,250"
+    );
+}
+
+#[test]
+fn rotate() {
+    let hitobject = transformed(
+        "356,192,1000,2,0,L|456:192,1,50",
+        Transform::rotate(FRAC_PI_2).around(Vec2::new(256.0, 192.0)),
+        TransformOptions::default(),
+    );
+
+    // the length stays the same
+    assert_eq!(
+        hitobject.to_string(14).unwrap(),
+        "256,292,1000,2,0,L|256:392,1,50"
+    );
+}
+
+#[test]
+fn scale() {
+    // the length is scaled with the slider
+    assert_eq!(
+        transformed_str(
+            "100,100,1000,2,0,L|200:100,1,100",
+            Transform::scale(1.5, 1.5),
+            TransformOptions::default()
+        ),
+        "150,150,1000,2,0,L|300:150,1,150"
+    );
+    // the length of a stretched slider is measured from its path
+    assert_eq!(
+        transformed_str(
+            "100,100,1000,2,0,L|200:100,1,100",
+            Transform::scale(2.0, 1.0),
+            TransformOptions::default()
+        ),
+        "200,100,1000,2,0,L|400:100,1,200"
+    );
+}
+
+#[test]
+fn clamp() {
+    let transform = Transform::translate(Vec2::new(200.0, -150.0));
+    let s = "400,100,1000,2,0,L|450:100,1,50";
+
+    assert_eq!(
+        transformed_str(s, transform, TransformOptions::default()),
+        "600,-50,1000,2,0,L|650:-50,1,50"
+    );
+    assert_eq!(
+        transformed_str(
+            s,
+            transform,
+            TransformOptions {
+                clamp: true,
+                ..Default::default()
+            }
+        ),
+        "512,0,1000,2,0,L|512:0,1,50"
+    );
+}
+
+#[test]
+fn keep_perfect_circles() {
+    let s = "100,200,1000,2,0,P|150:150|200:200,1,157.0796";
+    let stretch = Transform::scale(2.0, 1.0);
+    let keep = TransformOptions {
+        keep_perfect_circles: true,
+        ..Default::default()
+    };
+
+    // a stretched circle isn't a circle anymore
+    assert_eq!(
+        curve_type(&transformed(s, stretch, TransformOptions::default())),
+        CurveType::PerfectCircle
+    );
+
+    let hitobject = transformed(s, stretch, keep);
+    let path = hitobject.slider_path().unwrap();
+
+    assert_eq!(curve_type(&hitobject), CurveType::Bezier);
+    // the circle around 150,200 with a radius of 50 is stretched to an ellipse
+    for point in path.points() {
+        let ellipse = ((point.x - 300.0) / 100.0).powi(2) + ((point.y - 200.0) / 50.0).powi(2);
+
+        assert!(
+            (ellipse - 1.0).abs() < 0.05,
+            "{point:?} isn't on the ellipse"
+        );
+    }
+    assert!(path.position_at(1.0).distance(Vec2::new(400.0, 200.0)) < 1.5);
+
+    // rotating keeps the circle
+    assert_eq!(
+        curve_type(&transformed(
+            s,
+            Transform::rotate(1.0).around(Vec2::new(256.0, 192.0)),
+            keep
+        )),
+        CurveType::PerfectCircle
+    );
+    // a circle that's too small is on a line once rounded
+    let hitobject = transformed(
+        "100,100,1000,2,0,P|101:99|102:100,1,3",
+        Transform::scale(0.5, 0.5),
+        keep,
+    );
+    assert_eq!(curve_type(&hitobject), CurveType::Bezier);
+}
+
+#[test]
+fn transform_hitobjects() {
+    let mut hitobjects =
+        HitObjects::from_str("100,100,1000,1,0,0:0:0:0:\n200,300,2000,1,0,0:0:0:0:", 14)
+            .unwrap()
+            .unwrap();
+
+    hitobjects.transform(
+        &Transform::mirror_horizontally(),
+        &TransformOptions::default(),
+    );
+
+    assert_eq!(
+        hitobjects.to_string(14).unwrap(),
+        "412,100,1000,1,0,0:0:0:0:\n312,300,2000,1,0,0:0:0:0:"
+    );
+}
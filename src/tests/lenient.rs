@@ -0,0 +1,148 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::*;
+
+fn lenient() -> ParseOptions {
+    ParseOptions {
+        lenient: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn strict_returns_first_error() {
+    let i = "osu file format v14\n\n[HitObjects]\n256,192,0,1,0\nfoo\nbar";
+    let err = OsuFile::from_str_with_options(i, &ParseOptions::default()).unwrap_err();
+
+    assert_eq!(err.line_index(), 4);
+}
+
+#[test]
+fn valid_file() {
+    let i = include_str!("./osu_files/files/acid_rain.osu");
+    let (osu_file, errors) = OsuFile::from_str_with_options(i, &lenient()).unwrap();
+
+    assert!(errors.is_empty());
+    assert!(osu_file.unparsed_lines.is_empty());
+    assert_eq!(osu_file, i.parse::<OsuFile>().unwrap());
+}
+
+#[test]
+fn invalid_version() {
+    let i = "osu file format v100\n\n[General]\nAudioFilename: audio.mp3";
+    let err = OsuFile::from_str_with_options(i, &lenient()).unwrap_err();
+
+    assert!(matches!(err.error(), ParseError::InvalidFileVersion));
+}
+
+#[test]
+fn collects_errors() {
+    let i = "osu file format v14
+foo
+
+[General]
+AudioFilename: audio.mp3
+AudioLeadIn: foo
+Countdown: 0
+foobar
+Mode: 0
+
+[Metadata]
+Title:foo
+Title:bar
+
+[Events]
+0,0,\"bg.jpg\",0,0
+Sprite,Foo,Centre,\"sb/sprite.png\",320,240
+ F,0,500,1000,0,1
+foobar
+Sprite,Foreground,Centre,\"sb/sprite.png\",320,240
+ F,0,500,1000,0,1
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+0,500,foo,2,0,100,1,0
+
+[Unknown]
+foo: bar
+
+[HitObjects]
+256,192,0,1,0,0:0:0:0:
+256,192,500,foo,0,0:0:0:0:
+256,192,1000,1,0,0:0:0:0:
+
+[General]
+AudioFilename: audio2.mp3";
+
+    let (osu_file, errors) = OsuFile::from_str_with_options(i, &lenient()).unwrap();
+
+    let error_lines = errors
+        .iter()
+        .map(|err| err.line_index())
+        .collect::<Vec<_>>();
+    assert_eq!(error_lines, vec![1, 5, 7, 12, 16, 17, 18, 24, 31, 34]);
+    assert!(matches!(errors[0].error(), ParseError::UnexpectedLine));
+    assert!(matches!(
+        errors[3].error(),
+        ParseError::ParseMetadataError {
+            source: metadata::ParseError::DuplicateField
+        }
+    ));
+    assert!(matches!(
+        errors[5].error(),
+        ParseError::ParseEventsError {
+            source: events::ParseError::StoryboardCmdWithNoSprite
+        }
+    ));
+    assert!(matches!(errors[9].error(), ParseError::DuplicateSections));
+
+    let unparsed_lines = osu_file
+        .unparsed_lines
+        .iter()
+        .map(|line| (line.section.as_deref(), line.line_index, line.line.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        unparsed_lines,
+        vec![
+            (None, 1, "foo"),
+            (Some("General"), 5, "AudioLeadIn: foo"),
+            (Some("General"), 7, "foobar"),
+            (Some("Metadata"), 12, "Title:bar"),
+            (
+                Some("Events"),
+                16,
+                "Sprite,Foo,Centre,\"sb/sprite.png\",320,240"
+            ),
+            (Some("Events"), 17, " F,0,500,1000,0,1"),
+            (Some("Events"), 18, "foobar"),
+            (Some("TimingPoints"), 24, "0,500,foo,2,0,100,1,0"),
+            (Some("HitObjects"), 31, "256,192,500,foo,0,0:0:0:0:"),
+            (Some("General"), 34, "[General]"),
+            (Some("General"), 35, "AudioFilename: audio2.mp3"),
+        ]
+    );
+
+    assert_eq!(
+        osu_file.unknown_sections,
+        vec![UnknownSection {
+            name: "Unknown".to_string(),
+            lines: vec!["foo: bar".to_string()],
+            after: Some("TimingPoints".to_string()),
+        }]
+    );
+
+    let general = osu_file.general.unwrap();
+    assert_eq!(
+        general.audio_filename,
+        Some(std::path::PathBuf::from("audio.mp3").into())
+    );
+    assert_eq!(general.audio_lead_in, None);
+    assert_eq!(general.mode, Some(general::Mode::Osu));
+    assert_eq!(
+        osu_file.metadata.unwrap().title,
+        Some("foo".to_string().into())
+    );
+    assert_eq!(osu_file.events.unwrap().0.len(), 2);
+    assert_eq!(osu_file.timing_points.unwrap().0.len(), 1);
+    assert_eq!(osu_file.hitobjects.unwrap().0.len(), 2);
+}
@@ -0,0 +1,168 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::*;
+
+fn lossless() -> ParseOptions {
+    ParseOptions {
+        lossless: true,
+        ..Default::default()
+    }
+}
+
+fn parse_lossless(i: &str) -> OsuFile {
+    OsuFile::from_str_with_options(i, &lossless()).unwrap().0
+}
+
+#[test]
+fn round_trip_files() {
+    let files = std::fs::read_dir("./src/tests/osu_files/files").unwrap();
+
+    for file in files {
+        let path = file.unwrap().path();
+
+        if path.extension().unwrap() != "osu" {
+            continue;
+        }
+
+        let i = std::fs::read_to_string(&path).unwrap();
+        // some files are for testing errors
+        let osu_file = match OsuFile::from_str_with_options(&i, &lossless()) {
+            Ok((osu_file, _)) => osu_file,
+            Err(_) => continue,
+        };
+
+        assert_eq!(i, osu_file.to_string(), "{}", path.display());
+    }
+}
+
+#[test]
+fn comments_and_spacing() {
+    let i = "\u{feff}osu file format v14\r
+// comment before sections\r
+\r
+[General]\r
+AudioFilename:audio.mp3\r
+// comment: with a colon\r
+AudioLeadIn:  0\r
+Unknown:foo\r
+\r
+\r
+[Unknown]\r
+// kept\r
+foo\r
+\r
+[HitObjects]\r
+// first object\r
+256,192,0,1,0,0:0:0:0:\r
+\r
+256,192,500,1,0,0:0:0:0:";
+    let osu_file = parse_lossless(i);
+
+    assert_eq!(i, osu_file.to_string());
+}
+
+#[test]
+fn edited() {
+    let i = "osu file format v14
+
+[General]
+AudioFilename:audio.mp3
+// lead in
+AudioLeadIn:  0
+
+[Difficulty]
+HPDrainRate:5
+
+[HitObjects]
+// objects
+256,192,0,1,0,0:0:0:0:
+256,192,500,1,0,0:0:0:0:
+  
+256,192,1000,1,0,0:0:0:0:
+";
+    let mut osu_file = parse_lossless(i);
+
+    let general = osu_file.general.as_mut().unwrap();
+    general.audio_lead_in = Some(100.into());
+    general.preview_time = Some(500.into());
+
+    osu_file.difficulty = None;
+
+    let hitobjects = osu_file.hitobjects.as_mut().unwrap();
+    let removed = hitobjects.0.remove(1);
+    hitobjects.0.insert(0, removed);
+
+    osu_file.colours = Some(colours::Colours(vec![colours::Colour::SliderBorder(
+        colours::Rgb {
+            red: 255,
+            green: 0,
+            blue: 0,
+        },
+    )]));
+
+    assert_eq!(
+        osu_file.to_string(),
+        "osu file format v14
+
+[General]
+AudioFilename:audio.mp3
+// lead in
+AudioLeadIn: 100
+PreviewTime: 500
+
+[HitObjects]
+// objects
+256,192,500,1,0,0:0:0:0:
+  
+256,192,0,1,0,0:0:0:0:
+256,192,1000,1,0,0:0:0:0:
+
+[Colours]
+SliderBorder : 255,0,0
+"
+    );
+
+    // other versions are written in the normal format
+    let mut lossless_output = Vec::new();
+    osu_file.write_to(&mut lossless_output, 13).unwrap();
+
+    osu_file.trivia = None;
+    let mut output = Vec::new();
+    osu_file.write_to(&mut output, 13).unwrap();
+
+    assert_eq!(
+        String::from_utf8(lossless_output).unwrap(),
+        String::from_utf8(output).unwrap()
+    );
+}
+
+#[test]
+fn comments_need_lenient_or_lossless() {
+    let i = "osu file format v14
+
+[General]
+// comment
+AudioFilename:audio.mp3
+
+[HitObjects]
+// comment
+256,192,0,1,0,0:0:0:0:";
+
+    // comments are parsed like any other line otherwise
+    let err = i.parse::<OsuFile>().unwrap_err();
+    assert_eq!(err.line_index(), 3);
+    assert!(HitObjects::from_str("// comment", 14).is_err());
+
+    let lenient = ParseOptions {
+        lenient: true,
+        ..Default::default()
+    };
+    let (osu_file, errors) = OsuFile::from_str_with_options(i, &lenient).unwrap();
+    assert!(errors.is_empty());
+    assert!(osu_file.unparsed_lines.is_empty());
+    assert_eq!(osu_file.hitobjects.unwrap().0.len(), 1);
+
+    let (osu_file, errors) = OsuFile::from_str_with_options(i, &lossless()).unwrap();
+    assert!(errors.is_empty());
+    assert_eq!(i, osu_file.to_string());
+}
@@ -1,8 +1,24 @@
+mod beat_snap;
+mod borrowed;
+mod convert;
+mod difficulty_calc;
+mod difficulty_values;
 mod error_line_index;
+mod error_span;
+mod fixtures;
 mod hitobjects;
+mod lenient;
+mod lossless;
+mod mods;
+mod offset;
 mod osu_files;
 mod parsers;
+mod performance;
+mod rate;
+#[cfg(feature = "serde")]
+mod serde;
 mod storyboard;
+mod timing_state;
 
 use crate::osu_file::types::Decimal;
 use pretty_assertions::assert_eq;
@@ -19,7 +35,7 @@ use crate::osu_file::{
     metadata::Metadata,
     timingpoints,
     timingpoints::{Effects, SampleIndex, TimingPoint, TimingPoints, Volume},
-    types::Position,
+    types::{Position, UnknownField},
     VersionedFromStr, VersionedToString,
 };
 
@@ -72,6 +88,38 @@ SamplesMatchPlaybackRate: 1";
     assert_eq!(i_str, i.to_string(14).unwrap());
 }
 
+#[test]
+fn general_unknown_fields() {
+    let i_str = "Foo: foo
+AudioFilename: test.mp3
+Bar: bar
+Baz: baz
+Mode: 1";
+    let i = General::from_str(i_str, 14).unwrap().unwrap();
+
+    assert_eq!(
+        i.unknown_fields,
+        vec![
+            UnknownField {
+                key: "Foo".to_string(),
+                value: "foo".to_string(),
+                after: None,
+            },
+            UnknownField {
+                key: "Bar".to_string(),
+                value: "bar".to_string(),
+                after: Some("AudioFilename".to_string()),
+            },
+            UnknownField {
+                key: "Baz".to_string(),
+                value: "baz".to_string(),
+                after: Some("Bar".to_string()),
+            },
+        ]
+    );
+    assert_eq!(i_str, i.to_string(14).unwrap());
+}
+
 #[test]
 fn editor_parse_v14() {
     let i_str = "Bookmarks: 11018,21683,32349,37683,48349,59016,69683,80349,91016
@@ -93,6 +141,7 @@ TimelineZoom: 2";
         grid_size: Some(8.into()),
         timeline_zoom: Some(Decimal::from(dec!(2)).into()),
         current_time: None,
+        unknown_fields: Vec::new(),
     };
 
     assert_eq!(i, e);
@@ -136,6 +185,7 @@ BeatmapSetID:1499093";
         ),
         beatmap_id: Some(3072232.into()),
         beatmap_set_id: Some(1499093.into()),
+        unknown_fields: Vec::new(),
     };
 
     assert_eq!(i, m);
@@ -159,6 +209,7 @@ SliderTickRate:1";
         approach_rate: Some(Decimal::from(dec!(5)).into()),
         slider_multiplier: Some(Decimal::from(dec!(1.4)).into()),
         slider_tickrate: Some(Decimal::from(rust_decimal::Decimal::ONE).into()),
+        unknown_fields: Vec::new(),
     };
 
     assert_eq!(i, d);
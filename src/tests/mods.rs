@@ -0,0 +1,231 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::{
+    difficulty::DifficultyValues, general::Mode, Decimal, Difficulty, Mods, OsuFile,
+    VersionedToString,
+};
+
+#[test]
+fn bits() {
+    let mods = Mods {
+        hidden: true,
+        hard_rock: true,
+        double_time: true,
+        ..Default::default()
+    };
+
+    assert_eq!(mods.bits(), 8 | 16 | 64);
+    assert_eq!(Mods::from_bits(8 | 16 | 64), mods);
+    // autoplay isn't a mod that's kept
+    assert_eq!(Mods::from_bits(2048), Mods::default());
+    assert_eq!(Mods::from_bits(u32::MAX).bits(), 0b111_0111_1111_1111);
+}
+
+#[test]
+fn clock_rate() {
+    let mods = |bits| Mods::from_bits(bits).clock_rate();
+
+    assert_eq!(mods(0), 1.0);
+    assert_eq!(mods(64), 1.5);
+    assert_eq!(mods(64 | 512), 1.5);
+    assert_eq!(mods(256), 0.75);
+}
+
+#[test]
+fn hard_rock_and_easy() {
+    let difficulty = Difficulty::from_str(
+        "HPDrainRate:5\nCircleSize:4\nOverallDifficulty:8\nApproachRate:9\nSliderMultiplier:1.4",
+        14,
+    )
+    .unwrap()
+    .unwrap();
+
+    let hard_rock = Mods {
+        hard_rock: true,
+        ..Default::default()
+    }
+    .difficulty(&difficulty, Mode::Osu);
+    let easy = Mods {
+        easy: true,
+        ..Default::default()
+    }
+    .difficulty(&difficulty, Mode::Osu);
+
+    assert_eq!(
+        hard_rock.values(),
+        DifficultyValues {
+            hp_drain_rate: 7.0,
+            circle_size: 5.2,
+            overall_difficulty: 10.0,
+            approach_rate: 10.0,
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        Decimal::from(hard_rock.circle_size.unwrap()).to_string(),
+        "5.2"
+    );
+    assert_eq!(
+        easy.values(),
+        DifficultyValues {
+            hp_drain_rate: 2.5,
+            circle_size: 2.0,
+            overall_difficulty: 4.0,
+            approach_rate: 4.5,
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        Mods::default().difficulty(&difficulty, Mode::Osu),
+        difficulty
+    );
+}
+
+#[test]
+fn approach_rate_fallback() {
+    let difficulty = Difficulty::from_str("OverallDifficulty:6", 7)
+        .unwrap()
+        .unwrap();
+    let hard_rock = Mods {
+        hard_rock: true,
+        ..Default::default()
+    }
+    .difficulty(&difficulty, Mode::Osu);
+
+    assert_eq!(hard_rock.values().approach_rate, 8.4);
+    assert_eq!(hard_rock.values().circle_size, 6.5);
+}
+
+fn osu_file(mode: u8) -> OsuFile {
+    format!(
+        "osu file format v14
+
+[General]
+Mode: {mode}
+
+[Difficulty]
+HPDrainRate:5
+CircleSize:4
+OverallDifficulty:8
+ApproachRate:9
+SliderMultiplier:1.4
+SliderTickRate:1
+
+[TimingPoints]
+0,300,4,2,0,100,1,0
+
+[HitObjects]
+100,100,1000,1,0,0:0:0:0:
+100,84.5,1300,2,0,B|200:100|300:284.5,1,140
+"
+    )
+    .parse()
+    .unwrap()
+}
+
+#[test]
+fn hard_rock_flip() {
+    let hard_rock = Mods {
+        hard_rock: true,
+        ..Default::default()
+    };
+    let (osu_file, difficulty) = osu_file(0).with_mods(&hard_rock);
+    let hitobjects = osu_file.hitobjects.unwrap().to_string(14).unwrap();
+
+    assert_eq!(
+        hitobjects,
+        "100,284,1000,1,0,0:0:0:0:
+100,299.5,1300,2,0,B|200:284|300:99.5,1,140"
+    );
+    assert_eq!(osu_file.difficulty, Some(difficulty.clone()));
+    assert_eq!(difficulty.values().circle_size, 5.2);
+}
+
+#[test]
+fn hard_rock_other_modes() {
+    let hard_rock = Mods {
+        hard_rock: true,
+        ..Default::default()
+    };
+
+    for mode in 1..=3 {
+        let original = osu_file(mode);
+        let (osu_file, difficulty) = original.with_mods(&hard_rock);
+
+        assert_eq!(osu_file.hitobjects, original.hitobjects);
+        // the circle size is the key count in osu!mania and isn't used in osu!taiko
+        let circle_size = if mode == 2 { 5.2 } else { 4.0 };
+        assert_eq!(difficulty.values().circle_size, circle_size);
+        assert_eq!(
+            osu_file.difficulty.unwrap().values().circle_size,
+            circle_size
+        );
+    }
+}
+
+#[test]
+fn easy_mania() {
+    let easy = Mods {
+        easy: true,
+        ..Default::default()
+    };
+    let (osu_file, difficulty) = osu_file(3).with_mods(&easy);
+
+    assert_eq!(difficulty.values().circle_size, 4.0);
+    assert_eq!(difficulty.values().hp_drain_rate, 2.5);
+    assert_eq!(difficulty.values().overall_difficulty, 4.0);
+    assert_eq!(osu_file.difficulty, Some(difficulty));
+}
+
+#[test]
+fn easy() {
+    let easy = Mods {
+        easy: true,
+        ..Default::default()
+    };
+    let original = osu_file(0);
+    let (osu_file, difficulty) = original.with_mods(&easy);
+
+    assert_eq!(osu_file.hitobjects, original.hitobjects);
+    assert_eq!(difficulty.values().approach_rate, 4.5);
+    assert_eq!(difficulty.values().overall_difficulty, 4.0);
+}
+
+#[test]
+fn double_time() {
+    let double_time = Mods {
+        double_time: true,
+        ..Default::default()
+    };
+    let original = osu_file(0);
+    let (osu_file, difficulty) = original.with_mods(&double_time);
+    let values = difficulty.values();
+
+    // the beatmap isn't changed, only how it plays
+    assert_eq!(osu_file, original);
+    assert!((values.approach_rate - 31.0 / 3.0).abs() < 1e-9);
+    assert!((values.overall_difficulty - 88.0 / 9.0).abs() < 1e-9);
+    assert_eq!(values.circle_size, 4.0);
+
+    let half_time = Mods {
+        half_time: true,
+        hard_rock: true,
+        ..Default::default()
+    };
+    let values = original.with_mods(&half_time).1.values();
+
+    // approach rate 10 and overall difficulty 10 at three quarters of the speed
+    assert_eq!(values.approach_rate, 9.0);
+    assert!((values.overall_difficulty - 80.0 / 9.0).abs() < 1e-9);
+}
+
+#[test]
+fn effective_difficulty_mania() {
+    let double_time = Mods {
+        double_time: true,
+        ..Default::default()
+    };
+    let values = osu_file(3).with_mods(&double_time).1.values();
+
+    assert_eq!(values.overall_difficulty, 8.0);
+}
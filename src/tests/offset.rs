@@ -0,0 +1,111 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::*;
+
+#[test]
+fn shift_time() {
+    let i = "osu file format v14
+
+[General]
+AudioLeadIn: 300
+PreviewTime: 30000
+
+[Editor]
+Bookmarks: 1500,3000
+
+[Events]
+0,0,\"bg.jpg\",0,0
+1,500,\"video.mp4\"
+2,6000,9000
+Sprite,Foreground,Centre,\"sb.png\",320,240
+ F,0,1500,3000,0,1
+ L,4500,2
+  M,0,0,300,320,240,320,300
+ T,HitSound,0,3000
+  F,0,0,150,1,0
+Sample,3000,0,\"hit.wav\",70
+
+[TimingPoints]
+1500,500,4,2,1,60,1,0
+3000,-50,4,2,1,60,0,0
+
+[HitObjects]
+256,192,1500,1,0,0:0:0:0:
+256,192,3000,12,0,4500,0:0:0:0:";
+    let o = "osu file format v14
+
+[General]
+AudioLeadIn: 300
+PreviewTime: 29975
+
+[Editor]
+Bookmarks: 1475,2975
+
+[Events]
+0,-25,\"bg.jpg\",0,0
+1,475,\"video.mp4\"
+2,5975,8975
+Sprite,Foreground,Centre,\"sb.png\",320,240
+ F,0,1475,2975,0,1
+ L,4475,2
+  M,0,0,300,320,240,320,300
+ T,HitSound,-25,2975
+  F,0,0,150,1,0
+Sample,2975,0,\"hit.wav\",70
+
+[TimingPoints]
+1475,500,4,2,1,60,1,0
+2975,-50,4,2,1,60,0,0
+
+[HitObjects]
+256,192,1475,1,0,0:0:0:0:
+256,192,2975,12,0,4475,0:0:0:0:";
+    let mut osu_file = i.parse::<OsuFile>().unwrap();
+
+    osu_file.shift_time(-25);
+
+    assert_eq!(osu_file.to_string(), o);
+
+    // shifting back gives the same beatmap
+    osu_file.shift_time(25);
+
+    assert_eq!(osu_file, i.parse::<OsuFile>().unwrap());
+}
+
+#[test]
+fn shift_time_osb() {
+    let i = "[Events]
+Sprite,Foreground,Centre,\"sb.png\",320,240
+ F,0,1500,3000,0,1
+ L,4500,2
+  M,0,0,300,320,240,320,300
+Sample,1000,0,\"hit.wav\",70";
+    let o = "[Events]
+Sprite,Foreground,Centre,\"sb.png\",320,240
+ F,0,1600,3100,0,1
+ L,4600,2
+  M,0,0,300,320,240,320,300
+Sample,1100,0,\"hit.wav\",70";
+    let mut osb = Osb::from_str(i, 14).unwrap().unwrap();
+
+    osb.shift_time(100);
+
+    assert_eq!(osb.to_string(14).unwrap(), o);
+}
+
+#[test]
+fn shift_time_saturates() {
+    let i = "[Events]
+2,1000,2000
+Sprite,Foreground,Centre,\"sb.png\",320,240
+ F,0,-2147483000,1000,0,1";
+    let o = "[Events]
+2,2147483647,2147483647
+Sprite,Foreground,Centre,\"sb.png\",320,240
+ F,0,647,2147483647,0,1";
+    let mut osb = Osb::from_str(i, 14).unwrap().unwrap();
+
+    osb.shift_time(Integer::MAX);
+
+    assert_eq!(osb.to_string(14).unwrap(), o);
+}
@@ -1,4 +1,10 @@
-use crate::{assert_eq_osu_str, osu_file::OsuFile};
+use crate::{
+    assert_eq_osu_str,
+    osu_file::{
+        DecodeError, Osb, OsuFile, ParseOptions, ReadError, Section, VersionedFromStr,
+        VersionedToString,
+    },
+};
 
 #[test]
 fn v3() {
@@ -387,3 +393,288 @@ fn legacy_event_osb() {
 
     assert_eq_osu_str(osu.osb_to_string().unwrap(), osb);
 }
+
+#[test]
+fn from_path() {
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/tests/osu_files/files/v14.osu"
+    );
+    let i = include_str!("./files/v14.osu");
+    let o = OsuFile::from_path(path).unwrap();
+
+    assert_eq!(o, i.parse::<OsuFile>().unwrap());
+}
+
+#[test]
+fn from_path_missing() {
+    let err = OsuFile::from_path("./files/missing.osu").unwrap_err();
+
+    assert!(matches!(err, ReadError::Io(_)));
+}
+
+#[test]
+fn from_reader_utf8_bom() {
+    let i = include_bytes!("./files/combo_blue.osu");
+    let o = OsuFile::from_reader(&i[..]).unwrap();
+
+    assert_eq_osu_str(std::str::from_utf8(&i[3..]).unwrap(), o.to_string());
+}
+
+#[test]
+fn from_reader_utf16() {
+    let i = include_str!("./files/v14.osu");
+    let o = i.parse::<OsuFile>().unwrap();
+
+    let utf16_le_bom = [0xFF, 0xFE]
+        .into_iter()
+        .chain(i.encode_utf16().flat_map(u16::to_le_bytes))
+        .collect::<Vec<_>>();
+    let utf16_be_bom = [0xFE, 0xFF]
+        .into_iter()
+        .chain(i.encode_utf16().flat_map(u16::to_be_bytes))
+        .collect::<Vec<_>>();
+    let utf16_le = i
+        .encode_utf16()
+        .flat_map(u16::to_le_bytes)
+        .collect::<Vec<_>>();
+    let utf16_be = i
+        .encode_utf16()
+        .flat_map(u16::to_be_bytes)
+        .collect::<Vec<_>>();
+
+    for bytes in [utf16_le_bom, utf16_be_bom, utf16_le, utf16_be] {
+        assert_eq!(OsuFile::from_reader(bytes.as_slice()).unwrap(), o);
+    }
+}
+
+#[test]
+fn from_reader_utf16_odd_length() {
+    let err = OsuFile::from_reader(&[0xFF, 0xFE, b'o'][..]).unwrap_err();
+
+    assert!(matches!(
+        err,
+        ReadError::Decode(DecodeError::OddUtf16Length)
+    ));
+}
+
+#[test]
+fn from_reader_parse_error() {
+    let i = include_str!("./files/leading_ws_w_err.osu");
+    let err = OsuFile::from_reader(i.as_bytes()).unwrap_err();
+
+    assert_eq_osu_str(
+        err.to_string(),
+        "Line 7, Invalid colon set, expected format of `key: value`",
+    );
+}
+
+#[test]
+fn osb_from_path() {
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/tests/osu_files/files/osb.osb"
+    );
+    let i = include_str!("./files/osb.osb");
+    let mut osu = OsuFile::default(14);
+
+    osu.append_osb_from_path(path).unwrap();
+
+    assert_eq_osu_str(osu.osb_to_string().unwrap(), i);
+    assert_eq!(Osb::from_path(path, 14).unwrap(), osu.osb);
+}
+
+#[test]
+fn write_to() {
+    let files = [
+        include_str!("./files/v3.osu"),
+        include_str!("./files/v5.osu"),
+        include_str!("./files/v9_spaces.osu"),
+        include_str!("./files/v14.osu"),
+        include_str!("./files/acid_rain.osu"),
+        include_str!("./files/aspire27.osu"),
+    ];
+
+    for i in files {
+        let o = i.parse::<OsuFile>().unwrap();
+
+        for version in [3, 4, 5, 14] {
+            let mut buf = Vec::new();
+            o.write_to(&mut buf, version).unwrap();
+
+            let mut o = o.clone();
+            o.version = version;
+
+            assert_eq!(String::from_utf8(buf).unwrap(), o.to_string());
+        }
+    }
+}
+
+#[test]
+fn osb_write_to() {
+    let files = [
+        include_str!("./files/aspire_osb1.osb"),
+        include_str!("./files/variable.osb"),
+        include_str!("./files/variable2.osb"),
+        include_str!("./files/osb.osb"),
+        include_str!("./files/legacy_event.osb"),
+    ];
+
+    for i in files {
+        let mut osu = OsuFile::default(14);
+        osu.append_osb(i).unwrap();
+
+        let mut buf = Vec::new();
+        osu.osb_write_to(&mut buf).unwrap();
+
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            osu.osb_to_string().unwrap()
+        );
+    }
+
+    let mut buf = Vec::new();
+    OsuFile::default(14).osb_write_to(&mut buf).unwrap();
+    assert!(buf.is_empty());
+}
+
+#[test]
+fn write_to_io_error() {
+    struct FailingWriter;
+
+    impl std::io::Write for FailingWriter {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("failing writer"))
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let o = include_str!("./files/v14.osu").parse::<OsuFile>().unwrap();
+    let err = o.write_to(&mut FailingWriter, 14).unwrap_err();
+
+    assert_eq!(err.to_string(), "failing writer");
+}
+
+#[test]
+fn unknown_sections() {
+    let i = "osu file format v14
+
+[Foo]
+foo: bar
+// not a comment here
+
+[General]
+AudioFilename: audio.mp3
+
+[Bar]
+1,2,3
+
+[Metadata]
+Title:foo";
+    let osu_file = i.parse::<OsuFile>().unwrap();
+
+    assert_eq!(osu_file.unknown_sections.len(), 2);
+    assert_eq!(
+        osu_file.unknown_sections[1].after.as_deref(),
+        Some("General")
+    );
+    assert_eq!(i, osu_file.to_string());
+}
+
+#[test]
+fn selected_sections() {
+    let i = include_str!("./files/v14.osu");
+    let options =
+        ParseOptions::sections(&[Section::General, Section::Metadata, Section::Difficulty]);
+    let (osu_file, _) = OsuFile::from_str_with_options(i, &options).unwrap();
+    let full = i.parse::<OsuFile>().unwrap();
+
+    assert_eq!(osu_file.general, full.general);
+    assert_eq!(osu_file.metadata, full.metadata);
+    assert_eq!(osu_file.difficulty, full.difficulty);
+    assert!(osu_file.editor.is_none());
+    assert!(osu_file.events.is_none());
+    assert!(osu_file.timing_points.is_none());
+    assert!(osu_file.hitobjects.is_none());
+}
+
+#[test]
+fn selected_sections_lossless() {
+    let i = "osu file format v14
+
+[General]
+AudioFilename: audio.mp3
+
+[Foo]
+foo
+
+[TimingPoints]
+350,333.333333333333,4,2,1,60,1,0
+
+[HitObjects]
+not,a,hitobject";
+    let options = ParseOptions {
+        lossless: true,
+        ..ParseOptions::sections(&[Section::General])
+    };
+    let (mut osu_file, _) = OsuFile::from_str_with_options(i, &options).unwrap();
+
+    assert!(osu_file.unknown_sections.is_empty());
+    assert!(osu_file.hitobjects.is_none());
+    assert_eq!(i, osu_file.to_string());
+
+    osu_file.general.as_mut().unwrap().audio_filename =
+        Some(std::path::PathBuf::from("song.mp3").into());
+
+    assert_eq!(i.replace("audio.mp3", "song.mp3"), osu_file.to_string());
+}
+
+#[test]
+fn osb_unknown_sections() {
+    let i = "[Variables]
+$foo=bar
+
+[Foo]
+foo
+
+[Events]
+//Background and Video events";
+    let osb = <Osb as VersionedFromStr>::from_str(i, 14).unwrap().unwrap();
+
+    assert_eq!(osb.unknown_sections[0].name, "Foo");
+    assert_eq!(i, osb.to_string(14).unwrap());
+}
+
+#[test]
+fn write_no_larger_than_a_line() {
+    use std::fmt::Write;
+
+    /// Keeps the length of the largest write.
+    struct LargestWrite(usize);
+
+    impl Write for LargestWrite {
+        fn write_str(&mut self, s: &str) -> std::fmt::Result {
+            self.0 = self.0.max(s.len());
+            Ok(())
+        }
+    }
+
+    let files = [
+        include_str!("./files/v14.osu"),
+        include_str!("./files/acid_rain.osu"),
+        include_str!("./files/aspire27.osu"),
+    ];
+
+    for i in files {
+        let o = i.parse::<OsuFile>().unwrap();
+        let longest_line = o.to_string().lines().map(str::len).max().unwrap();
+
+        let mut writer = LargestWrite(0);
+        write!(writer, "{o}").unwrap();
+
+        assert!(writer.0 <= longest_line + 1);
+    }
+}
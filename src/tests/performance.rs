@@ -0,0 +1,330 @@
+use pretty_assertions::assert_eq;
+
+use super::difficulty_calc::{assert_close, beatmap, jumps, mania_notes, osu_file, taiko_notes};
+use crate::osu_file::{
+    performance::{HitCounts, PerformanceAttributes, Score, ScoreHits},
+    Mods, OsuFile,
+};
+
+fn mods(bits: u32) -> Score {
+    Score {
+        mods: Mods::from_bits(bits),
+        ..Default::default()
+    }
+}
+
+/// Performance points of a score with the `accuracy`, `misses` and `combo`.
+fn accuracy(osu_file: &OsuFile, accuracy: f64, misses: usize, combo: Option<usize>) -> f64 {
+    osu_file
+        .performance(&Score {
+            combo,
+            hits: ScoreHits::Accuracy(accuracy),
+            misses,
+            ..Default::default()
+        })
+        .pp()
+}
+
+#[test]
+fn osu() {
+    let osu_file = osu_file(0, &jumps(300, 150, 400));
+    let full_combo = osu_file.performance(&Score::default());
+    let PerformanceAttributes::Osu(attributes) = full_combo else {
+        panic!("expected osu!standard performance");
+    };
+
+    assert!(attributes.aim > attributes.speed);
+    assert!(attributes.accuracy > 0.0);
+    assert_eq!(attributes.flashlight, 0.0);
+    assert_eq!(attributes.effective_miss_count, 0.0);
+    assert_eq!(
+        Some(attributes.difficulty_attributes),
+        osu_file.osu_difficulty()
+    );
+
+    let misses = osu_file.performance(&Score {
+        combo: Some(200),
+        misses: 2,
+        ..Default::default()
+    });
+    let accuracy = osu_file.performance(&Score {
+        hits: ScoreHits::Accuracy(95.0),
+        ..Default::default()
+    });
+
+    assert!(misses.pp() < full_combo.pp());
+    assert!(accuracy.pp() < full_combo.pp());
+}
+
+#[test]
+fn osu_hit_counts() {
+    let osu_file = osu_file(0, &jumps(300, 150, 400));
+    let counts = |n300, n100, n50| {
+        osu_file
+            .performance(&Score {
+                hits: ScoreHits::Counts(HitCounts {
+                    n300,
+                    n100,
+                    n50,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .pp()
+    };
+
+    assert_eq!(
+        counts(400, 0, 0),
+        osu_file.performance(&Score::default()).pp()
+    );
+    // 95% accuracy
+    assert_eq!(
+        counts(370, 30, 0),
+        osu_file
+            .performance(&Score {
+                hits: ScoreHits::Accuracy(95.0),
+                ..Default::default()
+            })
+            .pp()
+    );
+    assert!(counts(370, 0, 30) < counts(370, 30, 0));
+}
+
+#[test]
+fn osu_mods() {
+    let osu_file = osu_file(0, &jumps(300, 150, 400));
+    let no_mods = osu_file.performance(&Score::default());
+    // hidden, hard rock, double time, flashlight
+    let hidden = osu_file.performance(&mods(8));
+    let hard_rock = osu_file.performance(&mods(16));
+    let double_time = osu_file.performance(&mods(64));
+    let flashlight = osu_file.performance(&mods(1024));
+
+    assert!(hidden.pp() > no_mods.pp());
+    assert!(hard_rock.star_rating() > no_mods.star_rating());
+    assert!(double_time.star_rating() > hard_rock.star_rating());
+    assert!(flashlight.star_rating() > no_mods.star_rating());
+    assert!(flashlight.pp() > no_mods.pp());
+
+    let PerformanceAttributes::Osu(relax) = osu_file.performance(&mods(128)) else {
+        panic!("expected osu!standard performance");
+    };
+    assert_eq!(relax.speed, 0.0);
+    assert_eq!(relax.accuracy, 0.0);
+}
+
+// like the star ratings, the performance points of the test beatmaps are the ones this crate gives, which aren't checked against osu-tools yet
+
+#[test]
+fn osu_performance() {
+    let v14_3 = beatmap("v14_3");
+    let PerformanceAttributes::Osu(full_combo) = v14_3.performance(&Score::default()) else {
+        panic!("expected osu!standard performance");
+    };
+
+    assert_close(full_combo.pp, 60.320386741481116);
+    assert_close(full_combo.aim, 20.47901685629305);
+    assert_close(full_combo.speed, 16.23080245119203);
+    assert_close(full_combo.accuracy, 21.716775368050463);
+
+    let PerformanceAttributes::Osu(misses) = v14_3.performance(&Score {
+        combo: Some(250),
+        hits: ScoreHits::Counts(HitCounts {
+            n300: 240,
+            n100: 12,
+            n50: 3,
+            ..Default::default()
+        }),
+        misses: 5,
+        ..Default::default()
+    }) else {
+        panic!("expected osu!standard performance");
+    };
+
+    assert_close(misses.pp, 13.724836884189497);
+    assert_close(misses.aim, 7.955104294885234);
+    assert_close(misses.speed, 4.558976843056386);
+    assert_close(misses.accuracy, 0.381378629667011);
+    assert_eq!(misses.effective_miss_count, 5.0);
+
+    assert_close(accuracy(&v14_3, 98.5, 0, None), 44.37411683866759);
+    assert_close(accuracy(&v14_3, 95.0, 3, Some(300)), 19.171234467377918);
+    assert_close(accuracy(&v14_3, 90.0, 10, Some(100)), 3.2664238680794098);
+
+    // hidden, hard rock, hidden and hard rock, double time
+    assert_close(v14_3.performance(&mods(8)).pp(), 68.16386158128338);
+    assert_close(v14_3.performance(&mods(16)).pp(), 116.5197606496214);
+    assert_close(v14_3.performance(&mods(24)).pp(), 125.8413415015911);
+    assert_close(v14_3.performance(&mods(64)).pp(), 164.71896259328994);
+
+    let aspire27 = beatmap("aspire27");
+
+    assert_close(
+        aspire27.performance(&Score::default()).pp(),
+        400.339123280032,
+    );
+    assert_close(accuracy(&aspire27, 98.5, 0, None), 369.3987948533828);
+    assert_close(accuracy(&aspire27, 95.0, 3, Some(300)), 107.87984172138027);
+    assert_close(aspire27.performance(&mods(64)).pp(), 1284.3640814963635);
+}
+
+#[test]
+fn other_mode_performance() {
+    let match_test = beatmap("match_test");
+
+    assert_close(
+        match_test.performance(&Score::default()).pp(),
+        145.19423536512434,
+    );
+    assert_close(match_test.performance(&mods(16)).pp(), 165.23586294731763);
+    assert_close(match_test.performance(&mods(64)).pp(), 272.2100342893675);
+    assert_close(accuracy(&match_test, 98.5, 0, None), 135.0029474398343);
+    assert_close(
+        accuracy(&match_test, 95.0, 3, Some(300)),
+        111.29631274563175,
+    );
+
+    let aspire1 = beatmap("aspire1");
+
+    assert_close(
+        aspire1.performance(&Score::default()).pp(),
+        1397.4709195750827,
+    );
+    assert_close(aspire1.performance(&mods(16)).pp(), 1749.8257529746631);
+    assert_close(aspire1.performance(&mods(64)).pp(), 2460.3590619364404);
+    assert_close(accuracy(&aspire1, 98.5, 0, None), 1286.0939599732944);
+    assert_close(accuracy(&aspire1, 95.0, 3, Some(300)), 100.0384243365704);
+
+    let v14 = beatmap("v14");
+
+    assert_close(v14.performance(&Score::default()).pp(), 181.34426885906387);
+    assert_close(v14.performance(&mods(2)).pp(), 90.67213442953194);
+    assert_close(v14.performance(&mods(64)).pp(), 383.4936209488015);
+    assert_close(accuracy(&v14, 98.5, 0, None), 167.4801708743176);
+    assert_close(accuracy(&v14, 95.0, 3, Some(300)), 134.4304019409833);
+}
+
+#[test]
+fn taiko() {
+    let osu_file = osu_file(1, &taiko_notes("ddkdkkdk", 83, 400));
+    let full_combo = osu_file.performance(&Score::default());
+    let PerformanceAttributes::Taiko(attributes) = full_combo else {
+        panic!("expected osu!taiko performance");
+    };
+
+    assert!(attributes.difficulty > 0.0);
+    assert!(attributes.accuracy > 0.0);
+    assert_eq!(
+        Some(attributes.difficulty_attributes),
+        osu_file.taiko_difficulty()
+    );
+
+    let oks = osu_file.performance(&Score {
+        hits: ScoreHits::Counts(HitCounts {
+            n300: 390,
+            n100: 10,
+            ..Default::default()
+        }),
+        ..Default::default()
+    });
+    // 98.75% accuracy
+    let accuracy = osu_file.performance(&Score {
+        hits: ScoreHits::Accuracy(98.75),
+        ..Default::default()
+    });
+
+    assert!(oks.pp() < full_combo.pp());
+    assert_eq!(oks, accuracy);
+    assert!(osu_file.performance(&mods(64)).pp() > full_combo.pp());
+}
+
+#[test]
+fn catch() {
+    let osu_file = osu_file(2, &jumps(300, 300, 400));
+    let full_combo = osu_file.performance(&Score::default());
+    let PerformanceAttributes::Catch(attributes) = full_combo else {
+        panic!("expected osu!catch performance");
+    };
+
+    assert!(attributes.pp > 0.0);
+    assert_eq!(
+        Some(attributes.difficulty_attributes),
+        osu_file.catch_difficulty()
+    );
+
+    let misses = osu_file.performance(&Score {
+        combo: Some(100),
+        misses: 1,
+        ..Default::default()
+    });
+
+    assert!(misses.pp() < full_combo.pp());
+    assert!(osu_file.performance(&mods(8)).pp() > full_combo.pp());
+    assert!(osu_file.performance(&mods(1)).pp() < full_combo.pp());
+}
+
+#[test]
+fn catch_hard_rock_offsets() {
+    // fruits close to each other are moved further apart with hard rock
+    let hitobjects = (0..100)
+        .map(|i| {
+            let x = 256 + i % 2 * 20;
+            format!("{x},192,{},1,0,0:0:0:0:", 1000 + i * 300)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let osu_file = osu_file(2, &hitobjects);
+
+    assert_eq!(osu_file.performance(&Score::default()).star_rating(), 0.0);
+    assert!(osu_file.performance(&mods(16)).star_rating() > 0.0);
+}
+
+#[test]
+fn mania() {
+    let osu_file = osu_file(3, &mania_notes(&[0, 1, 2, 3], 75, 800));
+    let full_combo = osu_file.performance(&Score::default());
+    let PerformanceAttributes::Mania(attributes) = full_combo else {
+        panic!("expected osu!mania performance");
+    };
+
+    assert!(attributes.pp > 0.0);
+    assert_eq!(
+        Some(attributes.difficulty_attributes),
+        osu_file.mania_difficulty()
+    );
+
+    let counts = |n_geki, n300| {
+        osu_file
+            .performance(&Score {
+                hits: ScoreHits::Counts(HitCounts {
+                    n_geki,
+                    n300,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .pp()
+    };
+
+    // 320s give more than 300s
+    assert_eq!(counts(800, 0), full_combo.pp());
+    assert!(counts(0, 800) < counts(800, 0));
+    // nothing is given below 80% accuracy
+    assert_eq!(
+        osu_file
+            .performance(&Score {
+                hits: ScoreHits::Accuracy(75.0),
+                ..Default::default()
+            })
+            .pp(),
+        0.0
+    );
+
+    let double_time = osu_file.performance(&mods(64));
+    let PerformanceAttributes::Mania(double_time) = double_time else {
+        panic!("expected osu!mania performance");
+    };
+    assert!(double_time.pp > attributes.pp);
+    // osu! scales the hit window with the speed twice
+    assert_eq!(double_time.difficulty_attributes.great_hit_window, 60.0);
+}
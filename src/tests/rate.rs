@@ -0,0 +1,184 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::*;
+
+#[test]
+fn scale_time() {
+    let i = "osu file format v14
+
+[General]
+AudioFilename: audio.mp3
+AudioLeadIn: 300
+PreviewTime: 30000
+Mode: 0
+
+[Editor]
+Bookmarks: 1500,3000
+
+[Metadata]
+Version:Insane
+
+[Events]
+0,0,\"bg.jpg\",0,0
+2,6000,9000
+Sprite,Foreground,Centre,\"sb.png\",320,240
+ F,0,1500,3000,0,1
+ L,4500,2
+  M,0,0,300,320,240,320,300
+Animation,Foreground,Centre,\"anim.png\",320,240,2,150,LoopForever
+ S,0,0,,1
+Sample,3000,0,\"hit.wav\",70
+
+[TimingPoints]
+1500,500,4,2,1,60,1,0
+3000,-50,4,2,1,60,0,0
+
+[HitObjects]
+256,192,1500,1,0,0:0:0:0:
+256,192,3000,12,0,4500,0:0:0:0:
+256,192,4501,1,0,0:0:0:0:";
+    let o = "osu file format v14
+
+[General]
+AudioFilename: audio 1.5x.mp3
+AudioLeadIn: 200
+PreviewTime: 20000
+Mode: 0
+
+[Editor]
+Bookmarks: 1000,2000
+
+[Metadata]
+Version:Insane 1.5x
+
+[Events]
+0,0,\"bg.jpg\",0,0
+2,4000,6000
+Sprite,Foreground,Centre,\"sb.png\",320,240
+ F,0,1000,2000,0,1
+ L,3000,2
+  M,0,0,200,320,240,320,300
+Animation,Foreground,Centre,\"anim.png\",320,240,2,100,LoopForever
+ S,0,0,,1
+Sample,2000,0,\"hit.wav\",70
+
+[TimingPoints]
+1000,333.3333333333333,4,2,1,60,1,0
+2000,-50,4,2,1,60,0,0
+
+[HitObjects]
+256,192,1000,1,0,0:0:0:0:
+256,192,2000,12,0,3000,0:0:0:0:
+256,192,3001,1,0,0:0:0:0:";
+    let mut osu_file = i.parse::<OsuFile>().unwrap();
+
+    let mut renamed = Vec::new();
+    osu_file
+        .scale_time(1.5, |field, name| {
+            renamed.push(field);
+            match field {
+                RenamedField::Version => format!("{name} 1.5x"),
+                RenamedField::AudioFilename => name.replace(".mp3", " 1.5x.mp3"),
+            }
+        })
+        .unwrap();
+
+    assert_eq!(
+        renamed,
+        vec![RenamedField::AudioFilename, RenamedField::Version]
+    );
+    assert_eq!(osu_file.to_string(), o);
+}
+
+#[test]
+fn scale_time_slower() {
+    let i = "osu file format v14
+
+[General]
+PreviewTime: -1
+
+[TimingPoints]
+1000,400,4,2,1,60,1,0
+
+[HitObjects]
+256,192,1000,1,0,0:0:0:0:
+0,192,1600,128,0,2200:0:0:0:0:";
+    let o = "osu file format v14
+
+[General]
+PreviewTime: -1
+
+[TimingPoints]
+1333,533.333333333333,4,2,1,60,1,0
+
+[HitObjects]
+256,192,1333,1,0,0:0:0:0:
+0,192,2133,128,0,2933:0:0:0:0:";
+    let mut osu_file = i.parse::<OsuFile>().unwrap();
+
+    // nothing to rename
+    osu_file.scale_time(0.75, |_, _| unreachable!()).unwrap();
+
+    assert_eq!(osu_file.to_string(), o);
+}
+
+#[test]
+fn scale_time_invalid_rate() {
+    let mut osu_file = "osu file format v14".parse::<OsuFile>().unwrap();
+
+    for rate in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+        assert!(matches!(
+            osu_file.scale_time(rate, |_, name| name.to_string()),
+            Err(ScaleTimeError::InvalidRate(_))
+        ));
+    }
+}
+
+#[test]
+fn scale_time_osb() {
+    let i = "[Events]
+Sprite,Foreground,Centre,\"sb.png\",320,240
+ F,0,1500,3000,0,1
+ T,HitSound,0,3000
+  F,0,0,150,1,0";
+    let o = "[Events]
+Sprite,Foreground,Centre,\"sb.png\",320,240
+ F,0,1000,2000,0,1
+ T,HitSound,0,2000
+  F,0,0,100,1,0";
+    let mut osb = Osb::from_str(i, 14).unwrap().unwrap();
+
+    osb.scale_time(1.5);
+
+    assert_eq!(osb.to_string(14).unwrap(), o);
+}
+
+#[test]
+fn scale_time_object_on_timing_point() {
+    let i = "osu file format v14
+
+[Difficulty]
+SliderMultiplier:1.4
+
+[TimingPoints]
+0,500,4,2,1,60,1,0
+10000,-50,4,2,1,60,0,0
+
+[HitObjects]
+0,0,10000,2,0,L|100:0,1,140";
+    let mut osu_file = i.parse::<OsuFile>().unwrap();
+
+    osu_file
+        .scale_time(1.3, |_, name| name.to_string())
+        .unwrap();
+
+    let timing_points = osu_file.timing_points.as_ref().unwrap();
+    let hitobject = &osu_file.hitobjects.as_ref().unwrap().0[0];
+
+    // the slider stays on the green line and uses its slider velocity
+    assert_eq!(timing_points.0[1].time(), &hitobject.time);
+    let end_time = hitobject
+        .end_time(timing_points, osu_file.difficulty.as_ref().unwrap())
+        .unwrap();
+    assert!((end_time - 7884.3).abs() < 0.1, "{end_time}");
+}
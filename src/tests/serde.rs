@@ -0,0 +1,126 @@
+use pretty_assertions::assert_eq;
+use rust_decimal_macros::dec;
+use serde_json::json;
+
+use crate::osu_file::events::audio_sample;
+use crate::osu_file::hitobjects::{self, ComboSkipCount, HitSound};
+use crate::osu_file::timingpoints::Effects;
+use crate::osu_file::*;
+
+#[test]
+fn osu_file_round_trip() {
+    let files = std::fs::read_dir("./src/tests/osu_files/files").unwrap();
+
+    for file in files {
+        let path = file.unwrap().path();
+
+        if path.extension().unwrap() != "osu" {
+            continue;
+        }
+
+        let i = std::fs::read_to_string(&path).unwrap();
+        // some files are for testing errors
+        let osu_file = match i.parse::<OsuFile>() {
+            Ok(osu_file) => osu_file,
+            Err(_) => continue,
+        };
+
+        let json = serde_json::to_string(&osu_file).unwrap();
+        let deserialized = serde_json::from_str::<OsuFile>(&json).unwrap();
+
+        assert_eq!(osu_file, deserialized, "{}", path.display());
+
+        let bytes = bincode::serialize(&osu_file).unwrap();
+        let deserialized = bincode::deserialize::<OsuFile>(&bytes).unwrap();
+
+        assert_eq!(osu_file, deserialized, "{}", path.display());
+    }
+}
+
+#[test]
+fn osb_round_trip() {
+    let i = include_str!("./osu_files/files/variable.osb");
+    let osb = <Osb as VersionedFromStr>::from_str(i, 14).unwrap().unwrap();
+
+    let json = serde_json::to_string(&osb).unwrap();
+
+    assert_eq!(osb, serde_json::from_str::<Osb>(&json).unwrap());
+}
+
+#[test]
+fn decimal() {
+    assert_eq!(
+        serde_json::to_value(Decimal::from(dec!(1.40))).unwrap(),
+        json!("1.40")
+    );
+    assert_eq!(
+        serde_json::to_value(Decimal::from("foo")).unwrap(),
+        json!("foo")
+    );
+
+    assert_eq!(
+        serde_json::from_value::<Decimal>(json!("1.40")).unwrap(),
+        Decimal::from(dec!(1.40))
+    );
+    assert_eq!(
+        serde_json::from_value::<Decimal>(json!("foo")).unwrap(),
+        Decimal::from("foo")
+    );
+    assert_eq!(
+        serde_json::from_value::<Decimal>(json!(5)).unwrap(),
+        Decimal::from(5)
+    );
+
+    for decimal in [Decimal::from(dec!(1.40)), Decimal::from("foo")] {
+        let bytes = bincode::serialize(&decimal).unwrap();
+
+        assert_eq!(bincode::deserialize::<Decimal>(&bytes).unwrap(), decimal);
+    }
+}
+
+#[test]
+fn checked_values() {
+    assert_eq!(
+        serde_json::from_value::<ComboSkipCount>(json!(7)).unwrap(),
+        ComboSkipCount::new(7, 14).unwrap().unwrap()
+    );
+    assert!(serde_json::from_value::<ComboSkipCount>(json!(8)).is_err());
+
+    assert_eq!(
+        serde_json::from_value::<hitobjects::Volume>(json!(null)).unwrap(),
+        hitobjects::Volume::new(None).unwrap()
+    );
+    assert!(serde_json::from_value::<hitobjects::Volume>(json!(0)).is_err());
+    assert!(serde_json::from_value::<hitobjects::Volume>(json!(101)).is_err());
+
+    assert_eq!(
+        serde_json::to_value(audio_sample::Volume::new(100, 14).unwrap()).unwrap(),
+        json!(100)
+    );
+    assert!(serde_json::from_value::<audio_sample::Volume>(json!(101)).is_err());
+}
+
+#[test]
+fn flags() {
+    let hitsound = HitSound::new(false, true, false, true);
+
+    assert_eq!(
+        serde_json::to_value(hitsound).unwrap(),
+        json!({ "normal": false, "whistle": true, "finish": false, "clap": true })
+    );
+
+    let effects = <Effects as VersionedFromStr>::from_str("11", 14)
+        .unwrap()
+        .unwrap();
+    let value = serde_json::to_value(effects).unwrap();
+
+    assert_eq!(
+        value,
+        json!({
+            "kiai_time_enabled": true,
+            "no_first_barline_in_taiko_mania": true,
+            "unused_bits": 2,
+        })
+    );
+    assert_eq!(serde_json::from_value::<Effects>(value).unwrap(), effects);
+}
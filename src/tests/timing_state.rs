@@ -0,0 +1,102 @@
+use pretty_assertions::assert_eq;
+
+use crate::osu_file::{
+    timingpoints::{SampleIndex, SampleSet, TimingPoints, TimingState, Volume},
+    VersionedFromStr,
+};
+use crate::tests::fixtures::timing_points_same_time;
+
+fn state(
+    beat_length: f64,
+    meter: i32,
+    slider_velocity: f64,
+    sample_set: SampleSet,
+    volume: i32,
+    kiai: bool,
+    (uninherited_index, uninherited_time): (usize, f64),
+) -> TimingState {
+    TimingState {
+        beat_length,
+        meter,
+        slider_velocity,
+        sample_set,
+        sample_index: SampleIndex::OsuDefaultHitsounds,
+        volume: Volume::new(volume, 14).unwrap(),
+        kiai,
+        uninherited_index,
+        uninherited_time,
+    }
+}
+
+#[test]
+fn state_at() {
+    let index = timing_points_same_time().index().unwrap();
+
+    assert_eq!(
+        index.state_at(-10.0),
+        state(500.0, 4, 1.0, SampleSet::Soft, 60, false, (0, 0.0))
+    );
+    assert_eq!(
+        index.state_at(999.0),
+        state(500.0, 4, 1.0, SampleSet::Soft, 60, false, (0, 0.0))
+    );
+    // the inherited timing point sets the slider velocity and sample at the same time
+    assert_eq!(
+        index.state_at(1000.0),
+        state(400.0, 3, 2.0, SampleSet::Normal, 70, true, (2, 1000.0))
+    );
+    // the first uninherited timing point at the same time is used
+    assert_eq!(
+        index.state_at(2500.0),
+        state(300.0, 4, 1.0, SampleSet::Soft, 90, false, (3, 2000.0))
+    );
+    assert_eq!(
+        index.state_at(3000.0),
+        state(300.0, 4, 0.5, SampleSet::Soft, 50, false, (3, 2000.0))
+    );
+    assert_eq!(index.state_at(0.0).bpm(), 120.0);
+    assert_eq!(index.states().len(), 4);
+}
+
+#[test]
+fn state_at_without_index() {
+    let timing_points = timing_points_same_time();
+
+    assert_eq!(
+        timing_points.state_at(1500.0),
+        Some(timing_points.index().unwrap().state_at(1500.0))
+    );
+    assert_eq!(TimingPoints::default().state_at(0.0), None);
+}
+
+#[test]
+fn state_at_matches_index() {
+    // out of order, with inherited timing points before the first uninherited one and invalid beat lengths
+    let timing_points = TimingPoints::from_str(
+        "2000,-25,4,1,0,40,0,1
+1000,-50,4,1,0,70,0,0
+1500,400,3,3,0,80,1,0
+500,-200,4,2,0,30,0,0
+1500,-80,4,2,0,35,0,1
+1500,300,4,2,0,90,1,0
+2500,-1,4,2,0,20,1,0
+2500,0,4,1,0,10,1,0
+3000,-100,4,2,0,50,0,0
+3000,-150,4,3,0,55,0,1
+3000,600,4,2,0,60,1,0",
+        14,
+    )
+    .unwrap()
+    .unwrap();
+    let index = timing_points.index().unwrap();
+
+    for time in [
+        -10.0, 500.0, 999.0, 1000.0, 1499.0, 1500.0, 2000.0, 2500.0, 2999.0, 3000.0, 4000.0,
+    ] {
+        assert_eq!(
+            timing_points.state_at(time),
+            Some(index.state_at(time)),
+            "{time}"
+        );
+    }
+}